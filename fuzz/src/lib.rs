@@ -0,0 +1,56 @@
+//! Shared body of the fuzz targets, one per day.
+//!
+//! Every target feeds the data to the day's parser and, when it parses, to the solvers.
+
+use aoc_2020::params::Params;
+use aoc_2020::registry;
+use aoc_2020::solution::DynSolution;
+
+/// Longer inputs are skipped, they only slow the fuzzing down.
+pub const MAX_INPUT_LEN: usize = 4096;
+
+/// Values of the number parameters, small enough for the solvers to finish in milliseconds on
+/// any input: day, parameter and value. The puzzle's values like the 30 million turns of day 15
+/// would be reported as timeouts.
+pub const CAPPED_PARAMS: [(u8, &str, &str); 9] = [
+    (1, "sum", "2020"),
+    (9, "preamble", "5"),
+    (15, "turns", "10000"),
+    (17, "cycles", "2"),
+    (23, "cups", "1000"),
+    (23, "moves", "10000"),
+    (24, "days", "10"),
+    (25, "subject", "7"),
+    (25, "modulus", "10007"),
+];
+
+/// The parameters the `solution` is fuzzed with.
+fn capped_params(solution: &dyn DynSolution) -> Params {
+    let mut params = Params::default();
+    for &(_, name, value) in CAPPED_PARAMS
+        .iter()
+        .filter(|capped| capped.0 == solution.day())
+    {
+        params
+            .set(solution.params(), name, value)
+            .expect("capped parameter is declared by the day");
+    }
+    params
+}
+
+/// Parses the `data` as the input of the `day` and solves all of its parts.
+///
+/// Panics are left to the fuzzer, the parsers should report every malformed input as an error.
+pub fn parse_and_solve(day: u8, data: &[u8]) {
+    let input = match std::str::from_utf8(data) {
+        Ok(input) if input.len() <= MAX_INPUT_LEN => input,
+        _ => return,
+    };
+    let solution = registry::find(day).expect("fuzzed day is registered");
+    if let Ok(parsed) = solution.parse(input) {
+        let params = capped_params(solution);
+        for part in 1..=solution.parts() {
+            solution.solve_with(parsed.as_ref(), part, &params);
+        }
+    }
+}
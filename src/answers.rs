@@ -0,0 +1,188 @@
+use crate::input::{self, Source};
+use aoc_2020::parser::Cursor;
+use aoc_2020::{registry, Answer, ParseError};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = "answers.txt";
+
+/// Answers recorded for the puzzle inputs, keyed by day and part.
+#[derive(PartialEq, Debug)]
+pub struct Answers(HashMap<(u8, u8), String>);
+
+#[derive(PartialEq, Debug)]
+pub enum Verdict {
+    Correct,
+    Mismatch { expected: String },
+    Unknown,
+}
+
+impl Verdict {
+    pub fn status(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "ok",
+            Verdict::Mismatch { .. } => "wrong",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+impl Answers {
+    /// Parses lines of `DAY:PART ANSWER`, blank lines and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> Result<Answers, ParseError> {
+        let mut answers = HashMap::new();
+        for mut line in Cursor::new(text).lines() {
+            let start = line;
+            if line.rest().trim_end().is_empty() || line.starts_with("#") {
+                continue;
+            }
+            let day_at = line;
+            let day = line.take_number()?;
+            let solution = match registry::find(day) {
+                Some(solution) => solution,
+                None => return Err(line.error_at(line.consumed_since(&day_at), "no such day")),
+            };
+            line.expect_literal(":")?;
+            let part_at = line;
+            let part = line.take_number()?;
+            if !(1..=solution.parts()).contains(&part) {
+                return Err(line.error_at(line.consumed_since(&part_at), "no such part"));
+            }
+            line.expect_literal(" ")?;
+            let answer = line.take_rest().trim();
+            if answers.insert((day, part), answer.to_string()).is_some() {
+                return Err(line.error_at(start.rest().trim_end(), "answer recorded twice"));
+            }
+        }
+        Ok(Answers(answers))
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.expected(day, part) {
+            Some(expected) if expected == answer.to_string() => Verdict::Correct,
+            Some(expected) => Verdict::Mismatch {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+pub fn embedded() -> &'static str {
+    include_str!("input/answers.txt")
+}
+
+pub fn path_in_dir(dir: &Path) -> PathBuf {
+    dir.join(FILE_NAME)
+}
+
+/// Loads answers from `answers.txt` in the lookup directory, so that they belong to the inputs
+/// found there. Without such a file only the days whose input is embedded keep an answer,
+/// those of the embedded inputs.
+pub fn load(dir: &Path) -> Result<Answers, String> {
+    let path = path_in_dir(dir);
+    if path.is_file() {
+        let text = input::read(&Source::File(path.clone()))?;
+        return Answers::parse(&text).map_err(|err| format!("{}: {}", path.display(), err));
+    }
+    let mut answers =
+        Answers::parse(embedded()).map_err(|err| format!("{}: {}", FILE_NAME, err))?;
+    answers
+        .0
+        .retain(|&(day, _), _| !input::path_in_dir(dir, day).is_file());
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_parse() {
+        let answers = Answers::parse("# comment\n1:1 42\n\n21:2 a,b\n25:1 7 \n").unwrap();
+        assert_eq!(Some("42"), answers.expected(1, 1));
+        assert_eq!(Some("a,b"), answers.expected(21, 2));
+        assert_eq!(Some("7"), answers.expected(25, 1));
+        assert_eq!(None, answers.expected(1, 2));
+    }
+
+    #[test]
+    fn test_answers_parse_errors() {
+        let err = Answers::parse("1:1 42\n1-2 43\n").unwrap_err();
+        assert_eq!((2, 2, "-2 43"), (err.line, err.column, err.text.as_str()));
+        let err = Answers::parse("1:1 42\n1:1 43\n").unwrap_err();
+        assert_eq!((2, 1, "1:1 43"), (err.line, err.column, err.text.as_str()));
+        assert!(Answers::parse("1:1\n").is_err());
+        let err = Answers::parse("26:1 42\n").unwrap_err();
+        assert_eq!(
+            (1, 1, "26", "no such day"),
+            (
+                err.line,
+                err.column,
+                err.text.as_str(),
+                err.message.as_str()
+            )
+        );
+        let err = Answers::parse("0:1 42\n").unwrap_err();
+        assert_eq!((1, 1, "0"), (err.line, err.column, err.text.as_str()));
+        let err = Answers::parse("1:3 42\n").unwrap_err();
+        assert_eq!(
+            (1, 3, "3", "no such part"),
+            (
+                err.line,
+                err.column,
+                err.text.as_str(),
+                err.message.as_str()
+            )
+        );
+        let err = Answers::parse("25:2 42\n").unwrap_err();
+        assert_eq!((1, 4, "2"), (err.line, err.column, err.text.as_str()));
+    }
+
+    #[test]
+    fn test_answers_check() {
+        let answers = Answers::parse("1:1 42\n1:2 -1\n").unwrap();
+        assert_eq!(Verdict::Correct, answers.check(1, 1, &Answer::UInt(42)));
+        assert_eq!(Verdict::Correct, answers.check(1, 2, &Answer::Int(-1)));
+        assert_eq!(
+            Verdict::Mismatch {
+                expected: "42".to_string()
+            },
+            answers.check(1, 1, &Answer::Int(41))
+        );
+        assert_eq!(Verdict::Unknown, answers.check(2, 1, &Answer::Int(41)));
+    }
+
+    #[test]
+    fn test_answers_embedded_cover_all_days() {
+        let answers = Answers::parse(embedded()).unwrap();
+        for solution in aoc_2020::registry::all() {
+            for part in 1..=solution.parts() {
+                assert!(answers.expected(solution.day(), part).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_answers_load_falls_back_to_embedded() {
+        let dir = Path::new("no-such-directory");
+        assert_eq!(Ok(Answers::parse(embedded()).unwrap()), load(dir));
+    }
+
+    #[test]
+    fn test_answers_load_skips_embedded_for_inputs_in_dir() {
+        let dir = std::env::temp_dir().join(format!("aoc_2020_answers_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(input::path_in_dir(&dir, 3), "..#\n").unwrap();
+        let answers = load(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let answers = answers.unwrap();
+        assert_eq!(None, answers.expected(3, 1));
+        assert_eq!(None, answers.expected(3, 2));
+        assert!(answers.expected(4, 1).is_some());
+    }
+}
@@ -0,0 +1,1204 @@
+use crate::input::{self, Source};
+use crate::output::Format;
+use crate::scaffold;
+use crate::server;
+use aoc_2020::params::Settings;
+use aoc_2020::{frames, registry};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const FIRST_DAY: u8 = 1;
+pub const LAST_DAY: u8 = 25;
+
+pub const USAGE: &str = "\
+Usage: aoc_2020 [OPTIONS] [--all] [DAY[:PART] | FROM-TO[:PART]]...
+       aoc_2020 --watch [OPTIONS] DAY[:PART]
+       aoc_2020 --samples [OPTIONS] [--all] [DAY[:PART] | FROM-TO[:PART]]...
+       aoc_2020 bench [--runs N] [OPTIONS] [--all] [DAY[:PART] | FROM-TO[:PART]]...
+       aoc_2020 matrix [OPTIONS] [--all] [DAY[:PART] | FROM-TO[:PART]]...
+       aoc_2020 frames [--output DIR] [OPTIONS] [DAY[:PART]]...
+       aoc_2020 report [--output PATH] [--html] [OPTIONS] [--all] [DAY[:PART] | FROM-TO[:PART]]...
+       aoc_2020 serve [--port PORT] [--timeout SECS] [--jobs N]
+       aoc_2020 repl [--input-dir DIR]
+       aoc_2020 new [--root DIR] YEAR DAY
+
+Runs the selected puzzle solutions; all of them when nothing is selected.
+The bench command runs every selected part N times and reports min/median/max
+durations.
+The matrix command runs the selected parts on the inputs of every user found
+as DIR/USER/dayN.txt in the input directory and prints the answers and
+durations side by side; inputs on which a solver panics, fails to parse or
+gives up without a solution are flagged and make the exit code nonzero.
+The frames command records every generation of the cellular automata of days
+11, 17 and 24 and writes them as text and images, PGM or SVG for the hexagonal
+tiles of day 24, into the output directory.
+The report command solves the selected parts and writes a Markdown report of
+the answers, durations, input sizes and relative costs of the days, linked to
+their sections in NOTES.md, relative to the output, from the current directory;
+with --html also a standalone HTML page next to it.
+The serve command answers HTTP requests on localhost: GET /days lists the
+solutions and POST /day/N/part/P solves the part on the input in the request
+body, the answer and its duration are returned as JSON.
+The repl command reads commands from stdin: 'parse DAY' loads and parses an
+input which the following commands use, e.g. 'part 2', 'match MESSAGE' with
+the rules of day 19 or 'step 10' of the seats of day 11; 'help' lists them.
+The new command adds a day of a later year to the library: a module with stubs
+of its solvers and a sample test in src/yYEAR/dayDAY.rs, an empty input in
+src/yYEAR/input/dayDAY.txt, and the day in the module of the year in
+src/yYEAR/mod.rs, which is declared in src/lib.rs; the crate is the one in the
+current directory or in the --root directory. The days of later years are not
+selected by the other commands, which solve the days of 2020: run their tests
+with 'cargo test yYEAR::dayDAY' or call aoc_2020::yYEAR::find from code.
+With --watch the single selected day is solved again whenever its input file
+changes and the answers are compared with the previous ones.
+With --samples the selected parts are solved on the sample inputs of the puzzle
+descriptions instead and compared with their expected answers.
+With --explain the solvers also print the intermediate facts their answers
+follow from, like the entries of day 1 adding up to 2020 or the columns of the
+ticket fields of day 16.
+Slow parts like the second ones of days 15 and 23 show their progress on
+stderr when it is a terminal and days are not solved concurrently.
+With --verify the answers are compared to the recorded ones and the exit code
+is nonzero on any mismatch.
+
+Examples:
+    aoc_2020 12        both parts of day 12
+    aoc_2020 12:2      only the second part of day 12
+    aoc_2020 1-10      days 1 to 10
+    aoc_2020 --all     every day
+    aoc_2020 8 --input my/day8.txt
+    aoc_2020 8 --input - < my/day8.txt
+    aoc_2020 --time 15
+    aoc_2020 --timeout 5 15 23
+    aoc_2020 --param day15.turns=1000000 15:2
+    aoc_2020 --explain 16 20:2 21
+    aoc_2020 --verify
+    aoc_2020 --format json 1-10
+    aoc_2020 --jobs 4
+    aoc_2020 --watch 8
+    aoc_2020 --samples 1-10
+    aoc_2020 bench --runs 20 1-10
+    aoc_2020 matrix --input-dir inputs 1-10
+    aoc_2020 frames --output frames 11:1 24:2
+    aoc_2020 report --output wiki/aoc.md --html 1-10
+    aoc_2020 serve --port 8080
+    aoc_2020 repl
+    aoc_2020 new 2021 1
+
+Options:
+    --all              select all days
+    --input PATH       read the input of the single selected day from PATH,
+                       or from stdin when PATH is '-'
+    --input-dir DIR    look up inputs as DIR/dayN.txt [default: inputs];
+                       days without such a file use the embedded input;
+                       the matrix command looks up DIR/USER/dayN.txt
+    --time             report durations of parsing and of each part
+    --format FORMAT    output format of answers: text, json or csv
+                       [default: text]; json and csv include timings
+    --verify           compare answers with the ones recorded in answers.txt
+                       of the input directory, or with those of the embedded
+                       inputs
+    --watch            poll the input file of the single selected day and solve
+                       it again on every change: the --input file, DIR/dayN.txt
+                       or the source of the embedded input
+    --samples          solve the sample inputs of the selected parts and compare
+                       the answers with the expected ones
+    --timeout SECS     abandon parts running longer than SECS seconds and report
+                       them as timed out; the exit code is then nonzero
+    --explain          print the intermediate facts found by the solvers below
+                       each answer, or as a list in the json format
+    --param DAYN.NAME=VALUE
+                       set a parameter of a puzzle, like the number of turns
+                       of day 15, to explore variants of it; see below
+    --config PATH      read parameters from PATH, one DAYN.NAME=VALUE per
+                       line; '#' starts a comment line, --param overrides
+    -j, --jobs N       solve up to N days concurrently [default: 1]; answers
+                       are still printed in day order, followed by the total
+                       wall time
+    -n, --runs N       number of runs of each part in bench [default: 10]
+    -o, --output PATH  directory of the exported frames [default: frames], or
+                       the Markdown file of the report [default: report.md]
+    --html             also write the report as HTML, with the extension .html
+                       (the output must then have another extension)
+    -p, --port PORT    port of the serve command [default: 2020]; it answers
+                       solves running longer than its --timeout [default: 60]
+                       with 504 and requests beyond --jobs concurrent ones
+                       [default: 4] with 503
+    -h, --help         print this help
+";
+
+/// Width of the help lines, the descriptions are wrapped to it.
+const HELP_WIDTH: usize = 80;
+/// Column of the descriptions of the options and parameters.
+const HELP_INDENT: usize = 23;
+
+/// The parameters of all the puzzles for the help, following the `USAGE`.
+pub fn params_help() -> String {
+    let mut help = String::from("\nParameters:\n");
+    for solution in registry::all() {
+        for param in solution.params() {
+            let key = format!("    day{}.{}", solution.day(), param.name);
+            let mut line = format!("{:<1$}", key, HELP_INDENT - 1);
+            for word in param.about.split(' ') {
+                if line.len() + 1 + word.len() > HELP_WIDTH {
+                    help += line.trim_end();
+                    help.push('\n');
+                    line = " ".repeat(HELP_INDENT - 1);
+                }
+                line.push(' ');
+                line += word;
+            }
+            help += &line;
+            help.push('\n');
+        }
+    }
+    help
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Parts {
+    Both,
+    Only(u8),
+}
+
+impl Parts {
+    pub fn contains(self, part: u8) -> bool {
+        match self {
+            Parts::Both => true,
+            Parts::Only(p) => p == part,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Selection {
+    pub day: u8,
+    pub parts: Parts,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Args {
+    pub selections: Vec<Selection>,
+    pub input: Option<Source>,
+    pub input_dir: PathBuf,
+    pub time: bool,
+    pub verify: bool,
+    pub format: Format,
+    pub jobs: usize,
+    pub watch: bool,
+    pub samples: bool,
+    /// Parts running longer are abandoned as timed out.
+    pub timeout: Option<Duration>,
+    /// Parameters set with `--param`, they override those of the `config` file.
+    pub params: Settings,
+    pub config: Option<PathBuf>,
+    /// Whether the facts of the solvers are printed with the answers.
+    pub explain: bool,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Command {
+    Run(Args),
+    Bench {
+        args: Args,
+        runs: usize,
+    },
+    Matrix(Args),
+    Frames {
+        args: Args,
+        output: PathBuf,
+    },
+    /// Markdown report written to `output`, and as HTML next to it with `html`.
+    Report {
+        args: Args,
+        output: PathBuf,
+        html: bool,
+    },
+    Serve {
+        port: u16,
+        limits: server::Limits,
+    },
+    Repl {
+        input_dir: PathBuf,
+    },
+    /// Scaffolding of the `day` of the `year` in the crate at `root`.
+    New {
+        root: PathBuf,
+        year: u16,
+        day: u8,
+    },
+    Help,
+}
+
+fn all_days() -> Vec<Selection> {
+    registry::all()
+        .iter()
+        .map(|solution| Selection {
+            day: solution.day(),
+            parts: Parts::Both,
+        })
+        .collect()
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    match s.parse::<u8>() {
+        Ok(day) if (FIRST_DAY..=LAST_DAY).contains(&day) => Ok(day),
+        _ => Err(format!(
+            "unknown day '{}' (expected {}-{})",
+            s, FIRST_DAY, LAST_DAY
+        )),
+    }
+}
+
+fn parse_part(s: &str) -> Result<u8, String> {
+    match s {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("unknown part '{}' (expected 1 or 2)", s)),
+    }
+}
+
+fn parse_selector(arg: &str) -> Result<Vec<Selection>, String> {
+    let (days_s, parts) = match arg.find(':') {
+        Some(i) => (&arg[..i], Parts::Only(parse_part(&arg[i + 1..])?)),
+        None => (arg, Parts::Both),
+    };
+    let (from, to) = match days_s.find('-') {
+        Some(i) => (parse_day(&days_s[..i])?, parse_day(&days_s[i + 1..])?),
+        None => {
+            let day = parse_day(days_s)?;
+            (day, day)
+        }
+    };
+    if from > to {
+        return Err(format!("invalid range '{}'", days_s));
+    }
+    if let Parts::Only(part) = parts {
+        for day in from..=to {
+            let count = registry::find(day).map_or(0, |s| s.parts());
+            if part > count {
+                return Err(format!("day {} has only {} part", day, count));
+            }
+        }
+    }
+    Ok((from..=to).map(|day| Selection { day, parts }).collect())
+}
+
+fn option_value(name: &str, args: &mut impl Iterator<Item = String>) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("option '{}' requires a value", name))
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    match value.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!("invalid timeout '{}'", value)),
+    }
+}
+
+fn parse_jobs(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid number of jobs '{}'", value)),
+    }
+}
+
+fn parse_serve_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut port = server::DEFAULT_PORT;
+    let mut limits = server::Limits::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-p" | "--port" => {
+                let value = option_value(&arg, &mut args)?;
+                port = value
+                    .parse()
+                    .map_err(|_| format!("invalid port '{}'", value))?;
+            }
+            "--timeout" => limits.timeout = parse_timeout(&option_value(&arg, &mut args)?)?,
+            "-j" | "--jobs" => limits.workers = parse_jobs(&option_value(&arg, &mut args)?)?,
+            s if s.starts_with('-') => {
+                return Err(format!(
+                    "option '{}' is not valid with the serve command",
+                    s
+                ))
+            }
+            s => return Err(format!("unexpected argument '{}' of the serve command", s)),
+        }
+    }
+    Ok(Command::Serve { port, limits })
+}
+
+fn parse_repl_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut input_dir = PathBuf::from(input::DEFAULT_DIR);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--input-dir" => input_dir = PathBuf::from(option_value(&arg, &mut args)?),
+            s if s.starts_with('-') => {
+                return Err(format!("option '{}' is not valid with the repl command", s))
+            }
+            s => return Err(format!("unexpected argument '{}' of the repl command", s)),
+        }
+    }
+    Ok(Command::Repl { input_dir })
+}
+
+fn parse_new_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut root = PathBuf::from(".");
+    let mut values = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--root" => root = PathBuf::from(option_value(&arg, &mut args)?),
+            s if s.starts_with('-') => {
+                return Err(format!("option '{}' is not valid with the new command", s))
+            }
+            _ => values.push(arg),
+        }
+    }
+    let (year, day) = match values.as_slice() {
+        [year, day] => (year, day),
+        _ => return Err("the new command requires a YEAR and a DAY".to_string()),
+    };
+    let year = match year.parse::<u16>() {
+        Ok(scaffold::BASE_YEAR) => {
+            return Err(format!(
+                "the days of {} exist already, at the root of the library",
+                scaffold::BASE_YEAR
+            ))
+        }
+        Ok(year) if year >= scaffold::FIRST_YEAR => year,
+        _ => {
+            return Err(format!(
+                "invalid year '{}' (expected {} or later)",
+                year,
+                scaffold::FIRST_YEAR
+            ))
+        }
+    };
+    let day = parse_day(day)?;
+    Ok(Command::New { root, year, day })
+}
+
+const DEFAULT_RUNS: usize = 10;
+const DEFAULT_FRAMES_DIR: &str = "frames";
+const DEFAULT_REPORT_PATH: &str = "report.md";
+
+/// The commands taking the selection of days and the common options.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Mode {
+    Run,
+    Bench,
+    Matrix,
+    Frames,
+    Report,
+}
+
+impl Mode {
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Run => "run",
+            Mode::Bench => "bench",
+            Mode::Matrix => "matrix",
+            Mode::Frames => "frames",
+            Mode::Report => "report",
+        }
+    }
+}
+
+fn not_valid_with(option: &str, mode: Mode) -> String {
+    format!(
+        "option '{}' is not valid with the {} command",
+        option,
+        mode.name()
+    )
+}
+
+fn one_day(args: &Args) -> bool {
+    args.selections
+        .iter()
+        .all(|s| s.day == args.selections[0].day)
+}
+
+// The rules of the options which are not valid with some commands or other options, one
+// function per option. They are checked in order and the first broken one is reported.
+
+fn check_selections(mode: Mode, args: &Args) -> Result<(), String> {
+    if mode == Mode::Frames {
+        if let Some(s) = args
+            .selections
+            .iter()
+            .find(|s| !frames::DAYS.contains(&s.day))
+        {
+            return Err(format!(
+                "day {} has no frames (expected 11, 17 or 24)",
+                s.day
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_input(mode: Mode, args: &Args) -> Result<(), String> {
+    if args.input.is_none() {
+        Ok(())
+    } else if !one_day(args) {
+        Err("option '--input' requires exactly one selected day".to_string())
+    } else if mode == Mode::Matrix {
+        Err(not_valid_with("--input", mode))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_params(mode: Mode, args: &Args) -> Result<(), String> {
+    let has_params = !args.params.is_empty() || args.config.is_some();
+    if has_params && (mode == Mode::Frames || args.samples) {
+        Err(
+            "options '--param' and '--config' are not valid with '--samples' and the frames \
+             command"
+                .to_string(),
+        )
+    } else if has_params && args.verify {
+        // the recorded answers are those of the puzzles' parameters
+        Err("option '--verify' cannot be used with '--param' and '--config'".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn check_verify(mode: Mode, args: &Args) -> Result<(), String> {
+    if !args.verify {
+        Ok(())
+    } else if args.input.is_some() {
+        Err("option '--verify' cannot be used with '--input'".to_string())
+    } else if args.samples {
+        Err("option '--verify' is not valid with '--samples'".to_string())
+    } else if args.watch {
+        Err("option '--verify' is not valid with '--watch'".to_string())
+    } else if mode != Mode::Run {
+        Err(not_valid_with("--verify", mode))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_timeout(mode: Mode, args: &Args) -> Result<(), String> {
+    let without_timeout = [Mode::Bench, Mode::Matrix, Mode::Frames];
+    if args.timeout.is_some() && (without_timeout.contains(&mode) || args.samples) {
+        Err(
+            "option '--timeout' is not valid with '--samples' and the bench, matrix and frames \
+             commands"
+                .to_string(),
+        )
+    } else {
+        Ok(())
+    }
+}
+
+fn check_explain(mode: Mode, args: &Args) -> Result<(), String> {
+    if !args.explain {
+        Ok(())
+    } else if mode != Mode::Run || args.watch || args.samples {
+        Err(
+            "option '--explain' is not valid with '--watch', '--samples' and the bench, matrix, \
+             frames and report commands"
+                .to_string(),
+        )
+    } else if args.format == Format::Csv {
+        Err("option '--explain' is not valid with the csv format".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn check_samples(mode: Mode, args: &Args) -> Result<(), String> {
+    if !args.samples {
+        Ok(())
+    } else if mode != Mode::Run {
+        Err(
+            "option '--samples' is not valid with the bench, matrix, frames and report commands"
+                .to_string(),
+        )
+    } else if args.watch || args.input.is_some() {
+        Err("option '--samples' cannot be used with '--watch' or '--input'".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn check_watch(mode: Mode, args: &Args) -> Result<(), String> {
+    if !args.watch {
+        Ok(())
+    } else if mode != Mode::Run {
+        Err(
+            "option '--watch' is not valid with the bench, matrix, frames and report commands"
+                .to_string(),
+        )
+    } else if !one_day(args) {
+        Err("option '--watch' requires exactly one selected day".to_string())
+    } else if args.input == Some(Source::Stdin) {
+        Err("option '--watch' cannot watch stdin".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn check_format(mode: Mode, args: &Args) -> Result<(), String> {
+    if args.format == Format::Text {
+        Ok(())
+    } else if args.samples {
+        Err("option '--format' is not valid with '--samples'".to_string())
+    } else if args.watch {
+        Err("option '--format' is not valid with '--watch'".to_string())
+    } else if mode != Mode::Run {
+        Err(not_valid_with("--format", mode))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_jobs(mode: Mode, args: &Args) -> Result<(), String> {
+    // concurrently solved days would skew the measurements and the durations
+    if args.jobs > 1 && (mode == Mode::Bench || mode == Mode::Report) {
+        Err(not_valid_with("--jobs", mode))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_runs(mode: Mode, runs: Option<usize>) -> Result<(), String> {
+    if runs.is_some() && mode != Mode::Bench {
+        Err("option '--runs' is only valid with the bench command".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn check_output(mode: Mode, output: Option<&Path>, html: bool) -> Result<(), String> {
+    if output.is_some() && mode != Mode::Frames && mode != Mode::Report {
+        Err("option '--output' is only valid with the frames and report commands".to_string())
+    } else if html && mode != Mode::Report {
+        Err("option '--html' is only valid with the report command".to_string())
+    } else if html && output.is_some_and(|path| path.extension() == Some("html".as_ref())) {
+        // the HTML page would be written over the Markdown report
+        Err("option '--html' requires an output without the extension .html".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn parse_args(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.peekable();
+    if args.next_if_eq("serve").is_some() {
+        return parse_serve_args(args);
+    }
+    if args.next_if_eq("repl").is_some() {
+        return parse_repl_args(args);
+    }
+    if args.next_if_eq("new").is_some() {
+        return parse_new_args(args);
+    }
+    let mode = [Mode::Bench, Mode::Matrix, Mode::Frames, Mode::Report]
+        .iter()
+        .copied()
+        .find(|mode| args.next_if_eq(mode.name()).is_some())
+        .unwrap_or(Mode::Run);
+
+    let mut selections = Vec::new();
+    let mut all = false;
+    let mut input = None;
+    let mut input_dir = PathBuf::from(input::DEFAULT_DIR);
+    let mut time = false;
+    let mut verify = false;
+    let mut format = Format::Text;
+    let mut jobs = 1;
+    let mut watch = false;
+    let mut samples = false;
+    let mut timeout = None;
+    let mut params = Settings::default();
+    let mut config = None;
+    let mut explain = false;
+    let mut runs = None;
+    let mut output = None;
+    let mut html = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--all" => all = true,
+            "--time" => time = true,
+            "--verify" => verify = true,
+            "--watch" => watch = true,
+            "--samples" => samples = true,
+            "--explain" => explain = true,
+            "--html" => html = true,
+            "-n" | "--runs" => {
+                let value = option_value(&arg, &mut args)?;
+                match value.parse::<usize>() {
+                    Ok(n) if n > 0 => runs = Some(n),
+                    _ => return Err(format!("invalid number of runs '{}'", value)),
+                }
+            }
+            "-j" | "--jobs" => jobs = parse_jobs(&option_value(&arg, &mut args)?)?,
+            "--timeout" => timeout = Some(parse_timeout(&option_value(&arg, &mut args)?)?),
+            "--param" => params.assign(&option_value(&arg, &mut args)?)?,
+            "--config" => config = Some(PathBuf::from(option_value(&arg, &mut args)?)),
+            "-o" | "--output" => output = Some(PathBuf::from(option_value(&arg, &mut args)?)),
+            "--input" => input = Some(Source::from_arg(&option_value(&arg, &mut args)?)),
+            "--format" => format = Format::from_arg(&option_value(&arg, &mut args)?)?,
+            "--input-dir" => input_dir = PathBuf::from(option_value(&arg, &mut args)?),
+            "-" => return Err("'-' is only valid as the value of '--input'".to_string()),
+            s if s.starts_with('-') => return Err(format!("unknown option '{}'", s)),
+            s => selections.extend(parse_selector(s)?),
+        }
+    }
+    if mode == Mode::Frames && (all || selections.is_empty()) {
+        selections = frames::DAYS
+            .iter()
+            .map(|&day| Selection {
+                day,
+                parts: Parts::Both,
+            })
+            .collect();
+    } else if all || selections.is_empty() {
+        selections = all_days();
+    }
+    let args = Args {
+        selections,
+        input,
+        input_dir,
+        time,
+        verify,
+        format,
+        jobs,
+        watch,
+        samples,
+        timeout,
+        params,
+        config,
+        explain,
+    };
+    check_selections(mode, &args)?;
+    check_input(mode, &args)?;
+    check_params(mode, &args)?;
+    check_verify(mode, &args)?;
+    check_timeout(mode, &args)?;
+    check_explain(mode, &args)?;
+    check_samples(mode, &args)?;
+    check_watch(mode, &args)?;
+    check_format(mode, &args)?;
+    check_jobs(mode, &args)?;
+    check_runs(mode, runs)?;
+    check_output(mode, output.as_deref(), html)?;
+    Ok(match mode {
+        Mode::Run => Command::Run(args),
+        Mode::Bench => {
+            let runs = runs.unwrap_or(DEFAULT_RUNS);
+            Command::Bench { args, runs }
+        }
+        Mode::Matrix => Command::Matrix(args),
+        Mode::Frames => {
+            let output = output.unwrap_or_else(|| PathBuf::from(DEFAULT_FRAMES_DIR));
+            Command::Frames { args, output }
+        }
+        Mode::Report => {
+            let output = output.unwrap_or_else(|| PathBuf::from(DEFAULT_REPORT_PATH));
+            Command::Report { args, output, html }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    fn sel(day: u8, parts: Parts) -> Selection {
+        Selection { day, parts }
+    }
+
+    fn run(selections: Vec<Selection>) -> Result<Command, String> {
+        Ok(Command::Run(args(selections)))
+    }
+
+    fn args(selections: Vec<Selection>) -> Args {
+        Args {
+            selections,
+            input: None,
+            input_dir: PathBuf::from(input::DEFAULT_DIR),
+            time: false,
+            verify: false,
+            format: Format::Text,
+            jobs: 1,
+            watch: false,
+            samples: false,
+            timeout: None,
+            params: Settings::default(),
+            config: None,
+            explain: false,
+        }
+    }
+
+    #[test]
+    fn test_cli_no_args_selects_all_days() {
+        assert_eq!(run(all_days()), parse(&[]));
+        assert_eq!(run(all_days()), parse(&["--all"]));
+    }
+
+    #[test]
+    fn test_cli_single_day_and_part() {
+        assert_eq!(run(vec![sel(12, Parts::Both)]), parse(&["12"]));
+        assert_eq!(run(vec![sel(12, Parts::Only(2))]), parse(&["12:2"]));
+    }
+
+    #[test]
+    fn test_cli_range_of_days() {
+        assert_eq!(
+            run(vec![
+                sel(3, Parts::Only(1)),
+                sel(4, Parts::Only(1)),
+                sel(5, Parts::Only(1)),
+                sel(20, Parts::Both),
+            ]),
+            parse(&["3-5:1", "20"])
+        );
+    }
+
+    #[test]
+    fn test_cli_errors() {
+        assert!(parse(&["0"]).is_err());
+        assert!(parse(&["26"]).is_err());
+        assert!(parse(&["abc"]).is_err());
+        assert!(parse(&["5:3"]).is_err());
+        assert!(parse(&["10-5"]).is_err());
+        assert!(parse(&["25:2"]).is_err());
+        assert_eq!(
+            Err("day 25 has only 1 part".to_string()),
+            parse(&["20-25:2"])
+        );
+        assert!(parse(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_input_options() {
+        assert_eq!(
+            Ok(Command::Run(Args {
+                selections: vec![sel(8, Parts::Only(1))],
+                input: Some(Source::Stdin),
+                input_dir: PathBuf::from("other"),
+                time: false,
+                verify: false,
+                format: Format::Text,
+                jobs: 1,
+                watch: false,
+                samples: false,
+                timeout: None,
+                params: Settings::default(),
+                config: None,
+                explain: false,
+            })),
+            parse(&["--input", "-", "8:1", "--input-dir", "other"])
+        );
+        assert_eq!(
+            Ok(Some(Source::File(PathBuf::from("my/day8.txt")))),
+            parse(&["8", "--input", "my/day8.txt"]).map(|c| match c {
+                Command::Run(args) => args.input,
+                _ => None,
+            })
+        );
+        assert!(parse(&["8", "9", "--input", "-"]).is_err());
+        assert!(parse(&["--input", "-"]).is_err());
+        assert!(parse(&["8", "--input"]).is_err());
+        assert!(parse(&["8", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_time_and_bench() {
+        match parse(&["--time", "15"]) {
+            Ok(Command::Run(args)) => assert!(args.time),
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse(&["bench", "1-3", "-n", "5"]) {
+            Ok(Command::Bench { args, runs }) => {
+                assert_eq!(3, args.selections.len());
+                assert_eq!(5, runs);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse(&["bench"]) {
+            Ok(Command::Bench { args, runs }) => {
+                assert_eq!(25, args.selections.len());
+                assert_eq!(DEFAULT_RUNS, runs);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["bench", "--runs", "0"]).is_err());
+        assert!(parse(&["1", "--runs", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_verify() {
+        match parse(&["--verify", "1-5"]) {
+            Ok(Command::Run(args)) => assert!(args.verify),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["8", "--verify", "--input", "my/day8.txt"]).is_err());
+        assert!(parse(&["bench", "--verify"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_format() {
+        match parse(&["--format", "csv", "1"]) {
+            Ok(Command::Run(args)) => assert_eq!(Format::Csv, args.format),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["bench", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_jobs() {
+        match parse(&["-j", "4", "1-10"]) {
+            Ok(Command::Run(args)) => assert_eq!(4, args.jobs),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--jobs", "0"]).is_err());
+        assert!(parse(&["--jobs", "x"]).is_err());
+        assert!(parse(&["bench", "--jobs", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_watch() {
+        match parse(&["--watch", "8:2", "--input", "my/day8.txt"]) {
+            Ok(Command::Run(args)) => {
+                assert!(args.watch);
+                assert_eq!(vec![sel(8, Parts::Only(2))], args.selections);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--watch"]).is_err());
+        assert!(parse(&["--watch", "8-9"]).is_err());
+        assert!(parse(&["--watch", "8", "--input", "-"]).is_err());
+        assert!(parse(&["--watch", "8", "--verify"]).is_err());
+        assert!(parse(&["--watch", "8", "--format", "json"]).is_err());
+        assert!(parse(&["bench", "--watch", "8"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_samples() {
+        match parse(&["--samples", "-j", "2", "9-10"]) {
+            Ok(Command::Run(args)) => {
+                assert!(args.samples);
+                assert_eq!(2, args.selections.len());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--samples", "8", "--input", "my/day8.txt"]).is_err());
+        assert!(parse(&["--samples", "--verify"]).is_err());
+        assert!(parse(&["--samples", "--watch", "8"]).is_err());
+        assert!(parse(&["--samples", "--format", "csv"]).is_err());
+        assert!(parse(&["matrix", "--samples"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_timeout() {
+        match parse(&["--timeout", "2.5", "15"]) {
+            Ok(Command::Run(args)) => assert_eq!(Some(Duration::from_millis(2500)), args.timeout),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--watch", "15", "--timeout", "10"]).is_ok());
+        assert!(parse(&["--timeout", "0"]).is_err());
+        assert!(parse(&["--timeout", "-1"]).is_err());
+        assert!(parse(&["--timeout", "soon"]).is_err());
+        assert!(parse(&["--timeout", "inf"]).is_err());
+        assert!(parse(&["bench", "--timeout", "1"]).is_err());
+        assert!(parse(&["--samples", "--timeout", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_explain() {
+        match parse(&["--explain", "--format", "json", "16"]) {
+            Ok(Command::Run(args)) => assert!(args.explain),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--explain", "-j", "4", "--time"]).is_ok());
+        assert!(parse(&["--explain", "--format", "csv"]).is_err());
+        assert!(parse(&["--explain", "--watch", "8"]).is_err());
+        assert!(parse(&["--explain", "--samples"]).is_err());
+        assert!(parse(&["bench", "--explain"]).is_err());
+        assert!(parse(&["report", "--explain"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_params_help() {
+        let help = params_help();
+        assert!(help.contains("\n    day15.turns        number of turns [default: 2020, 30000000"));
+        assert!(help.contains("\n                       [default: 1000000]\n"));
+        assert!(help.lines().all(|line| line.len() <= HELP_WIDTH));
+    }
+
+    #[test]
+    fn test_cli_params() {
+        match parse(&["--param", "day15.turns=10", "--config", "my.conf", "15:2"]) {
+            Ok(Command::Run(args)) => {
+                assert_eq!(10, args.params.for_day(15).number("turns", 0usize));
+                assert_eq!(Some(PathBuf::from("my.conf")), args.config);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["bench", "--param", "day23.moves=10", "23"]).is_ok());
+        assert!(parse(&["matrix", "--config", "my.conf"]).is_ok());
+        assert!(parse(&["--param", "day15.turns"]).is_err());
+        assert!(parse(&["--param", "day15.turns=0"]).is_err());
+        assert!(parse(&["--param", "day15.turns=4294967295"]).is_err());
+        assert!(parse(&["--param", "day23.cups=4294967295"]).is_err());
+        assert!(parse(&["--param", "day15.rounds=10"]).is_err());
+        assert!(parse(&["--param"]).is_err());
+        assert!(parse(&["--verify", "--param", "day15.turns=10"]).is_err());
+        assert!(parse(&["--samples", "--config", "my.conf"]).is_err());
+        assert!(parse(&["frames", "--param", "day17.cycles=3"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_matrix() {
+        match parse(&["matrix", "--input-dir", "users", "-j", "2", "8-9"]) {
+            Ok(Command::Matrix(args)) => {
+                assert_eq!(PathBuf::from("users"), args.input_dir);
+                assert_eq!(2, args.jobs);
+                assert_eq!(2, args.selections.len());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["matrix", "8", "--input", "day8.txt"]).is_err());
+        assert!(parse(&["matrix", "--verify"]).is_err());
+        assert!(parse(&["matrix", "--format", "csv"]).is_err());
+        assert!(parse(&["matrix", "--runs", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_report() {
+        match parse(&[
+            "report",
+            "--html",
+            "-o",
+            "wiki/aoc.md",
+            "--timeout",
+            "5",
+            "1-3",
+        ]) {
+            Ok(Command::Report { args, output, html }) => {
+                assert_eq!(PathBuf::from("wiki/aoc.md"), output);
+                assert!(html);
+                assert_eq!(3, args.selections.len());
+                assert!(args.timeout.is_some());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse(&["report"]) {
+            Ok(Command::Report { args, output, html }) => {
+                assert_eq!(PathBuf::from("report.md"), output);
+                assert!(!html);
+                assert_eq!(all_days(), args.selections);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--html"]).is_err());
+        assert!(parse(&["report", "--html", "--output", "r.html"]).is_err());
+        assert!(parse(&["report", "--output", "r.html"]).is_ok());
+        assert!(parse(&["report", "--verify"]).is_err());
+        assert!(parse(&["report", "--format", "json"]).is_err());
+        assert!(parse(&["report", "--jobs", "2"]).is_err());
+        assert!(parse(&["report", "--samples"]).is_err());
+        assert!(parse(&["report", "--watch", "8"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_frames() {
+        match parse(&["frames", "-o", "out", "11:2", "24"]) {
+            Ok(Command::Frames { args, output }) => {
+                assert_eq!(PathBuf::from("out"), output);
+                assert_eq!(
+                    vec![sel(11, Parts::Only(2)), sel(24, Parts::Both)],
+                    args.selections
+                );
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse(&["frames"]) {
+            Ok(Command::Frames { args, output }) => {
+                assert_eq!(PathBuf::from(DEFAULT_FRAMES_DIR), output);
+                let days = args.selections.iter().map(|s| s.day).collect::<Vec<_>>();
+                assert_eq!(vec![11, 17, 24], days);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["frames", "10-11"]).is_err());
+        assert!(parse(&["frames", "--verify"]).is_err());
+        assert!(parse(&["11", "--output", "out"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_serve() {
+        assert_eq!(
+            Ok(Command::Serve {
+                port: server::DEFAULT_PORT,
+                limits: server::Limits::default()
+            }),
+            parse(&["serve"])
+        );
+        assert_eq!(
+            Ok(Command::Serve {
+                port: 8080,
+                limits: server::Limits {
+                    timeout: Duration::from_millis(2500),
+                    workers: 2
+                }
+            }),
+            parse(&["serve", "-p", "8080", "--timeout", "2.5", "-j", "2"])
+        );
+        assert_eq!(Ok(Command::Help), parse(&["serve", "--help"]));
+        assert!(parse(&["serve", "--port", "99999"]).is_err());
+        assert!(parse(&["serve", "--jobs", "0"]).is_err());
+        assert!(parse(&["serve", "--timeout", "-1"]).is_err());
+        assert!(parse(&["serve", "--runs", "2"]).is_err());
+        assert!(parse(&["serve", "12"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_repl() {
+        assert_eq!(
+            Ok(Command::Repl {
+                input_dir: PathBuf::from(input::DEFAULT_DIR)
+            }),
+            parse(&["repl"])
+        );
+        assert_eq!(
+            Ok(Command::Repl {
+                input_dir: PathBuf::from("my")
+            }),
+            parse(&["repl", "--input-dir", "my"])
+        );
+        assert!(parse(&["repl", "--time"]).is_err());
+        assert!(parse(&["repl", "19"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_new() {
+        assert_eq!(
+            Ok(Command::New {
+                root: PathBuf::from("."),
+                year: 2021,
+                day: 7
+            }),
+            parse(&["new", "2021", "7"])
+        );
+        assert_eq!(
+            Ok(Command::New {
+                root: PathBuf::from("../aoc"),
+                year: 2021,
+                day: 7
+            }),
+            parse(&["new", "2021", "--root", "../aoc", "7"])
+        );
+        assert!(parse(&["new", "2021", "7", "--root"]).is_err());
+        assert_eq!(Ok(Command::Help), parse(&["new", "--help"]));
+        assert!(parse(&["new", "2020", "7"]).is_err());
+        assert!(parse(&["new", "2014", "7"]).is_err());
+        assert!(parse(&["new", "2021", "26"]).is_err());
+        assert!(parse(&["new", "2021"]).is_err());
+        assert!(parse(&["new", "2021", "7", "8"]).is_err());
+        assert!(parse(&["new", "--year", "2021", "7"]).is_err());
+    }
+
+    /// Default args of day 8, or of days 8 and 9 with `two_days`, changed by `change`.
+    fn changed(two_days: bool, change: impl FnOnce(&mut Args)) -> Args {
+        let mut selections = vec![sel(8, Parts::Both)];
+        if two_days {
+            selections.push(sel(9, Parts::Both));
+        }
+        let mut args = args(selections);
+        change(&mut args);
+        args
+    }
+
+    #[test]
+    fn test_cli_option_rules() {
+        let frames = changed(false, |_| ());
+        assert!(check_selections(Mode::Run, &frames).is_ok());
+        assert!(check_selections(Mode::Frames, &frames).is_err());
+        let file = || Some(Source::File(PathBuf::from("day8.txt")));
+        let input = changed(false, |a| a.input = file());
+        assert!(check_input(Mode::Run, &input).is_ok());
+        assert!(check_input(Mode::Matrix, &input).is_err());
+        assert!(check_input(Mode::Run, &changed(true, |a| a.input = file())).is_err());
+        let config = || Some(PathBuf::from("params.txt"));
+        let params = changed(false, |a| a.config = config());
+        assert!(check_params(Mode::Run, &params).is_ok());
+        assert!(check_params(Mode::Frames, &params).is_err());
+        let params = changed(false, |a| (a.config, a.verify) = (config(), true));
+        assert!(check_params(Mode::Run, &params).is_err());
+        let verify = changed(false, |a| a.verify = true);
+        assert!(check_verify(Mode::Run, &verify).is_ok());
+        assert!(check_verify(Mode::Bench, &verify).is_err());
+        let verify = changed(false, |a| (a.verify, a.input) = (true, file()));
+        assert!(check_verify(Mode::Run, &verify).is_err());
+        let verify = changed(false, |a| (a.verify, a.watch) = (true, true));
+        assert!(check_verify(Mode::Run, &verify).is_err());
+        let verify = changed(false, |a| (a.verify, a.samples) = (true, true));
+        assert_eq!(
+            Err("option '--verify' is not valid with '--samples'".to_string()),
+            check_verify(Mode::Run, &verify)
+        );
+        let timeout = changed(false, |a| a.timeout = Some(Duration::from_secs(1)));
+        assert!(check_timeout(Mode::Report, &timeout).is_ok());
+        assert!(check_timeout(Mode::Matrix, &timeout).is_err());
+        let timeout = changed(false, |a| {
+            (a.timeout, a.samples) = (Some(Duration::from_secs(1)), true)
+        });
+        assert!(check_timeout(Mode::Run, &timeout).is_err());
+        let explain = changed(false, |a| a.explain = true);
+        assert!(check_explain(Mode::Run, &explain).is_ok());
+        assert!(check_explain(Mode::Report, &explain).is_err());
+        let explain = changed(false, |a| (a.explain, a.format) = (true, Format::Csv));
+        assert!(check_explain(Mode::Run, &explain).is_err());
+        let samples = changed(true, |a| a.samples = true);
+        assert!(check_samples(Mode::Run, &samples).is_ok());
+        assert!(check_samples(Mode::Bench, &samples).is_err());
+        let samples = changed(false, |a| (a.samples, a.watch) = (true, true));
+        assert!(check_samples(Mode::Run, &samples).is_err());
+        let watch = changed(false, |a| a.watch = true);
+        assert!(check_watch(Mode::Run, &watch).is_ok());
+        assert!(check_watch(Mode::Matrix, &watch).is_err());
+        assert!(check_watch(Mode::Run, &changed(true, |a| a.watch = true)).is_err());
+        let watch = changed(false, |a| (a.watch, a.input) = (true, Some(Source::Stdin)));
+        assert!(check_watch(Mode::Run, &watch).is_err());
+        let json = changed(true, |a| a.format = Format::Json);
+        assert!(check_format(Mode::Run, &json).is_ok());
+        assert!(check_format(Mode::Frames, &json).is_err());
+        let json = changed(false, |a| (a.format, a.watch) = (Format::Json, true));
+        assert!(check_format(Mode::Run, &json).is_err());
+        let json = changed(true, |a| (a.format, a.samples) = (Format::Json, true));
+        assert!(check_format(Mode::Run, &json).is_err());
+        let jobs = changed(true, |a| a.jobs = 4);
+        assert!(check_jobs(Mode::Run, &jobs).is_ok());
+        assert!(check_jobs(Mode::Matrix, &jobs).is_ok());
+        assert!(check_jobs(Mode::Bench, &jobs).is_err());
+        assert!(check_jobs(Mode::Report, &jobs).is_err());
+        assert!(check_runs(Mode::Bench, Some(3)).is_ok());
+        assert!(check_runs(Mode::Report, Some(3)).is_err());
+        assert!(check_output(Mode::Frames, Some(Path::new("out")), false).is_ok());
+        assert!(check_output(Mode::Run, Some(Path::new("out")), false).is_err());
+        assert!(check_output(Mode::Report, None, true).is_ok());
+        assert!(check_output(Mode::Bench, None, true).is_err());
+        assert!(check_output(Mode::Report, Some(Path::new("r.html")), true).is_err());
+    }
+
+    #[test]
+    fn test_cli_help() {
+        assert_eq!(Ok(Command::Help), parse(&["1", "--help"]));
+    }
+}
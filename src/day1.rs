@@ -1,9 +1,19 @@
+use crate::explain;
+use crate::parser::Cursor;
+use crate::progress;
+use crate::ParseError;
+
 static EXPECTED: i32 = 2020;
 
-fn find_pair(nums: &Vec<i32>) -> Option<(i32, i32)> {
-    for a in nums.into_iter() {
-        for b in nums.into_iter() {
-            if a + b == EXPECTED {
+pub fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    Cursor::new(input).map_lines(Cursor::take_number)
+}
+
+fn find_pair(nums: &[i32], expected: i32) -> Option<(i32, i32)> {
+    for a in nums.iter() {
+        progress::check();
+        for b in nums.iter() {
+            if a + b == expected {
                 let t = (*a, *b);
                 return Some(t);
             }
@@ -12,16 +22,23 @@ fn find_pair(nums: &Vec<i32>) -> Option<(i32, i32)> {
     None
 }
 
-fn part1(nums: &Vec<i32>) -> i32 {
-    let t = find_pair(&nums).unwrap();
-    t.0 * t.1
+/// Product of the two entries adding up to `expected`, none when there are no such.
+pub fn part1_with_sum(nums: &[i32], expected: i32) -> Option<i32> {
+    let (a, b) = find_pair(nums, expected)?;
+    explain::fact("pair", format_args!("{} + {} = {}", a, b, expected));
+    Some(a * b)
+}
+
+pub fn part1(nums: &[i32]) -> i32 {
+    part1_with_sum(nums, EXPECTED).expect("no two entries add up to 2020")
 }
 
-fn find_triple(nums: &Vec<i32>) -> Option<(i32, i32, i32)> {
-    for a in nums.into_iter() {
-        for b in nums.into_iter() {
-            for c in nums.into_iter() {
-                if a + b + c == EXPECTED {
+fn find_triple(nums: &[i32], expected: i32) -> Option<(i32, i32, i32)> {
+    for a in nums.iter() {
+        for b in nums.iter() {
+            progress::check();
+            for c in nums.iter() {
+                if a + b + c == expected {
                     let t = (*a, *b, *c);
                     return Some(t);
                 }
@@ -31,16 +48,53 @@ fn find_triple(nums: &Vec<i32>) -> Option<(i32, i32, i32)> {
     None
 }
 
-fn part2(nums: &Vec<i32>) -> i32 {
-    let t = find_triple(&nums).unwrap();
-    t.0 * t.1 * t.2
+/// Product of the three entries adding up to `expected`, none when there are no such.
+pub fn part2_with_sum(nums: &[i32], expected: i32) -> Option<i32> {
+    let (a, b, c) = find_triple(nums, expected)?;
+    explain::fact(
+        "triple",
+        format_args!("{} + {} + {} = {}", a, b, c, expected),
+    );
+    Some(a * b * c)
+}
+
+pub fn part2(nums: &[i32]) -> i32 {
+    part2_with_sum(nums, EXPECTED).expect("no three entries add up to 2020")
 }
 
-use crate::lib;
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day1;
 
-pub fn run() {
-    let input = include_str!("input/day1.txt");
-    let nums = &lib::to_i32s(input);
-    println!("Day 1/1: {}", part1(nums));
-    println!("Day 1/2: {}", part2(nums));
+pub const SOLUTION: &dyn DynSolution = &Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    type Parsed = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1_with_sum(parsed, EXPECTED).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2_with_sum(parsed, EXPECTED).into()
+    }
+    const PARAMS: &'static [Param] = &[Param::number(
+        "sum",
+        0,
+        i32::MAX as u64,
+        "sum of the entries to find [default: 2020]",
+    )];
+    fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part1_with_sum(parsed, params.number("sum", EXPECTED)).into()
+    }
+    fn sentinel(_part: u8) -> Option<Answer> {
+        Some(Answer::None)
+    }
+    fn part2_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part2_with_sum(parsed, params.number("sum", EXPECTED)).into()
+    }
 }
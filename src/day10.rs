@@ -1,12 +1,15 @@
-fn parse_input<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<u32> {
-    let mut v = lines.map(|s| s.parse::<u32>().unwrap()).collect::<Vec<_>>();
+use crate::parser::Cursor;
+use crate::ParseError;
+
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut v: Vec<u32> = Cursor::new(input).map_lines(Cursor::take_number)?;
     v.sort();
     v.insert(0, 0);
     v.push(v.last().unwrap() + 3);
-    v
+    Ok(v)
 }
 
-fn part1(jolts: &Vec<u32>) -> u32 {
+pub fn part1(jolts: &[u32]) -> u32 {
     let mut current = 0;
     let mut diffs1 = 0;
     let mut diffs3 = 0;
@@ -21,7 +24,7 @@ fn part1(jolts: &Vec<u32>) -> u32 {
     diffs1 * diffs3
 }
 
-fn part2(jolts: &Vec<u32>) -> u64 {
+pub fn part2(jolts: &[u32]) -> u64 {
     let mut track = vec![1; jolts.len()];
     for i in (0..jolts.len()).rev() {
         let can_connect_with = |q| jolts.get(q).map(|x| x - jolts[i] <= 3).unwrap_or_default();
@@ -39,82 +42,23 @@ fn part2(jolts: &Vec<u32>) -> u64 {
     track[0]
 }
 
-pub fn run() {
-    let input = include_str!("input/day10.txt");
-    let jolts = parse_input(input.lines());
-    println!("Day 10/1: {}", part1(&jolts));
-    println!("Day 10/2: {}", part2(&jolts));
-}
+use crate::solution::{Answer, DynSolution, Solution};
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+struct Day10;
 
-    const SAMPLE1: &'static str = "16
-10
-15
-5
-1
-11
-7
-19
-6
-12
-4
-";
+pub const SOLUTION: &dyn DynSolution = &Day10;
 
-    const SAMPLE2: &'static str = "28
-33
-18
-42
-31
-14
-46
-20
-48
-47
-24
-23
-49
-45
-19
-38
-39
-11
-1
-32
-25
-35
-8
-17
-7
-9
-4
-2
-34
-10
-3
-";
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    type Parsed = Vec<u32>;
 
-    #[test]
-    fn test_day10_part1_sample1() {
-        let jolts = &parse_input(SAMPLE1.lines());
-        assert_eq!(7 * 5, part1(jolts));
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
     }
-    #[test]
-    fn test_day10_part1_sample2() {
-        let jolts = &parse_input(SAMPLE2.lines());
-        assert_eq!(22 * 10, part1(jolts));
-    }
-
-    #[test]
-    fn test_day10_part2_sample1() {
-        let jolts = &parse_input(SAMPLE1.lines());
-        assert_eq!(8, part2(jolts));
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
     }
-    #[test]
-    fn test_day10_part2_sample2() {
-        let jolts = &parse_input(SAMPLE2.lines());
-        assert_eq!(19208, part2(jolts));
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
     }
 }
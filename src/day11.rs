@@ -1,161 +1,165 @@
-#[derive(Copy, Clone, PartialEq)]
-enum PosType {
+use crate::frames::Frame;
+use crate::grid::{Cell, Grid, DIRECTIONS8};
+use crate::parser::Cursor;
+use crate::ParseError;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PosType {
     Floor,
     SeatFree,
     SeatOccupied,
 }
 
-fn parse_input<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Vec<PosType>> {
-    lines
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    'L' => PosType::SeatFree,
-                    '.' => PosType::Floor,
-                    _ => panic!(),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>()
+impl Cell for PosType {
+    const EXPECTED: &'static str = "'L', '#' or '.'";
+
+    fn from_char(c: char) -> Option<PosType> {
+        match c {
+            'L' => Some(PosType::SeatFree),
+            '#' => Some(PosType::SeatOccupied),
+            '.' => Some(PosType::Floor),
+            _ => None,
+        }
+    }
+    fn to_char(&self) -> char {
+        match self {
+            PosType::SeatFree => 'L',
+            PosType::SeatOccupied => '#',
+            PosType::Floor => '.',
+        }
+    }
 }
 
-fn count_occupied(seats: &Vec<Vec<PosType>>) -> usize {
-    seats
-        .iter()
-        .flatten()
-        .filter(|p| *p == &PosType::SeatOccupied)
-        .count()
+pub type Layout = Grid<PosType>;
+
+pub fn parse(input: &str) -> Result<Layout, ParseError> {
+    Grid::parse(Cursor::new(input))
 }
 
-static DIRECTIONS: [(i32, i32); 8] = [
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-];
-
-fn part1(seats: &Vec<Vec<PosType>>) -> usize {
-    let rows_len = seats.len();
-    let cols_len = seats[0].len();
-
-    let count_adjacent_occupied = |seats: &Vec<Vec<_>>, i, j| {
-        let mut adjacent_occupied = 0;
-        for (x, y) in &DIRECTIONS {
-            let a = i as i32 + x;
-            let b = j as i32 + y;
-            if a < 0 || b < 0 || a >= rows_len as i32 || b >= cols_len as i32 {
-                continue;
-            }
-            if seats[a as usize][b as usize] == PosType::SeatOccupied {
-                adjacent_occupied += 1
-            }
-        }
-        adjacent_occupied
-    };
+/// Applies the seating rules once.
+/// A seat is left when at least `tolerance` of the seats counted by `occupied` are taken.
+fn next_generation(
+    seats: &Layout,
+    tolerance: usize,
+    occupied: impl Fn(&Layout, usize, usize) -> usize,
+) -> Layout {
+    Grid::from_fn(seats.width(), seats.height(), |x, y| match seats[(x, y)] {
+        PosType::SeatFree if occupied(seats, x, y) == 0 => PosType::SeatOccupied,
+        PosType::SeatOccupied if occupied(seats, x, y) >= tolerance => PosType::SeatFree,
+        state => state,
+    })
+}
 
-    let mut old_seats = seats.clone();
-    loop {
-        let mut new_seats = vec![vec![PosType::Floor; cols_len]; rows_len];
-        for i in 0..rows_len {
-            for j in 0..cols_len {
-                let occupied = count_adjacent_occupied(&old_seats, i, j);
-                new_seats[i][j] = match old_seats[i][j] {
-                    PosType::SeatFree if occupied == 0 => PosType::SeatOccupied,
-                    PosType::SeatOccupied if occupied >= 4 => PosType::SeatFree,
-                    state => state,
-                };
-            }
-        }
-        if old_seats == new_seats {
-            return count_occupied(&old_seats);
-        }
-        old_seats = new_seats;
+/// Applies the seating rules of the `part` once.
+pub fn step(seats: &Layout, part: u8) -> Layout {
+    match part {
+        1 => next_generation(seats, 4, adjacent_occupied),
+        _ => next_generation(seats, 5, visible_occupied),
     }
 }
 
-fn part2(seats: &Vec<Vec<PosType>>) -> usize {
-    let rows_len = seats.len();
-    let cols_len = seats[0].len();
-
-    let count_visible_occupied = |seats: &Vec<Vec<_>>, i, j| {
-        let mut visible_occupied = 0;
-        for (x, y) in &DIRECTIONS {
-            let mut row = i as i32;
-            let mut col = j as i32;
-            loop {
-                row += x;
-                col += y;
-                if row < 0 || col < 0 || row >= rows_len as i32 || col >= cols_len as i32 {
-                    break;
-                }
-                match seats[row as usize][col as usize] {
-                    PosType::Floor => continue,
-                    PosType::SeatFree => (),
-                    PosType::SeatOccupied => visible_occupied += 1,
-                }
-                break;
-            }
-        }
-        visible_occupied
-    };
-
-    let mut old_seats = seats.clone();
+/// Applies the seating rules of the `part` until nothing changes, returns all the layouts
+/// on the way.
+fn generations(seats: &Layout, part: u8) -> Vec<Layout> {
+    let mut generations = vec![seats.clone()];
     loop {
-        let mut new_seats = vec![vec![PosType::Floor; cols_len]; rows_len];
-        for i in 0..rows_len {
-            for j in 0..cols_len {
-                let occupied = count_visible_occupied(&old_seats, i, j);
-                new_seats[i][j] = match old_seats[i][j] {
-                    PosType::SeatFree if occupied == 0 => PosType::SeatOccupied,
-                    PosType::SeatOccupied if occupied >= 5 => PosType::SeatFree,
-                    state => state,
-                };
-            }
+        let old_seats = generations.last().unwrap();
+        let new_seats = step(old_seats, part);
+        if *old_seats == new_seats {
+            return generations;
         }
-        if old_seats == new_seats {
-            return count_occupied(&old_seats);
-        }
-        old_seats = new_seats;
+        generations.push(new_seats);
     }
 }
 
-pub fn run() {
-    let input = include_str!("input/day11.txt");
-    let seats = parse_input(input.lines());
-    println!("Day 11/1: {}", part1(&seats));
-    println!("Day 11/2: {}", part2(&seats));
+fn adjacent_occupied(seats: &Layout, x: usize, y: usize) -> usize {
+    seats
+        .neighbours8(x, y)
+        .filter(|&pos| seats[pos] == PosType::SeatOccupied)
+        .count()
+}
+
+fn visible_occupied(seats: &Layout, x: usize, y: usize) -> usize {
+    DIRECTIONS8
+        .iter()
+        .filter_map(|&dir| {
+            seats
+                .ray(x, y, dir)
+                .map(|pos| seats[pos])
+                .find(|&p| p != PosType::Floor)
+        })
+        .filter(|&p| p == PosType::SeatOccupied)
+        .count()
+}
+
+pub fn count_occupied(seats: &Layout) -> usize {
+    seats
+        .iter()
+        .filter(|&&p| p == PosType::SeatOccupied)
+        .count()
+}
+
+pub fn part1(seats: &Layout) -> usize {
+    count_occupied(generations(seats, 1).last().unwrap())
+}
+
+pub fn part2(seats: &Layout) -> usize {
+    count_occupied(generations(seats, 2).last().unwrap())
+}
+
+/// Layouts of the `part` from the initial one until the seating settles.
+pub fn frames(seats: &Layout, part: u8) -> Vec<Frame> {
+    generations(seats, part)
+        .iter()
+        .map(|seats| Frame::Map(seats.map(Cell::to_char)))
+        .collect()
+}
+
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day11;
+
+pub const SOLUTION: &dyn DynSolution = &Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    type Parsed = Layout;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE1: &'static str = "\
-L.LL.LL.LL
-LLLLLLL.LL
-L.L.L..L..
-LLLL.LL.LL
-L.LL.LL.LL
-L.LLLLL.LL
-..L.L.....
-LLLLLLLLLL
-L.LLLLLL.L
-L.LLLLL.LL
-";
+    const SAMPLE1: &str = include_str!("samples/day11.txt");
 
     #[test]
-    fn test_day11_part1_sample1() {
-        let seats = &parse_input(SAMPLE1.lines());
-        assert_eq!(37, part1(seats));
+    fn test_day11_frames() {
+        let seats = &parse(SAMPLE1).unwrap();
+        let frames = frames(seats, 1);
+        assert_eq!(6, frames.len());
+        assert_eq!(Frame::Map(seats.map(Cell::to_char)), frames[0]);
+        match &frames[1] {
+            Frame::Map(grid) => assert!(grid.iter().all(|&c| c != 'L')),
+            frame => panic!("unexpected {:?}", frame),
+        }
     }
 
     #[test]
-    fn test_day11_part2_sample1() {
-        let seats = &parse_input(SAMPLE1.lines());
-        assert_eq!(26, part2(seats));
+    fn test_day11_parse_errors() {
+        let err = parse("L.L\nLxL\n").unwrap_err();
+        assert_eq!((2, 2, "x"), (err.line, err.column, err.text.as_str()));
+        let err = parse("L.L\nLL\n").unwrap_err();
+        assert_eq!((2, 1, "LL"), (err.line, err.column, err.text.as_str()));
+        assert!(parse("").is_err());
     }
 }
@@ -1,4 +1,7 @@
-use points::Point;
+use crate::parser::Cursor;
+use crate::ParseError;
+
+type Point = crate::point::Point<i32>;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 enum Direction {
@@ -18,7 +21,7 @@ enum Action {
     F,
 }
 
-struct Move(Action, i32);
+pub struct Move(Action, i32);
 
 impl From<i32> for Direction {
     fn from(item: i32) -> Self {
@@ -52,186 +55,115 @@ impl Direction {
     pub fn left_by_angle(&mut self, angle: i32) {
         self.right_by_angle(360 - angle)
     }
-}
-
-mod points {
-    use std::ops::{AddAssign, Mul};
-
-    #[derive(Copy, Clone, PartialEq, Debug)]
-    pub struct Point {
-        x: i32,
-        y: i32,
-    }
-
-    impl Point {
-        pub fn new(x: i32, y: i32) -> Point {
-            Point { x, y }
-        }
-        pub fn zero() -> Point {
-            Point::new(0, 0)
-        }
-        pub fn manhattan_dist(&self, from: Point) -> i32 {
-            (from.x - self.x).abs() + (from.y - self.y).abs()
-        }
-        pub fn move_north(&mut self, value: i32) {
-            self.y += value;
-        }
-        pub fn move_south(&mut self, value: i32) {
-            self.y -= value;
-        }
-        pub fn move_east(&mut self, value: i32) {
-            self.x += value;
-        }
-        pub fn move_west(&mut self, value: i32) {
-            self.x -= value;
-        }
-        pub fn rotate_right(&mut self, angle: i32) {
-            let a = angle / 90 % 4;
-            for _ in 0..a {
-                let x2 = self.y;
-                let y2 = -self.x;
-                self.x = x2;
-                self.y = y2;
-            }
-        }
-        pub fn rotate_left(&mut self, angle: i32) {
-            self.rotate_right(360 - angle % 360);
-        }
-    }
-
-    impl Mul<i32> for Point {
-        type Output = Self;
-
-        fn mul(self, rhs: i32) -> Self {
-            Self::new(self.x * rhs, self.y * rhs)
+    fn to_point(self) -> Point {
+        match self {
+            Direction::North => Point::new(0, 1),
+            Direction::East => Point::new(1, 0),
+            Direction::South => Point::new(0, -1),
+            Direction::West => Point::new(-1, 0),
         }
     }
+}
 
-    impl AddAssign for Point {
-        fn add_assign(&mut self, other: Self) {
-            *self = Self::new(self.x + other.x, self.y + other.y)
-        }
-    }
-
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-
-        #[test]
-        fn test_day12_point_rotate_right() {
-            let mut p = Point::new(10, 4);
-            p.rotate_right(90);
-            assert_eq!(Point::new(4, -10), p);
-            p.rotate_right(90);
-            assert_eq!(Point::new(-10, -4), p);
-            p.rotate_right(360);
-            assert_eq!(Point::new(-10, -4), p);
-            p.rotate_right(270);
-            assert_eq!(Point::new(4, -10), p);
-            p.rotate_right(180);
-            assert_eq!(Point::new(-4, 10), p);
-        }
+fn rotate_right(pt: Point, angle: i32) -> Point {
+    (0..angle / 90 % 4).fold(pt, |pt, _| pt.rotate_right())
+}
 
-        #[test]
-        fn test_day12_point_rotate_left() {
-            let mut p = Point::new(2, 4);
-            p.rotate_left(90);
-            assert_eq!(Point::new(-4, 2), p);
-            p.rotate_left(90);
-            assert_eq!(Point::new(-2, -4), p);
-            p.rotate_left(360);
-            assert_eq!(Point::new(-2, -4), p);
-            p.rotate_left(270);
-            assert_eq!(Point::new(-4, 2), p);
-            p.rotate_left(180);
-            assert_eq!(Point::new(4, -2), p);
-        }
-    }
+fn rotate_left(pt: Point, angle: i32) -> Point {
+    rotate_right(pt, 360 - angle % 360)
 }
 
-fn parse_input<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Move> {
-    lines
-        .map(|line| {
-            let action = match line.chars().next().unwrap() {
-                'N' => Action::N,
-                'S' => Action::S,
-                'E' => Action::E,
-                'W' => Action::W,
-                'L' => Action::L,
-                'R' => Action::R,
-                'F' => Action::F,
-                _ => panic!(),
-            };
-            let value = line[1..].parse::<i32>().unwrap();
-            Move(action, value)
-        })
-        .collect::<Vec<_>>()
+pub fn parse(input: &str) -> Result<Vec<Move>, ParseError> {
+    Cursor::new(input).map_lines(|line| {
+        let action = match line.peek() {
+            Some('N') => Action::N,
+            Some('S') => Action::S,
+            Some('E') => Action::E,
+            Some('W') => Action::W,
+            Some('L') => Action::L,
+            Some('R') => Action::R,
+            Some('F') => Action::F,
+            _ => return Err(line.error("expected an action")),
+        };
+        line.take_char();
+        let start = *line;
+        let value = line.take_number::<i32>()?;
+        let is_turn = matches!(action, Action::L | Action::R);
+        if is_turn && !(value % 90 == 0 && (0..=360).contains(&value)) {
+            return Err(line.error_at(
+                line.consumed_since(&start),
+                "expected a multiple of 90 degrees up to 360",
+            ));
+        }
+        Ok(Move(action, value))
+    })
 }
 
-fn part1(moves: &Vec<Move>) -> i32 {
+pub fn part1(moves: &Vec<Move>) -> i32 {
     let mut dir = Direction::East;
     let mut pt = Point::zero();
 
     for Move(action, value) in moves {
         let v = *value;
         match action {
-            Action::N => pt.move_north(v),
-            Action::S => pt.move_south(v),
-            Action::E => pt.move_east(v),
-            Action::W => pt.move_west(v),
+            Action::N => pt += Direction::North.to_point() * v,
+            Action::S => pt += Direction::South.to_point() * v,
+            Action::E => pt += Direction::East.to_point() * v,
+            Action::W => pt += Direction::West.to_point() * v,
             Action::L => dir.left_by_angle(v),
             Action::R => dir.right_by_angle(v),
-            Action::F => match dir {
-                Direction::North => pt.move_north(v),
-                Direction::South => pt.move_south(v),
-                Direction::East => pt.move_east(v),
-                Direction::West => pt.move_west(v),
-            },
+            Action::F => pt += dir.to_point() * v,
         }
     }
 
-    pt.manhattan_dist(Point::zero())
+    pt.manhattan_distance(&Point::zero())
 }
 
-fn part2(moves: &Vec<Move>) -> i32 {
+pub fn part2(moves: &Vec<Move>) -> i32 {
     let mut ship = Point::zero();
     let mut waypoint = Point::new(10, 1);
 
     for Move(action, value) in moves {
         let v = *value;
         match action {
-            Action::N => waypoint.move_north(v),
-            Action::S => waypoint.move_south(v),
-            Action::E => waypoint.move_east(v),
-            Action::W => waypoint.move_west(v),
-            Action::L => waypoint.rotate_left(v),
-            Action::R => waypoint.rotate_right(v),
+            Action::N => waypoint += Direction::North.to_point() * v,
+            Action::S => waypoint += Direction::South.to_point() * v,
+            Action::E => waypoint += Direction::East.to_point() * v,
+            Action::W => waypoint += Direction::West.to_point() * v,
+            Action::L => waypoint = rotate_left(waypoint, v),
+            Action::R => waypoint = rotate_right(waypoint, v),
             Action::F => ship += waypoint * v,
         }
     }
 
-    ship.manhattan_dist(Point::zero())
+    ship.manhattan_distance(&Point::zero())
 }
 
-pub fn run() {
-    let input = include_str!("input/day12.txt");
-    let moves = parse_input(input.lines());
-    println!("Day 12/1: {}", part1(&moves));
-    println!("Day 12/2: {}", part2(&moves));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day12;
+
+pub const SOLUTION: &dyn DynSolution = &Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    type Parsed = Vec<Move>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE1: &'static str = "\
-F10
-N3
-F7
-R90
-F11
-";
-
     #[test]
     fn test_day12_direction_right_by_angle() {
         let mut dir = Direction::North;
@@ -255,21 +187,30 @@ F11
     }
 
     #[test]
-    fn test_day12_direction_manhattan_dist_from() {
-        assert_eq!(17 + 8, Point::new(-17, -8).manhattan_dist(Point::zero()));
-        assert_eq!(17 + 8, Point::new(-17, 8).manhattan_dist(Point::zero()));
-        assert_eq!(17 + 8, Point::new(17, 8).manhattan_dist(Point::zero()));
+    fn test_day12_rotate_right() {
+        let p = Point::new(10, 4);
+        assert_eq!(Point::new(4, -10), rotate_right(p, 90));
+        assert_eq!(Point::new(-10, -4), rotate_right(p, 180));
+        assert_eq!(p, rotate_right(p, 360));
+        assert_eq!(Point::new(-4, 10), rotate_right(p, 270));
     }
 
     #[test]
-    fn test_day12_part1_sample1() {
-        let parsed = &parse_input(SAMPLE1.lines());
-        assert_eq!(25, part1(parsed));
+    fn test_day12_rotate_left() {
+        let p = Point::new(2, 4);
+        assert_eq!(Point::new(-4, 2), rotate_left(p, 90));
+        assert_eq!(Point::new(-2, -4), rotate_left(p, 180));
+        assert_eq!(p, rotate_left(p, 360));
+        assert_eq!(Point::new(4, -2), rotate_left(p, 270));
     }
 
     #[test]
-    fn test_day12_part2_sample1() {
-        let parsed = &parse_input(SAMPLE1.lines());
-        assert_eq!(286, part2(parsed));
+    fn test_day12_parse_errors() {
+        let err = parse("F10\nX3\n").err().unwrap();
+        assert_eq!((2, 1, "X3"), (err.line, err.column, err.text.as_str()));
+        let err = parse("F10\nR45\n").err().unwrap();
+        assert_eq!((2, 2, "45"), (err.line, err.column, err.text.as_str()));
+        let err = parse("F1x\n").err().unwrap();
+        assert_eq!((1, 2, "1x"), (err.line, err.column, err.text.as_str()));
     }
 }
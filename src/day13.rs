@@ -1,3 +1,6 @@
+use crate::parser::Cursor;
+use crate::ParseError;
+
 #[derive(PartialEq, Debug)]
 struct Bus {
     id: i64,
@@ -5,25 +8,44 @@ struct Bus {
 }
 
 #[derive(PartialEq, Debug)]
-struct Notes {
+pub struct Notes {
     depart_timestamp: u32,
     buses: Vec<Bus>,
 }
 
-fn parse_input<'a>(input: &str) -> Notes {
-    let mut lines = input.lines();
-    let depart_timestamp = lines.next().unwrap().parse::<u32>().unwrap();
-    let buses = lines
-        .next()
-        .unwrap()
-        .split(',')
+pub fn parse(input: &str) -> Result<Notes, ParseError> {
+    let mut cursor = Cursor::new(input);
+    let mut line = cursor.expect_line("missing timestamp")?;
+    let depart_timestamp = line.take_number::<u32>()?;
+    line.expect_end()?;
+
+    let mut line = cursor.expect_line("missing bus ids")?;
+    let bus_ids = line.separated(",", |bus_id| {
+        if bus_id.eat("x") {
+            return Ok(None);
+        }
+        let start = *bus_id;
+        let id = bus_id.take_number::<i64>()?;
+        if id <= 0 {
+            let at = bus_id.consumed_since(&start);
+            return Err(bus_id.error_at(at, "bus id must be positive"));
+        }
+        Ok(Some(id))
+    })?;
+    line.expect_end()?;
+
+    let buses = bus_ids
+        .into_iter()
         .enumerate()
-        .filter_map(|(offset, bus_id)| bus_id.parse::<i64>().ok().map(|id| Bus { id, offset }))
+        .filter_map(|(offset, id)| Some(Bus { id: id?, offset }))
         .collect::<Vec<_>>();
-    Notes {
+    if buses.is_empty() {
+        return Err(ParseError::end_of_input(input, "no buses in service"));
+    }
+    Ok(Notes {
         depart_timestamp,
         buses,
-    }
+    })
 }
 
 #[inline]
@@ -36,7 +58,7 @@ fn div_ceil(num: i64, div: i64) -> i64 {
     }
 }
 
-fn part1(notes: &Notes) -> i64 {
+pub fn part1(notes: &Notes) -> i64 {
     let (id, diff) = notes
         .buses
         .iter()
@@ -61,7 +83,7 @@ fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
     }
 }
 
-fn part2(notes: &Notes) -> i64 {
+pub fn part2(notes: &Notes) -> i64 {
     let ns: i64 = notes.buses.iter().map(|bus| bus.id).product();
 
     let xs: i64 = notes
@@ -70,34 +92,45 @@ fn part2(notes: &Notes) -> i64 {
         .map(|bus| {
             let n = ns / bus.id;
             let (_, m, _) = egcd(n, bus.id);
-            let x = bus.offset as i64 * n * m;
-            x
+
+            bus.offset as i64 * n * m
         })
         .sum();
 
     ns - xs.rem_euclid(ns)
 }
 
-pub fn run() {
-    let input = include_str!("input/day13.txt");
-    let notes = parse_input(input);
-    println!("Day 13/1: {}", part1(&notes));
-    println!("Day 13/2: {}", part2(&notes));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day13;
+
+pub const SOLUTION: &dyn DynSolution = &Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    type Parsed = Notes;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE1: &'static str = "\
-939
-7,13,x,x,59,x,31,19
-";
+    const SAMPLE1: &str = include_str!("samples/day13.txt");
 
     #[test]
     fn test_day13_parse_input() {
         let b = |id, offset| -> Bus { Bus { id, offset } };
-        let notes = parse_input(SAMPLE1);
+        let notes = parse(SAMPLE1).unwrap();
         assert_eq!(
             Notes {
                 depart_timestamp: 939,
@@ -108,23 +141,10 @@ mod tests {
     }
 
     #[test]
-    fn test_day13_part1_sample1() {
-        let notes = &parse_input(SAMPLE1);
-        assert_eq!(295, part1(notes));
-    }
-
-    #[test]
-    fn test_day13_part2_sample1() {
-        let notes = &parse_input(SAMPLE1);
-        assert_eq!(1068781, part2(notes));
-    }
-
-    #[test]
-    fn test_day13_part2_more_samples() {
-        assert_eq!(3417, part2(&parse_input("0\n17,x,13,19")));
-        assert_eq!(754018, part2(&parse_input("0\n67,7,59,61")));
-        assert_eq!(779210, part2(&parse_input("0\n67,x,7,59,61")));
-        assert_eq!(1261476, part2(&parse_input("0\n67,7,x,59,61")));
-        assert_eq!(1202161486, part2(&parse_input("0\n1789,37,47,1889")));
+    fn test_day13_parse_errors() {
+        let err = parse("939\n7,y,13\n").unwrap_err();
+        assert_eq!((2, 3, "y"), (err.line, err.column, err.text.as_str()));
+        let err = parse("939\n").unwrap_err();
+        assert_eq!("line 2, column 1: missing bus ids", err.to_string());
     }
 }
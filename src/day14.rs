@@ -1,54 +1,55 @@
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq)]
-struct Mask {
+pub struct Mask {
     ones: u64,
     zeros: u64,
     flucts: Vec<u64>, // fluctuating positions (with 'X')
 }
 
 #[derive(Debug, PartialEq)]
-enum Op {
+pub enum Op {
     Mask(Mask),
     Mem(u64, u64),
 }
 
-fn parse_input<'a>(input: &str) -> Vec<Op> {
-    input
-        .lines()
-        .map(|line| {
-            let p = line.split(" = ").collect::<Vec<_>>();
-            let left = p[0];
-            let right = p[1];
-            if left == "mask" {
-                let zeros_str = right.replace('1', "X").replace('0', "1").replace('X', "0");
-                let ones_str = right.replace('X', "0");
-                let zeros = u64::from_str_radix(&zeros_str, 2).unwrap();
-                let ones = u64::from_str_radix(&ones_str, 2).unwrap();
-                let flucts = right
-                    .chars()
-                    .enumerate()
-                    .filter(|(_, v)| v == &'X')
-                    .map(|(i, _)| 35 - i as u64)
-                    .collect::<Vec<u64>>();
-                Op::Mask(Mask {
-                    zeros,
-                    ones,
-                    flucts,
-                })
-            } else {
-                let address = left
-                    .replace(|c| !char::is_numeric(c), "")
-                    .parse::<u64>()
-                    .unwrap();
-                let value = right.parse::<u64>().unwrap();
-                Op::Mem(address, value)
+pub fn parse(input: &str) -> Result<Vec<Op>, ParseError> {
+    Cursor::new(input).map_lines(|line| {
+        if line.eat("mask = ") {
+            let mask = line.take_rest();
+            if mask.len() != 36 || !mask.chars().all(|c| matches!(c, '0' | '1' | 'X')) {
+                return Err(line.error_at(mask, "expected 36 of '0', '1' or 'X'"));
             }
-        })
-        .collect::<Vec<_>>()
+            let zeros_str = mask.replace('1', "X").replace('0', "1").replace('X', "0");
+            let ones_str = mask.replace('X', "0");
+            let zeros = u64::from_str_radix(&zeros_str, 2).unwrap();
+            let ones = u64::from_str_radix(&ones_str, 2).unwrap();
+            let flucts = mask
+                .chars()
+                .enumerate()
+                .filter(|(_, v)| v == &'X')
+                .map(|(i, _)| 35 - i as u64)
+                .collect::<Vec<u64>>();
+            Ok(Op::Mask(Mask {
+                zeros,
+                ones,
+                flucts,
+            }))
+        } else if line.eat("mem[") {
+            let address = line.take_number::<u64>()?;
+            line.expect_literal("] = ")?;
+            let value = line.take_number::<u64>()?;
+            Ok(Op::Mem(address, value))
+        } else {
+            let left = line.take_until(" = ")?;
+            Err(line.error_at(left, "expected 'mask' or 'mem[N]'"))
+        }
+    })
 }
 
-fn part1(ops: &Vec<Op>) -> u64 {
+pub fn part1(ops: &Vec<Op>) -> u64 {
     let mut hm = HashMap::new();
     let mut mask = &Mask {
         zeros: 0,
@@ -77,7 +78,19 @@ fn clr_bit(num: u64, bit: u64) -> u64 {
     num & !(1 << bit)
 }
 
-fn part2(ops: &Vec<Op>) -> u64 {
+/// Most floating bits of a mask in part 2, every write of a mask with more of them would set
+/// too many addresses to keep in memory, like the 34 of the first sample.
+const MAX_FLOATING: usize = 16;
+
+/// The sum of the memory after the decoder of version 2, none when a mask has more than
+/// [`MAX_FLOATING`] floating bits.
+pub fn part2(ops: &Vec<Op>) -> Option<u64> {
+    if ops.iter().any(|op| match op {
+        Op::Mask(mask) => mask.flucts.len() > MAX_FLOATING,
+        Op::Mem(..) => false,
+    }) {
+        return None;
+    }
     let mut hm = HashMap::new();
     let mut mask = &Mask {
         zeros: 0,
@@ -87,11 +100,11 @@ fn part2(ops: &Vec<Op>) -> u64 {
     #[inline]
     fn permute(addr: u64, flucts: &[u64], value: u64, hm: &mut HashMap<u64, u64>) {
         if let Some((idx, tail)) = flucts.split_first() {
-            permute(set_bit(addr, *idx as u64), tail, value, hm);
-            permute(clr_bit(addr, *idx as u64), tail, value, hm);
+            permute(set_bit(addr, *idx), tail, value, hm);
+            permute(clr_bit(addr, *idx), tail, value, hm);
         } else {
             let entry = hm.entry(addr).or_default();
-            *entry = value.into();
+            *entry = value;
         }
     }
     for op in ops {
@@ -103,37 +116,48 @@ fn part2(ops: &Vec<Op>) -> u64 {
             }
         }
     }
-    hm.values().sum()
+    Some(hm.values().sum())
 }
 
-pub fn run() {
-    let input = include_str!("input/day14.txt");
-    let ops = parse_input(input);
-    println!("Day 14/1: {}", part1(&ops));
-    println!("Day 14/2: {}", part2(&ops));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day14;
+
+pub const SOLUTION: &dyn DynSolution = &Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    type Parsed = Vec<Op>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    fn sentinel(part: u8) -> Option<Answer> {
+        if part == 2 {
+            Some(Answer::None)
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE1: &'static str = "\
-mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
-mem[8] = 11
-mem[7] = 101
-mem[8] = 0
-";
+    const SAMPLE1: &str = include_str!("samples/day14.txt");
 
-    const SAMPLE2: &'static str = "\
-mask = 000000000000000000000000000000X1001X
-mem[42] = 100
-mask = 00000000000000000000000000000000X0XX
-mem[26] = 1
-";
+    const SAMPLE2: &str = include_str!("samples/day14-2.txt");
 
     #[test]
     fn test_day14_parse_input_sample1() {
-        let ops = parse_input(SAMPLE1);
+        let ops = parse(SAMPLE1).unwrap();
         let flucts = (0..36)
             .rev()
             .filter(|n| *n != 1 && *n != 6)
@@ -155,7 +179,7 @@ mem[26] = 1
 
     #[test]
     fn test_day14_parse_input_sample2() {
-        let ops = parse_input(SAMPLE2);
+        let ops = parse(SAMPLE2).unwrap();
         assert_eq!(
             vec![
                 Op::Mask(Mask {
@@ -176,15 +200,19 @@ mem[26] = 1
     }
 
     #[test]
-    fn test_day14_part1_sample1() {
-        let ops = &parse_input(SAMPLE1);
-        assert_eq!(165, part1(ops));
+    fn test_day14_parse_input_errors() {
+        let err = parse("mem[8] = 11\nmask = 1X0\n").unwrap_err();
+        assert_eq!((2, 8, "1X0"), (err.line, err.column, err.text.as_str()));
+        let err = parse("mem[x] = 11\n").unwrap_err();
+        assert_eq!((1, 5, "x"), (err.line, err.column, err.text.as_str()));
+        let err = parse("mem(8) = 11\n").unwrap_err();
+        assert_eq!((1, 1, "mem(8)"), (err.line, err.column, err.text.as_str()));
     }
 
     #[test]
-    fn test_day14_part2_sample2() {
-        let ops = &parse_input(SAMPLE2);
-        assert_eq!(208, part2(ops));
+    fn test_day14_part2_too_many_floating_bits() {
+        assert_eq!(None, part2(&parse(SAMPLE1).unwrap()));
+        assert_eq!(Some(208), part2(&parse(SAMPLE2).unwrap()));
     }
 
     #[test]
@@ -1,7 +1,33 @@
-fn part1(nums: &Vec<u32>) -> usize {
-    let mut stack = nums.clone();
+use crate::parser::Cursor;
+use crate::progress;
+use crate::ParseError;
 
-    let rfind = |vec: &Vec<u32>, start, val| -> Option<usize> {
+/// Number of turns between two reports of the progress.
+const REPORT_EVERY: usize = 1 << 16;
+
+/// Number of turns of the first part.
+const PART1_TURNS: usize = 2020;
+/// Number of turns of the second part.
+const PART2_TURNS: usize = 30_000_000;
+/// Most turns of a game, the second part remembers a turn for each of them.
+const MAX_TURNS: usize = 100_000_000;
+
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut line = Cursor::new(input).expect_line("missing starting numbers")?;
+    let nums = line.separated(",", Cursor::take_number)?;
+    line.expect_end()?;
+    Ok(nums)
+}
+
+/// Plays the memory game for `turns` turns, looking up the previous turn of the last number
+/// by scanning the numbers spoken so far.
+fn play_by_scanning(nums: &[u32], turns: usize) -> usize {
+    if turns <= nums.len() {
+        return nums[turns - 1] as usize;
+    }
+    let mut stack = nums.to_vec();
+
+    let rfind = |vec: &[u32], start, val| -> Option<usize> {
         for i in (0..start).rev() {
             if vec[i] == val {
                 return Some(i + 1);
@@ -11,34 +37,40 @@ fn part1(nums: &Vec<u32>) -> usize {
     };
 
     let mut last = *stack.last().unwrap();
-    let mut last_turn_no = stack.len() as u32;
-    for _i in nums.len()..2020 {
+    for last_turn_no in stack.len() as u32..turns as u32 {
+        if (last_turn_no as usize).is_multiple_of(REPORT_EVERY) {
+            progress::report(last_turn_no as usize, turns);
+        }
         let prev_turn_no: Option<usize> = rfind(&stack, stack.len() - 1, last);
         last = match prev_turn_no {
             None => 0,
             Some(p) => last_turn_no - p as u32,
         };
-        stack.push(last as u32);
-        last_turn_no += 1;
+        stack.push(last);
     }
     last as usize
 }
 
-fn part2(nums: &Vec<u32>) -> usize {
-    static LEN: usize = 30000000;
-    let mut mem = vec![0; LEN];
-
-    let max = LEN - nums.len();
+/// Plays the memory game for `turns` turns, remembering the last turn of every number.
+fn play_with_memory(nums: &[u32], turns: usize) -> usize {
+    if turns <= nums.len() {
+        return nums[turns - 1] as usize;
+    }
     let (last_elem, elements) = nums[..].split_last().unwrap();
+    // spoken numbers are smaller than the number of turns, the starting ones may be larger
+    let len = nums.iter().map(|&v| v as usize + 1).fold(turns, usize::max);
+    let mut mem = vec![0; len];
 
     for (i, v) in elements.iter().enumerate() {
-        mem[*v as usize] = (i + 1) as usize;
+        mem[*v as usize] = i + 1;
     }
 
     let mut last = *last_elem as usize;
-    let mut last_turn_no = nums.len();
 
-    for _i in 0..max {
+    for last_turn_no in nums.len()..turns {
+        if last_turn_no.is_multiple_of(REPORT_EVERY) {
+            progress::report(last_turn_no, turns);
+        }
         let v = {
             let prev_turn_no = mem[last];
             if prev_turn_no == 0 {
@@ -49,46 +81,123 @@ fn part2(nums: &Vec<u32>) -> usize {
         };
 
         mem[last] = last_turn_no;
-
-        last_turn_no += 1;
         last = v;
     }
     last
 }
 
-pub fn run() {
-    let input = include_str!("input/day15.txt");
-    let nums = &input
-        .split(',')
-        .map(|s| s.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
-    println!("Day 15/1: {}", part1(nums));
-    println!("Day 15/2: {}", part2(nums));
+/// Plays the game for `turns` turns, scanning the spoken numbers like the first part does.
+pub fn part1_with_turns(nums: &[u32], turns: usize) -> usize {
+    play_by_scanning(nums, turns)
+}
+
+pub fn part1(nums: &[u32]) -> usize {
+    part1_with_turns(nums, PART1_TURNS)
+}
+
+/// Plays the game for `turns` turns, remembering the spoken numbers like the second part does.
+pub fn part2_with_turns(nums: &[u32], turns: usize) -> usize {
+    play_with_memory(nums, turns)
+}
+
+pub fn part2(nums: &[u32]) -> usize {
+    part2_with_turns(nums, PART2_TURNS)
+}
+
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day15;
+
+pub const SOLUTION: &dyn DynSolution = &Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    type Parsed = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    const PARAMS: &'static [Param] = &[Param::number(
+        "turns",
+        1,
+        MAX_TURNS as u64,
+        "number of turns [default: 2020, 30000000 in part 2]",
+    )];
+    fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part1_with_turns(parsed, params.number("turns", PART1_TURNS)).into()
+    }
+    fn part2_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part2_with_turns(parsed, params.number("turns", PART2_TURNS)).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::differential::{self, Config, Rng};
 
     #[test]
-    fn test_day15_part1_samples() {
-        assert_eq!(436, part1(&vec![0, 3, 6]));
-        assert_eq!(1, part1(&vec![1, 3, 2]));
-        assert_eq!(10, part1(&vec![2, 1, 3]));
-        assert_eq!(27, part1(&vec![1, 2, 3]));
-        assert_eq!(78, part1(&vec![2, 3, 1]));
-        assert_eq!(438, part1(&vec![3, 2, 1]));
-        assert_eq!(1836, part1(&vec![3, 1, 2]));
+    fn test_day15_parse() {
+        assert_eq!(Ok(vec![0, 3, 6]), parse("0,3,6\n"));
+        let err = parse("0,3,,6\n").unwrap_err();
+        assert_eq!((1, 5, ""), (err.line, err.column, err.text.as_str()));
+        assert!(parse("\n").is_err());
     }
 
     #[test]
-    fn test_day15_part2_samples() {
-        assert_eq!(175594, part2(&vec![0, 3, 6]));
-        assert_eq!(2578, part2(&vec![1, 3, 2]));
-        assert_eq!(3544142, part2(&vec![2, 1, 3]));
-        assert_eq!(261214, part2(&vec![1, 2, 3]));
-        assert_eq!(6895259, part2(&vec![2, 3, 1]));
-        assert_eq!(18, part2(&vec![3, 2, 1]));
-        assert_eq!(362, part2(&vec![3, 1, 2]));
+    fn test_day15_starting_turns() {
+        let nums = [14, 1, 17, 0, 3, 20];
+        assert_eq!(14, part1_with_turns(&nums, 1));
+        assert_eq!(1, part1_with_turns(&nums, 2));
+        assert_eq!(1, part2_with_turns(&nums, 2));
+        assert_eq!(20, part2_with_turns(&nums, 6));
+    }
+
+    #[test]
+    fn test_day15_differential() {
+        let config = Config::from_env(200, 15);
+        let generate = |rng: &mut Rng| {
+            let nums: Vec<u32> = (0..rng.range(1, 6))
+                .map(|_| rng.range(0, 20) as u32)
+                .collect();
+            // also games ending while the starting numbers are spoken
+            let turns = rng.range(1, nums.len() + 500);
+            (nums, turns)
+        };
+        let shrink = |(nums, turns): &(Vec<u32>, usize)| {
+            let mut smaller = Vec::new();
+            for t in differential::shrink_number(*turns, 1) {
+                smaller.push((nums.clone(), t));
+            }
+            for i in 0..nums.len() {
+                if nums.len() > 1 {
+                    let mut fewer = nums.clone();
+                    fewer.remove(i);
+                    smaller.push((fewer, (*turns - 1).max(1)));
+                }
+                for v in differential::shrink_number(nums[i] as usize, 0) {
+                    let mut lower = nums.clone();
+                    lower[i] = v as u32;
+                    smaller.push((lower, *turns));
+                }
+            }
+            smaller
+        };
+        let result = differential::check(&config, generate, shrink, |(nums, turns)| {
+            differential::agree(
+                play_by_scanning(nums, *turns),
+                play_with_memory(nums, *turns),
+            )
+        });
+        if let Err(counterexample) = result {
+            panic!("{}", counterexample);
+        }
     }
 }
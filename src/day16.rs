@@ -1,4 +1,6 @@
-use crate::lib::*;
+use crate::explain;
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq)]
@@ -17,7 +19,7 @@ impl Field {
 }
 
 #[derive(Debug, PartialEq)]
-struct Notes {
+pub struct Notes {
     fields: Vec<Field>,
     your_ticket: Vec<u32>,
     nearby_tickets: Vec<Vec<u32>>,
@@ -29,49 +31,72 @@ impl Notes {
     }
 }
 
-fn parse_input<'a>(input: &str) -> Notes {
-    let areas = input.split("\n\n").collect::<Vec<&str>>();
-    let a_fields = areas[0];
-    let a_your = areas[1];
-    let a_nearby = areas[2];
-
-    let fields = a_fields
-        .lines()
-        .map(|line| {
-            let (name, rules) = split_once_str(line, ": ");
-            let (r1, r2) = split_once_str(rules, " or ");
-            let (r1a, r1b) = split_once_char(r1, '-');
-            let (r2a, r2b) = split_once_char(r2, '-');
-
-            Field {
-                name: name.to_string(),
-                rule1: (parse_unwrap(r1a), parse_unwrap(r1b)),
-                rule2: (parse_unwrap(r2a), parse_unwrap(r2b)),
-            }
+/// Returns the section following its `header` line.
+fn section<'a>(
+    input: &'a str,
+    block: Option<Cursor<'a>>,
+    header: &str,
+) -> Result<Cursor<'a>, ParseError> {
+    let mut block = block.ok_or_else(|| ParseError::end_of_input(input, "missing section"))?;
+    let line = block.expect_line("missing section")?;
+    if line.rest() != header {
+        return Err(line.error(format!("expected '{}'", header)));
+    }
+    Ok(block)
+}
+
+fn parse_range(cursor: &mut Cursor) -> Result<(u32, u32), ParseError> {
+    let from = cursor.take_number()?;
+    cursor.expect_literal("-")?;
+    Ok((from, cursor.take_number()?))
+}
+
+pub fn parse(input: &str) -> Result<Notes, ParseError> {
+    let mut blocks = Cursor::new(input).split_blocks();
+    let b_fields = blocks.next().unwrap_or_else(|| Cursor::new(input));
+
+    let fields = b_fields.map_lines(|line| {
+        let name = line.take_until(": ")?;
+        let rule1 = parse_range(line)?;
+        line.expect_literal(" or ")?;
+        let rule2 = parse_range(line)?;
+        Ok(Field {
+            name: name.to_string(),
+            rule1,
+            rule2,
         })
-        .collect::<Vec<_>>();
+    })?;
+
+    let parse_ticket = |line: &mut Cursor| {
+        let start = *line;
+        let ticket: Vec<u32> = line.separated(",", Cursor::take_number)?;
+        if ticket.len() != fields.len() {
+            let message = format!("expected {} values", fields.len());
+            return Err(line.error_at(start.rest(), message));
+        }
+        Ok(ticket)
+    };
 
-    let your = parse_to_vec(a_your.lines().into_iter().last().unwrap().split(','));
+    let mut b_your = section(input, blocks.next(), "your ticket:")?;
+    let mut your_line = b_your.expect_line("missing your ticket")?;
+    let your = parse_ticket(&mut your_line)?;
+    your_line.expect_end()?;
+    b_your.expect_end()?;
 
-    let nearby = a_nearby
-        .lines()
-        .skip(1)
-        .map(|line| parse_to_vec(line.split(',')))
-        .collect::<Vec<_>>();
+    let nearby = section(input, blocks.next(), "nearby tickets:")?.map_lines(parse_ticket)?;
 
-    Notes {
-        fields: fields,
+    Ok(Notes {
+        fields,
         your_ticket: your,
         nearby_tickets: nearby,
-    }
+    })
 }
 
-fn part1(notes: &Notes) -> u32 {
+pub fn part1(notes: &Notes) -> u32 {
     notes
         .nearby_tickets
         .iter()
-        .map(|ticket| ticket.iter().filter(|&&x| !notes.has_valid_field(x)))
-        .flatten()
+        .flat_map(|ticket| ticket.iter().filter(|&&x| !notes.has_valid_field(x)))
         .sum()
 }
 
@@ -88,6 +113,7 @@ fn detect_fields(notes: &Notes) -> HashMap<String, u32> {
     let mut unmatched_fields = (0..notes.fields.len()).collect::<Vec<usize>>();
     let mut unmatched_indexes = (0..notes.fields.len()).collect::<Vec<usize>>();
     let mut map = HashMap::new();
+    let mut columns = Vec::new();
     loop {
         for f_idx in 0..notes.fields.len() {
             if !unmatched_fields.contains(&f_idx) {
@@ -103,6 +129,7 @@ fn detect_fields(notes: &Notes) -> HashMap<String, u32> {
             if matching_indexes.len() == 1 {
                 let i = *matching_indexes[0];
                 map.insert(field.name.clone(), notes.your_ticket[i]);
+                columns.push((i, &field.name));
                 unmatched_indexes.retain(|&u| u != i);
                 unmatched_fields.retain(|&n| n != f_idx);
             }
@@ -112,10 +139,17 @@ fn detect_fields(notes: &Notes) -> HashMap<String, u32> {
             break;
         }
     }
+    columns.sort();
+    for (i, name) in columns {
+        explain::fact(
+            "field",
+            format_args!("column {} is {}, {} on your ticket", i, name, map[name]),
+        );
+    }
     map
 }
 
-fn part2(notes: &Notes) -> usize {
+pub fn part2(notes: &Notes) -> usize {
     detect_fields(notes)
         .iter()
         .filter(|(k, _)| k.starts_with("departure"))
@@ -123,49 +157,39 @@ fn part2(notes: &Notes) -> usize {
         .product()
 }
 
-pub fn run() {
-    let input = include_str!("input/day16.txt");
-    let notes = &parse_input(input);
-    println!("Day 16/1: {}", part1(notes));
-    println!("Day 16/2: {}", part2(notes));
-}
+use crate::solution::{Answer, DynSolution, Solution};
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+struct Day16;
 
-    static SAMPLE1: &'static str = "\
-class: 1-3 or 5-7
-row: 6-11 or 33-44
-seat: 13-40 or 45-50
+pub const SOLUTION: &dyn DynSolution = &Day16;
 
-your ticket:
-7,1,14
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+    type Parsed = Notes;
 
-nearby tickets:
-7,3,47
-40,4,50
-55,2,20
-38,6,12
-";
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
 
-    static SAMPLE2: &'static str = "\
-class: 0-1 or 4-19
-row: 0-5 or 8-19
-seat: 0-13 or 16-19
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explain::Fact;
 
-your ticket:
-11,12,13
+    static SAMPLE1: &str = include_str!("samples/day16.txt");
 
-nearby tickets:
-3,9,18
-15,1,5
-5,14,9
-";
+    static SAMPLE2: &str = include_str!("samples/day16-2.txt");
 
     #[test]
     fn test_day16_parse_input() {
-        let notes = parse_input(SAMPLE1);
+        let notes = parse(SAMPLE1).unwrap();
         let expected = Notes {
             fields: vec![
                 Field {
@@ -196,16 +220,40 @@ nearby tickets:
     }
 
     #[test]
-    fn test_day16_part1_sample1() {
-        assert_eq!(71, part1(&parse_input(SAMPLE1)));
+    fn test_day16_parse_input_errors() {
+        let input = SAMPLE1.replace("40,4,50", "40,4");
+        let err = parse(&input).unwrap_err();
+        assert_eq!((10, 1, "40,4"), (err.line, err.column, err.text.as_str()));
+        let input = SAMPLE1.replace("your ticket", "my ticket");
+        let err = parse(&input).unwrap_err();
+        assert_eq!(
+            "line 5, column 1: expected 'your ticket:' in 'my ticket:'",
+            err.to_string()
+        );
+        let err = parse("class: 1-3 or 5-x\n").unwrap_err();
+        assert_eq!((1, 17, "x"), (err.line, err.column, err.text.as_str()));
     }
 
     #[test]
     fn test_day16_part2_sample2() {
-        let detected = detect_fields(&parse_input(SAMPLE2));
+        let detected = detect_fields(&parse(SAMPLE2).unwrap());
         assert_eq!(detected.len(), 3);
         assert_eq!(detected.get("class"), Some(&12));
         assert_eq!(detected.get("row"), Some(&11));
         assert_eq!(detected.get("seat"), Some(&13));
     }
+
+    #[test]
+    fn test_day16_explain() {
+        let notes = parse(SAMPLE2).unwrap();
+        let (_, facts) = explain::collect(|| detect_fields(&notes));
+        assert_eq!(
+            vec![
+                "field: column 0 is row, 11 on your ticket",
+                "field: column 1 is class, 12 on your ticket",
+                "field: column 2 is seat, 13 on your ticket",
+            ],
+            facts.iter().map(Fact::to_string).collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,36 +1,43 @@
-use std::cmp::max;
-use std::collections::BTreeSet;
-
-type Cube = BTreeSet<(i8, i8, i8)>;
-type HyperCube = BTreeSet<(i8, i8, i8, i8)>;
-
-fn parse_input<'a>(input: &str) -> Cube {
-    let data = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .into_iter()
-                .enumerate()
-                .filter_map(|(x, ch)| if ch == '#' { Some(x) } else { None })
-        })
-        .enumerate()
-        .map(|(y, xs)| xs.map(|x| (x as i8, y as i8, 0i8)).collect::<Vec<_>>())
-        .flatten()
-        .collect::<Vec<_>>();
-
+use crate::frames::Frame;
+use crate::grid::Grid;
+use crate::parser::Cursor;
+use crate::point::{Point3, Point4, PointN};
+use crate::progress;
+use crate::ParseError;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub type Cube = BTreeSet<Point3<i8>>;
+
+// leaves room for the growth during the cycles within the i8 coordinates
+const MAX_SIZE: usize = 100;
+/// Number of cycles of the boot process, the slices grow by one cube every cycle.
+const CYCLES: usize = 6;
+/// Most cycles which keep the coordinates of the largest slices within `i8`.
+const MAX_CYCLES: usize = i8::MAX as usize - MAX_SIZE;
+
+pub fn parse(input: &str) -> Result<Cube, ParseError> {
     let mut set = Cube::new();
-    for d in data {
-        set.insert(d);
+    for (y, mut line) in Cursor::new(input).lines().enumerate() {
+        if y >= MAX_SIZE || line.rest().len() > MAX_SIZE {
+            return Err(line.error("the slice is too large"));
+        }
+        let mut x = 0;
+        while let Some(ch) = line.peek() {
+            match ch {
+                '#' => {
+                    set.insert(Point3::new(x as i8, y as i8, 0i8));
+                }
+                '.' => {}
+                _ => return Err(line.error_ahead(1, "expected '#' or '.'")),
+            }
+            line.take_char();
+            x += 1;
+        }
     }
-    set
-}
-
-fn max_key(cube: &Cube) -> i8 {
-    *cube
-        .iter()
-        .map(|(x, y, z)| max(x, max(y, z)))
-        .max()
-        .unwrap()
+    if set.is_empty() {
+        return Err(ParseError::end_of_input(input, "no active cubes"));
+    }
+    Ok(set)
 }
 
 fn is_new_state_active(state: bool, active_neighbours: i32) -> bool {
@@ -40,133 +47,186 @@ fn is_new_state_active(state: bool, active_neighbours: i32) -> bool {
     }
 }
 
-fn part1(cube: &Cube) -> usize {
-    let mut min: i8 = 0;
-    let mut max: i8 = max_key(cube);
-    let mut cube = cube.clone();
-
-    for _ in 1..=6 {
-        let mut new_cube = Cube::new();
-        min -= 1;
-        max += 1;
-
-        for x in min..=max {
-            for y in min..=max {
-                for z in min..=max {
-                    let at_position = (x, y, z);
-
-                    let mut active_neighbours = 0;
-                    for nx in x - 1..=x + 1 {
-                        for ny in y - 1..=y + 1 {
-                            for nz in z - 1..=z + 1 {
-                                let at_neighbour = (nx, ny, nz);
-                                if at_neighbour == at_position {
-                                    continue;
-                                }
-                                if cube.contains(&at_neighbour) {
-                                    active_neighbours += 1;
-                                }
-                            }
-                        }
-                    }
-
-                    if is_new_state_active(cube.contains(&at_position), active_neighbours) {
-                        new_cube.insert(at_position);
-                    }
-                }
+/// Runs `cycles` cycles of the boot process of the pocket dimension with `D` dimensions,
+/// every cycle and the initial state are passed to `observe`.
+fn boot<const D: usize>(
+    mut active: BTreeSet<PointN<i8, D>>,
+    cycles: usize,
+    mut observe: impl FnMut(&BTreeSet<PointN<i8, D>>),
+) -> usize {
+    assert!(
+        cycles <= MAX_CYCLES,
+        "too many cycles for the i8 coordinates"
+    );
+    observe(&active);
+    for cycle in 1..=cycles {
+        progress::report(cycle - 1, cycles);
+        let mut active_neighbours = BTreeMap::new();
+        for pt in &active {
+            for neighbour in pt.neighbours() {
+                *active_neighbours.entry(neighbour).or_insert(0) += 1;
             }
         }
-        cube = new_cube;
+        active = active_neighbours
+            .into_iter()
+            .filter(|(pt, count)| is_new_state_active(active.contains(pt), *count))
+            .map(|(pt, _)| pt)
+            .collect();
+        observe(&active);
     }
+    active.len()
+}
 
-    cube.len()
+fn hypercube(cube: &Cube) -> BTreeSet<Point4<i8>> {
+    cube.iter()
+        .map(|pt| {
+            let [x, y, z] = pt.coords();
+            PointN([x, y, z, 0])
+        })
+        .collect()
 }
 
-fn part2(cube: &Cube) -> usize {
-    let mut min: i8 = 0;
-    let mut max: i8 = max_key(cube);
-    let mut hypercube = HyperCube::new();
-    for (x, y, z) in cube {
-        hypercube.insert((*x, *y, *z, 0));
-    }
+pub fn part1_with_cycles(cube: &Cube, cycles: usize) -> usize {
+    boot(cube.clone(), cycles, |_| {})
+}
 
-    for _ in 1..=6 {
-        let mut new_hypercube = HyperCube::new();
-        min -= 1;
-        max += 1;
-
-        for x in min..=max {
-            for y in min..=max {
-                for z in min..=max {
-                    for w in min..=max {
-                        let at_position = (x, y, z, w);
-
-                        let mut active_neighbours = 0;
-                        for nx in x - 1..=x + 1 {
-                            for ny in y - 1..=y + 1 {
-                                for nz in z - 1..=z + 1 {
-                                    for nw in w - 1..=w + 1 {
-                                        let at_neighbour = (nx, ny, nz, nw);
-                                        if at_neighbour == at_position {
-                                            continue;
-                                        }
-                                        if hypercube.contains(&at_neighbour) {
-                                            active_neighbours += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        if is_new_state_active(hypercube.contains(&at_position), active_neighbours)
-                        {
-                            new_hypercube.insert(at_position);
-                        }
-                    }
-                }
+pub fn part1(cube: &Cube) -> usize {
+    part1_with_cycles(cube, CYCLES)
+}
+
+pub fn part2_with_cycles(cube: &Cube, cycles: usize) -> usize {
+    boot(hypercube(cube), cycles, |_| {})
+}
+
+pub fn part2(cube: &Cube) -> usize {
+    part2_with_cycles(cube, CYCLES)
+}
+
+/// Draws the `x`/`y` slices side by side, `z` grows to the right and `w` down.
+fn slices<const D: usize>(active: &BTreeSet<PointN<i8, D>>) -> Frame {
+    let coords = |axis: usize| active.iter().map(move |pt| pt[axis] as isize);
+    let min: [isize; D] = std::array::from_fn(|axis| coords(axis).min().unwrap_or(0));
+    let max: [isize; D] = std::array::from_fn(|axis| coords(axis).max().unwrap_or(0));
+    let size = |axis: usize| match (min.get(axis), max.get(axis)) {
+        (Some(min), Some(max)) => (max - min + 1) as usize,
+        _ => 1,
+    };
+    let (width, height) = (size(0), size(1));
+    let grid = Grid::from_fn(
+        size(2) * (width + 1) - 1,
+        size(3) * (height + 1) - 1,
+        |x, y| {
+            let (tile_x, x) = (x / (width + 1), x % (width + 1));
+            let (tile_y, y) = (y / (height + 1), y % (height + 1));
+            if x == width || y == height {
+                return ' ';
             }
-        }
-        hypercube = new_hypercube;
-    }
+            let mut pt = min;
+            pt[0] += x as isize;
+            pt[1] += y as isize;
+            pt[2] += tile_x as isize;
+            if let Some(w) = pt.get_mut(3) {
+                *w += tile_y as isize;
+            }
+            if active.contains(&PointN(pt.map(|c| c as i8))) {
+                '#'
+            } else {
+                '.'
+            }
+        },
+    );
+    Frame::Map(grid)
+}
 
-    hypercube.len()
+/// The initial state and the states after each cycle of the `part`.
+pub fn frames(cube: &Cube, part: u8) -> Vec<Frame> {
+    let mut frames = Vec::new();
+    match part {
+        1 => boot(cube.clone(), CYCLES, |active| frames.push(slices(active))),
+        _ => boot(hypercube(cube), CYCLES, |active| {
+            frames.push(slices(active))
+        }),
+    };
+    frames
 }
 
-pub fn run() {
-    let input = include_str!("input/day17.txt");
-    let cube = &parse_input(input);
-    println!("Day 17/1: {}", part1(cube));
-    println!("Day 17/2: {}", part2(cube));
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day17;
+
+pub const SOLUTION: &dyn DynSolution = &Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+    type Parsed = Cube;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    const PARAMS: &'static [Param] = &[Param::number(
+        "cycles",
+        0,
+        MAX_CYCLES as u64,
+        "number of cycles of the boot process [default: 6]",
+    )];
+    fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part1_with_cycles(parsed, params.number("cycles", CYCLES)).into()
+    }
+    fn part2_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part2_with_cycles(parsed, params.number("cycles", CYCLES)).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::frames;
 
-    static SAMPLE1: &'static str = "\
-.#.
-..#
-###
-";
+    static SAMPLE1: &str = include_str!("samples/day17.txt");
 
     #[test]
     fn test_day17_parse_input() {
-        let cube = parse_input(SAMPLE1);
+        let cube = parse(SAMPLE1).unwrap();
         assert_eq!(5, cube.len());
-        assert!(cube.contains(&(0, 2, 0)));
-        assert!(cube.contains(&(1, 0, 0)));
-        assert!(cube.contains(&(1, 2, 0)));
-        assert!(cube.contains(&(2, 1, 0)));
-        assert!(cube.contains(&(2, 2, 0)));
+        assert!(cube.contains(&Point3::new(0, 2, 0)));
+        assert!(cube.contains(&Point3::new(1, 0, 0)));
+        assert!(cube.contains(&Point3::new(1, 2, 0)));
+        assert!(cube.contains(&Point3::new(2, 1, 0)));
+        assert!(cube.contains(&Point3::new(2, 2, 0)));
     }
 
     #[test]
-    fn test_day17_part1_sample1() {
-        assert_eq!(112, part1(&parse_input(SAMPLE1)));
+    fn test_day17_frames() {
+        let cube = parse(SAMPLE1).unwrap();
+        let frames = frames(&cube, 1);
+        assert_eq!(7, frames.len());
+        assert_eq!(
+            Frame::Map(Grid::parse(Cursor::new(SAMPLE1)).unwrap()),
+            frames[0]
+        );
+        let after_one_cycle = "\
+#.. #.# #..
+..# .## ..#
+.#. .#. .#.
+";
+        assert_eq!(after_one_cycle, frames[1].to_text());
+        match &frames::record(17, 2, SAMPLE1).unwrap().unwrap()[1] {
+            Frame::Map(grid) => assert_eq!((11, 11), (grid.width(), grid.height())),
+            frame => panic!("unexpected {:?}", frame),
+        }
     }
 
     #[test]
-    fn test_day17_part2_sample1() {
-        assert_eq!(848, part2(&parse_input(SAMPLE1)));
+    fn test_day17_parse_input_errors() {
+        let err = parse(".#.\n..o\n").unwrap_err();
+        assert_eq!((2, 3, "o"), (err.line, err.column, err.text.as_str()));
+        assert!(parse("...\n").is_err());
     }
 }
@@ -1,4 +1,5 @@
-use crate::lib::parse_unwrap;
+use crate::parser::Cursor;
+use crate::ParseError;
 
 #[derive(Debug, Clone)]
 enum Token {
@@ -9,7 +10,7 @@ enum Token {
 }
 
 #[derive(Debug, Clone)]
-enum Lexeme {
+pub enum Lexeme {
     Product,
     Sum,
     Num(u64),
@@ -17,21 +18,45 @@ enum Lexeme {
     ParenEnd,
 }
 
-fn lex(input: &str) -> Vec<Lexeme> {
-    input
-        .chars()
-        .filter_map(|c| match c {
-            '0'..='9' => Some(Lexeme::Num(parse_unwrap(&c.to_string()))),
-            '+' => Some(Lexeme::Sum),
-            '*' => Some(Lexeme::Product),
-            '(' => Some(Lexeme::ParenStart),
-            ')' => Some(Lexeme::ParenEnd),
-            ' ' | '\n' => None,
-            _ => {
-                panic!(format!("unexpected character '{}'", c));
+/// Splits the `line` into lexemes, checking that they form a valid expression.
+fn lex(line: &mut Cursor) -> Result<Vec<Lexeme>, ParseError> {
+    let start = *line;
+    let mut lexemes = Vec::new();
+    let mut depth = 0;
+    let mut expects_operand = true;
+    while let Some(c) = line.peek() {
+        let lexeme = match c {
+            '0'..='9' if expects_operand => Lexeme::Num(c.to_digit(10).unwrap().into()),
+            '(' if expects_operand => Lexeme::ParenStart,
+            '+' if !expects_operand => Lexeme::Sum,
+            '*' if !expects_operand => Lexeme::Product,
+            ')' if !expects_operand && depth > 0 => Lexeme::ParenEnd,
+            ' ' => {
+                line.take_char();
+                continue;
             }
-        })
-        .collect::<Vec<_>>()
+            '0'..='9' | '(' | '+' | '*' | ')' => {
+                return Err(line.error_ahead(1, "unexpected token"))
+            }
+            _ => return Err(line.error_ahead(1, "unexpected character")),
+        };
+        line.take_char();
+        match lexeme {
+            Lexeme::ParenStart => depth += 1,
+            Lexeme::ParenEnd => depth -= 1,
+            Lexeme::Num(_) => expects_operand = false,
+            Lexeme::Sum | Lexeme::Product => expects_operand = true,
+        }
+        lexemes.push(lexeme);
+    }
+    if expects_operand || depth > 0 {
+        return Err(line.error_at(start.rest(), "incomplete expression"));
+    }
+    Ok(lexemes)
+}
+
+pub fn parse(input: &str) -> Result<Vec<Vec<Lexeme>>, ParseError> {
+    Cursor::new(input).map_lines(lex)
 }
 
 fn parse_from_left(lexemes: &Vec<Lexeme>) -> Token {
@@ -53,12 +78,12 @@ fn parse_from_left(lexemes: &Vec<Lexeme>) -> Token {
     }
 
     fn parse_term(tokens: &Vec<Lexeme>, pos: isize) -> (Token, isize) {
-        match &tokens[pos as usize] {
-            &Lexeme::Num(n) => {
+        match tokens[pos as usize] {
+            Lexeme::Num(n) => {
                 let node = Token::Number(n);
                 (node, pos - 1)
             }
-            &Lexeme::ParenEnd => {
+            Lexeme::ParenEnd => {
                 let (node, next_pos) = parse_expr(tokens, pos - 1);
                 if let Some(&Lexeme::ParenStart) = tokens.get(next_pos as usize) {
                     let paren = Token::Paren(Box::new(node));
@@ -73,7 +98,7 @@ fn parse_from_left(lexemes: &Vec<Lexeme>) -> Token {
 
     // in order to generate syntax tree to be unwind sequentially,
     // it needs to be parsed backwards.
-    let (token, _) = parse_expr(&lexemes, lexemes.len() as isize - 1);
+    let (token, _) = parse_expr(lexemes, lexemes.len() as isize - 1);
     token
 }
 
@@ -101,12 +126,12 @@ fn parse_with_sum_before_mul(lexemes: &Vec<Lexeme>) -> Token {
         }
     }
     fn parse_term(tokens: &Vec<Lexeme>, pos: usize) -> (Token, usize) {
-        match &tokens[pos] {
-            &Lexeme::Num(n) => {
+        match tokens[pos] {
+            Lexeme::Num(n) => {
                 let node = Token::Number(n);
                 (node, pos + 1)
             }
-            &Lexeme::ParenStart => {
+            Lexeme::ParenStart => {
                 let (node, next_pos) = parse_expr(tokens, pos + 1);
                 if let Some(&Lexeme::ParenEnd) = tokens.get(next_pos) {
                     let paren = Token::Paren(Box::new(node));
@@ -119,7 +144,7 @@ fn parse_with_sum_before_mul(lexemes: &Vec<Lexeme>) -> Token {
         }
     }
 
-    let (token, _) = parse_expr(&lexemes, 0);
+    let (token, _) = parse_expr(lexemes, 0);
     token
 }
 
@@ -131,88 +156,85 @@ fn exec(root_token: &Token) -> u64 {
             Token::Sum(s) => match &**s {
                 (Token::Number(l), Token::Number(r)) => Token::Number(l + r),
                 (lhs, rhs) => {
-                    let sum = Token::Sum(Box::new((run(&lhs), run(&rhs))));
+                    let sum = Token::Sum(Box::new((run(lhs), run(rhs))));
                     run(&sum)
                 }
             },
             Token::Product(s) => match &**s {
                 (Token::Number(l), Token::Number(r)) => Token::Number(l * r),
                 (lhs, rhs) => {
-                    let product = Token::Product(Box::new((run(&lhs), run(&rhs))));
+                    let product = Token::Product(Box::new((run(lhs), run(rhs))));
                     run(&product)
                 }
             },
         }
     }
 
-    match run(&root_token) {
+    match run(root_token) {
         Token::Number(n) => n,
         _ => panic!(),
     }
 }
 
-fn parse_and_exec<F>(lex_lines: &Vec<Vec<Lexeme>>, f: F) -> u64
+fn parse_and_exec<F>(lex_lines: &[Vec<Lexeme>], f: F) -> u64
 where
     F: Fn(&Vec<Lexeme>) -> Token,
 {
     lex_lines
         .iter()
-        .map(|line| f(line))
+        .map(f)
         .map(|root_token| exec(&root_token))
         .sum()
 }
 
-fn part1(lex_lines: &Vec<Vec<Lexeme>>) -> u64 {
+/// Evaluates a single expression with the operator precedence of the `part`.
+pub fn evaluate(expression: &str, part: u8) -> Result<u64, ParseError> {
+    let lexemes = lex(&mut Cursor::new(expression))?;
+    let root_token = match part {
+        1 => parse_from_left(&lexemes),
+        _ => parse_with_sum_before_mul(&lexemes),
+    };
+    Ok(exec(&root_token))
+}
+
+pub fn part1(lex_lines: &[Vec<Lexeme>]) -> u64 {
     parse_and_exec(lex_lines, parse_from_left)
 }
 
-fn part2(lex_lines: &Vec<Vec<Lexeme>>) -> u64 {
+pub fn part2(lex_lines: &[Vec<Lexeme>]) -> u64 {
     parse_and_exec(lex_lines, parse_with_sum_before_mul)
 }
 
-pub fn run() {
-    let input = include_str!("input/day18.txt");
-    let lex_lines = &input.lines().map(|line| lex(line)).collect::<Vec<_>>();
-    println!("Day 18/1: {}", part1(lex_lines));
-    println!("Day 18/2: {}", part2(lex_lines));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day18;
+
+pub const SOLUTION: &dyn DynSolution = &Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+    type Parsed = Vec<Vec<Lexeme>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static SAMPLE1: &'static str = "\
-2 * 3 + (4 * 5)
-5 + (8 * 3 + 9 + 3 * 4 * 3)
-5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))
-((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2
-";
-
-    static SAMPLE2: &'static str = "\
-1 + (2 * 3) + (4 * (5 + 6)) 
-2 * 3 + (4 * 5) 
-5 + (8 * 3 + 9 + 3 * 4 * 3) 
-5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4)) 
-((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2 
-";
-
     fn p1(s: &str) -> u64 {
-        let lex_lines = &s.lines().map(|line| lex(line)).collect::<Vec<_>>();
-        part1(lex_lines)
+        part1(&parse(s).unwrap())
     }
     fn p2(s: &str) -> u64 {
-        let lex_lines = &s.lines().map(|line| lex(line)).collect::<Vec<_>>();
-        part2(lex_lines)
-    }
-
-    #[test]
-    fn test_day18_part1_sample1() {
-        assert_eq!(26 + 437 + 12240 + 13632, p1(&SAMPLE1));
-    }
-
-    #[test]
-    fn test_day18_part2_sample2() {
-        assert_eq!(51 + 46 + 1445 + 669060 + 23340, p2(&SAMPLE2));
+        part2(&parse(s).unwrap())
     }
 
     #[test]
@@ -232,4 +254,25 @@ mod tests {
         assert_eq!(669060, p2("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))"));
         assert_eq!(23340, p2("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"));
     }
+
+    #[test]
+    fn test_day18_evaluate() {
+        assert_eq!(Ok(10), evaluate("2 * 3 + 4", 1));
+        assert_eq!(Ok(14), evaluate("2 * 3 + 4", 2));
+        assert!(evaluate("1 + 2 * 3\n4", 1).is_err());
+    }
+
+    #[test]
+    fn test_day18_lex_errors() {
+        let lex_line = |s| lex(&mut Cursor::new(s));
+        let input = "1 + 2\n3 - 4";
+        let err = parse(input).unwrap_err();
+        assert_eq!((2, 3, "-"), (err.line, err.column, err.text.as_str()));
+        let err = lex_line("1 + (2 3)").unwrap_err();
+        assert_eq!((1, 8, "3"), (err.line, err.column, err.text.as_str()));
+        assert!(lex_line("1 + 2)").is_err());
+        assert!(lex_line("(1 + 2").is_err());
+        assert!(lex_line("1 +").is_err());
+        assert!(lex_line("").is_err());
+    }
 }
@@ -1,8 +1,10 @@
-use crate::lib::*;
-use std::collections::HashMap;
+use crate::parser::Cursor;
+use crate::progress;
+use crate::ParseError;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Rule {
+pub enum Rule {
     Literal(char),
     Ref(u8),
     Rec(u8),
@@ -10,43 +12,128 @@ enum Rule {
     Alt(Vec<Rule>),
 }
 
-fn parse_rules(rules: &str) -> HashMap<u8, Rule> {
-    let parse_refs = |id: u8, s: &str| {
-        let refs: Vec<Rule> = s
-            .split(' ')
-            .map(|s| {
-                let v = parse_unwrap(s);
-                if v == id {
-                    Rule::Rec(v)
-                } else {
-                    Rule::Ref(v)
-                }
-            })
-            .collect();
-        Rule::Seq(refs)
+fn parse_rule<'a>(
+    line: &mut Cursor<'a>,
+    refs: &mut Vec<Reference<'a>>,
+) -> Result<(u8, Rule), ParseError> {
+    let id = line.take_number()?;
+    line.expect_literal(": ")?;
+    if line.starts_with("\"") {
+        let literal = line.take_rest();
+        let mut chars = literal.chars();
+        return match (chars.next(), chars.next(), chars.next(), chars.next()) {
+            (Some('"'), Some(c), Some('"'), None) => Ok((id, Rule::Literal(c))),
+            _ => Err(line.error_at(literal, "expected a quoted character")),
+        };
+    }
+    let mut alts = vec![Vec::new()];
+    for mut word in line.words() {
+        if word.eat("|") {
+            word.expect_end()?;
+            alts.push(Vec::new());
+            continue;
+        }
+        let start = word;
+        let v = word.take_number()?;
+        word.expect_end()?;
+        refs.push((id, v, word.consumed_since(&start)));
+        let r = if v == id { Rule::Rec(v) } else { Rule::Ref(v) };
+        alts.last_mut().unwrap().push(r);
+    }
+    line.take_rest();
+    let rule = if alts.len() == 1 {
+        Rule::Seq(alts.pop().unwrap())
+    } else {
+        Rule::Alt(alts.into_iter().map(Rule::Seq).collect())
     };
+    Ok((id, rule))
+}
 
-    rules
-        .lines()
-        .map(|l| {
-            let (id_s, value_s) = split_once_str(l, ": ");
-            let id = parse_unwrap(id_s);
-            let rule = if value_s.contains('"') {
-                Rule::Literal(value_s.chars().nth(1).unwrap())
-            } else if value_s.contains('|') {
-                Rule::Alt(value_s.split(" | ").map(|v| parse_refs(id, v)).collect())
-            } else {
-                parse_refs(id, value_s)
-            };
-            (id, rule)
-        })
-        .collect()
+/// A reference of a rule to a rule, with its location.
+type Reference<'a> = (u8, u8, &'a str);
+
+/// Parses the rules, collecting the references to other rules with their locations.
+fn parse_rules<'a>(
+    block: Cursor<'a>,
+    refs: &mut Vec<Reference<'a>>,
+) -> Result<HashMap<u8, Rule>, ParseError> {
+    let rules = block.map_lines(|line| parse_rule(line, refs))?;
+    Ok(rules.into_iter().collect())
 }
 
-fn parse(input: &str) -> (HashMap<u8, Rule>, Vec<String>) {
-    let (rules, messages) = split_once_str(input.trim(), "\n\n");
-    let messages = messages.lines().map(|l| l.to_string()).collect();
-    (parse_rules(rules), messages)
+/// Finds a reference closing a cycle of the rules. Rules referring to themselves are not
+/// cycles, their expansion is limited, but rules referring back to each other would be
+/// expanded endlessly.
+fn find_cycle<'a>(refs: &[Reference<'a>]) -> Option<Reference<'a>> {
+    fn visit<'a>(
+        refs: &[Reference<'a>],
+        id: u8,
+        path: &mut Vec<u8>,
+        done: &mut HashSet<u8>,
+    ) -> Option<Reference<'a>> {
+        if !done.insert(id) {
+            return None;
+        }
+        path.push(id);
+        for &r in refs.iter().filter(|&&(from, to, _)| from == id && to != id) {
+            if path.contains(&r.1) {
+                return Some(r);
+            }
+            if let Some(r) = visit(refs, r.1, path, done) {
+                return Some(r);
+            }
+        }
+        path.pop();
+        None
+    }
+
+    let mut done = HashSet::new();
+    refs.iter()
+        .find_map(|&(from, _, _)| visit(refs, from, &mut Vec::new(), &mut done))
+}
+
+pub fn parse(input: &str) -> Result<(HashMap<u8, Rule>, Vec<String>), ParseError> {
+    let mut blocks = Cursor::new(input).split_blocks();
+    let b_rules = blocks.next().unwrap_or_else(|| Cursor::new(input));
+    let b_messages = blocks
+        .next()
+        .ok_or_else(|| ParseError::end_of_input(input, "missing messages"))?;
+
+    let mut refs = Vec::new();
+    let rules = parse_rules(b_rules, &mut refs)?;
+    if !rules.contains_key(&0) {
+        return Err(b_rules.error_at(b_rules.rest(), "missing rule 0"));
+    }
+    // every referenced rule has to exist
+    if let Some((_, _, r)) = refs.iter().find(|(_, id, _)| !rules.contains_key(id)) {
+        return Err(b_rules.error_at(r, "reference to a missing rule"));
+    }
+    if let Some((_, _, r)) = find_cycle(&refs) {
+        return Err(b_rules.error_at(r, "reference closing a cycle of the rules"));
+    }
+    // the rules of the second part replace the rules 8 and 11 of the input
+    let mut looping = Vec::new();
+    parse_rules(Cursor::new(UPDATED_RULES), &mut looping).expect("updated rules are valid");
+    looping.retain(|(from, _, _)| rules.contains_key(from));
+    if let Some((from, to, _)) = looping.iter().find(|(_, to, _)| !rules.contains_key(to)) {
+        let message = format!(
+            "missing rule {} which the looping rule {} refers to",
+            to, from
+        );
+        return Err(b_rules.error_at(b_rules.rest(), message));
+    }
+    let replaced = |id: u8| looping.iter().any(|&(from, _, _)| from == id);
+    refs.retain(|&(from, _, _)| !replaced(from));
+    if let Some((from, _, r)) = find_cycle(&[&refs[..], &looping[..]].concat()) {
+        // the references of the looping rules are not in the input
+        let r = if replaced(from) { b_rules.rest() } else { r };
+        return Err(b_rules.error_at(
+            r,
+            "reference closing a cycle of the rules in the second part",
+        ));
+    }
+    let messages = b_messages.lines().map(|l| l.rest().to_string()).collect();
+    Ok((rules, messages))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -63,7 +150,7 @@ fn expand(rules: &HashMap<u8, Rule>) -> Vec<Node> {
         match rule {
             Rule::Literal(c) => vec![Node::Chr(*c)],
             Rule::Alt(alt) => vec![Node::Alt(alt.iter().map(|n| exp(rules, n, rec)).collect())],
-            Rule::Seq(seq) => seq.iter().map(|n| exp(rules, n, rec)).flatten().collect(),
+            Rule::Seq(seq) => seq.iter().flat_map(|n| exp(rules, n, rec)).collect(),
             Rule::Ref(id) => exp(rules, &rules[id], rec),
             Rule::Rec(id) => exp(rules, &rules[id], rec + 1),
         }
@@ -73,19 +160,20 @@ fn expand(rules: &HashMap<u8, Rule>) -> Vec<Node> {
     exp(rules, root, 0)
 }
 
-fn is_match(nodes: &Vec<Node>, message: &str) -> bool {
+fn is_match(nodes: &[Node], message: &str) -> bool {
     fn check_ex(nodes: &[Node], msg: &[char]) -> bool {
         if let Some((node, rest)) = nodes.split_first() {
             return match node {
                 Node::Chr(c) => {
                     if let Some((m, msg_rest)) = msg.split_first() {
                         if c == m {
-                            return check_ex(&rest.to_vec(), msg_rest);
+                            return check_ex(rest, msg_rest);
                         }
                     }
                     false
                 }
                 Node::Alt(alt) => alt.iter().any(|n| {
+                    progress::check();
                     let alt_nodes = [n, rest].concat();
                     check_ex(&alt_nodes, msg)
                 }),
@@ -95,35 +183,67 @@ fn is_match(nodes: &Vec<Node>, message: &str) -> bool {
     }
 
     let msg = message.chars().collect::<Vec<char>>();
-    check_ex(&nodes, &msg)
+    check_ex(nodes, &msg)
 }
 
-fn part1(data: &(HashMap<u8, Rule>, Vec<String>)) -> usize {
+pub fn part1(data: &(HashMap<u8, Rule>, Vec<String>)) -> usize {
     let (rules, messages) = data;
-    let expanded = expand(&rules);
-    messages.iter().filter(|m| is_match(&expanded, m)).count()
+    count_matches(&expand(rules), messages)
 }
 
-fn part2(data: &(HashMap<u8, Rule>, Vec<String>)) -> usize {
-    let (rules, messages) = data;
-    let new_rules = {
-        let mut map = rules.clone();
-        let updated_rules = parse_rules("8: 42 | 42 8\n11: 42 31 | 42 11 31");
+static UPDATED_RULES: &str = "8: 42 | 42 8\n11: 42 31 | 42 11 31";
+
+/// The rules of the `part`, the second one replaces the rules 8 and 11 with looping ones.
+fn rules_of_part(rules: &HashMap<u8, Rule>, part: u8) -> HashMap<u8, Rule> {
+    let mut map = rules.clone();
+    if part == 2 {
+        let updated_rules = parse_rules(Cursor::new(UPDATED_RULES), &mut Vec::new())
+            .expect("updated rules are valid");
         for (id, rule) in updated_rules {
             map.insert(id, rule);
         }
-        map
-    };
+    }
+    map
+}
+
+fn count_matches(nodes: &[Node], messages: &[String]) -> usize {
+    let mut count = 0;
+    for (i, message) in messages.iter().enumerate() {
+        progress::report(i, messages.len());
+        count += is_match(nodes, message) as usize;
+    }
+    count
+}
 
-    let expanded = expand(&new_rules);
-    messages.iter().filter(|m| is_match(&expanded, m)).count()
+/// Checks whether the `message` matches the rule 0 with the rules of the `part`.
+pub fn matches(rules: &HashMap<u8, Rule>, message: &str, part: u8) -> bool {
+    is_match(&expand(&rules_of_part(rules, part)), message)
 }
 
-pub fn run() {
-    let input = include_str!("input/day19.txt");
-    let parsed = &parse(input);
-    println!("Day 19/1: {}", part1(parsed));
-    println!("Day 19/2: {}", part2(parsed));
+pub fn part2(data: &(HashMap<u8, Rule>, Vec<String>)) -> usize {
+    let (rules, messages) = data;
+    count_matches(&expand(&rules_of_part(rules, 2)), messages)
+}
+
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day19;
+
+pub const SOLUTION: &dyn DynSolution = &Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+    type Parsed = (HashMap<u8, Rule>, Vec<String>);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
 #[cfg(test)]
@@ -137,74 +257,11 @@ mod tests {
 3: \"b\"
 ";
 
-    static SAMPLE1: &'static str = "\
-0: 4 1 5
-1: 2 3 | 3 2
-2: 4 4 | 5 5
-3: 4 5 | 5 4
-4: \"a\"
-5: \"b\"
-
-ababbb
-bababa
-abbbab
-aaabbb
-aaaabbb
-";
-
-    static SAMPLE2: &'static str = "\
-42: 9 14 | 10 1
-9: 14 27 | 1 26
-10: 23 14 | 28 1
-1: \"a\"
-11: 42 31
-5: 1 14 | 15 1
-19: 14 1 | 14 14
-12: 24 14 | 19 1
-16: 15 1 | 14 14
-31: 14 17 | 1 13
-6: 14 14 | 1 14
-2: 1 24 | 14 4
-0: 8 11
-13: 14 3 | 1 12
-15: 1 | 14
-17: 14 2 | 1 7
-23: 25 1 | 22 14
-28: 16 1
-4: 1 1
-20: 14 14 | 1 15
-3: 5 14 | 16 1
-27: 1 6 | 14 18
-14: \"b\"
-21: 14 1 | 1 14
-25: 1 1 | 1 14
-22: 14 14
-8: 42
-26: 14 22 | 1 20
-18: 15 15
-7: 14 5 | 1 21
-24: 14 1
-
-abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
-bbabbbbaabaabba
-babbbbaabbbbbabbbbbbaabaaabaaa
-aaabbbbbbaaaabaababaabababbabaaabbababababaaa
-bbbbbbbaaaabbbbaaabbabaaa
-bbbababbbbaaaaaaaabbababaaababaabab
-ababaaaaaabaaab
-ababaaaaabbbaba
-baabbaaaabbaaaababbaababb
-abbbbabbbbaaaababbbbbbaaaababb
-aaaaabbaabaaaaababaa
-aaaabbaaaabbaaa
-aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
-babaaabbbaaabaababbaabababaaab
-aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba
-";
+    static SAMPLE2: &str = include_str!("samples/day19-2.txt");
 
     #[test]
     fn test_day19_parse_rules_sample_rules1() {
-        let rules = &parse_rules(SAMPLE_RULES1);
+        let rules = &parse_rules(Cursor::new(SAMPLE_RULES1), &mut Vec::new()).unwrap();
         let expanded = &expand(rules);
         assert_eq!(false, is_match(expanded, "a"));
         assert_eq!(false, is_match(expanded, "b"));
@@ -216,22 +273,19 @@ aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba
 
     #[test]
     fn test_day19_part1_sample1() {
-        let rules = &parse_rules(
-            "\
+        let input = "\
 0: 4 1 5
 1: 2 3 | 3 2
 2: 4 4 | 5 5
 3: 4 5 | 5 4
 4: \"a\"
 5: \"b\"
-",
-        );
+";
+        let rules = &parse_rules(Cursor::new(input), &mut Vec::new()).unwrap();
 
         assert_eq!(true, is_match(&expand(rules), "ababbb"));
         assert_eq!(true, is_match(&expand(rules), "abbbab"));
         assert_eq!(false, is_match(&expand(rules), "aaaabbb"));
-
-        assert_eq!(2, part1(&parse(SAMPLE1)));
     }
 
     #[test]
@@ -239,8 +293,9 @@ aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba
         // last example does not match because max rec depth is 4
         assert_eq!(
             4,
-            part2(&parse(
-                "\
+            part2(
+                &parse(
+                    "\
 0: 3 4
 1: \"a\"
 2: \"b\"
@@ -253,7 +308,9 @@ abababaaa
 abababababaaa
 ababababababaaa
 "
-            ))
+                )
+                .unwrap()
+            )
         );
     }
     #[test]
@@ -261,8 +318,9 @@ ababababababaaa
         // only first 8 should match
         assert_eq!(
             8,
-            part2(&parse(
-                "\
+            part2(
+                &parse(
+                    "\
 0: 3 4 4 4
 1: \"a\"
 2: \"b\"
@@ -283,12 +341,40 @@ aabb
 abbbbbbb
 aabbbbbbb
 "
-            ))
+                )
+                .unwrap()
+            )
         );
     }
 
     #[test]
-    fn test_day19_part2_sample2() {
-        assert_eq!(12, part2(&parse(SAMPLE2)));
+    fn test_day19_matches() {
+        let (rules, _) = parse(SAMPLE2).unwrap();
+        assert!(matches(&rules, "bbabbbbaabaabba", 1));
+        assert!(!matches(&rules, "babbbbaabbbbbabbbbbbaabaaabaaa", 1));
+        assert!(matches(&rules, "babbbbaabbbbbabbbbbbaabaaabaaa", 2));
+        assert!(!matches(
+            &rules,
+            "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa",
+            2
+        ));
+    }
+
+    #[test]
+    fn test_day19_parse_errors() {
+        let err = parse("0: 1 2\n1: \"a\"\n\nab\n").unwrap_err();
+        assert_eq!((1, 6, "2"), (err.line, err.column, err.text.as_str()));
+        let err = parse("0: 1\n1: \"ab\"\n\nab\n").unwrap_err();
+        assert_eq!((2, 4, "\"ab\""), (err.line, err.column, err.text.as_str()));
+        let err = parse("0: 1 | x\n1: \"a\"\n\nab\n").unwrap_err();
+        assert_eq!((1, 8, "x"), (err.line, err.column, err.text.as_str()));
+        assert!(parse("1: \"a\"\n\na\n").is_err());
+        assert!(parse("0: \"a\"\n").is_err());
+        let err = parse("0: 1\n1: 0\n\na\n").unwrap_err();
+        assert_eq!((2, 4, "0"), (err.line, err.column, err.text.as_str()));
+        assert!(parse("0: 8\n8: \"a\"\n\na\n").is_err());
+        assert!(parse("0: 8\n8: \"a\"\n42: \"a\"\n\na\n").is_ok());
+        let looping = "0: 8 11\n8: \"a\"\n11: 42 31\n42: 8\n31: \"b\"\n\nab\n";
+        assert!(parse(looping).is_err());
     }
 }
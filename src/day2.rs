@@ -1,32 +1,45 @@
+use crate::parser::Cursor;
+use crate::ParseError;
+
 struct Policy {
     c: char,
     x: usize,
     y: usize,
 }
 
-struct Line {
+pub struct Line {
     policy: Policy,
     password: String,
 }
 
-fn parse_line(s: &String) -> Line {
-    let parts: Vec<&str> = s.split(": ").collect();
-    let policy_parts: Vec<&str> = parts[0].split(' ').collect();
-    let number_part: Vec<&str> = policy_parts[0].split('-').collect();
-
-    let min = number_part[0].parse::<usize>().unwrap();
-    let max = number_part[1].parse::<usize>().unwrap();
-    let ch = policy_parts[1].chars().next().unwrap();
-    let password = String::from(parts[1]);
+fn parse_line(line: &mut Cursor) -> Result<Line, ParseError> {
+    let start = *line;
+    let min = line.take_number::<usize>()?;
+    line.expect_literal("-")?;
+    let max = line.take_number::<usize>()?;
+    if min == 0 {
+        let numbers = line.consumed_since(&start);
+        return Err(line.error_at(numbers, "positions start at 1"));
+    }
+    line.expect_literal(" ")?;
+    let ch = line
+        .take_char()
+        .ok_or_else(|| line.error("expected a letter"))?;
+    line.expect_literal(": ")?;
+    let password = line.take_rest();
 
-    Line {
+    Ok(Line {
         policy: Policy {
             c: ch,
             x: min,
             y: max,
         },
-        password: password,
-    }
+        password: String::from(password),
+    })
+}
+
+pub fn parse(input: &str) -> Result<Vec<Line>, ParseError> {
+    Cursor::new(input).map_lines(parse_line)
 }
 
 fn is_line_valid(l: &Line) -> bool {
@@ -34,8 +47,8 @@ fn is_line_valid(l: &Line) -> bool {
     l.policy.x <= count && count <= l.policy.y
 }
 
-fn part1(lines: &Vec<String>) -> usize {
-    lines.iter().map(parse_line).filter(is_line_valid).count()
+pub fn part1(lines: &[Line]) -> usize {
+    lines.iter().filter(|l| is_line_valid(l)).count()
 }
 
 fn is_line_valid_new(l: &Line) -> bool {
@@ -47,19 +60,27 @@ fn is_line_valid_new(l: &Line) -> bool {
     false
 }
 
-fn part2(lines: &Vec<String>) -> usize {
-    lines
-        .iter()
-        .map(parse_line)
-        .filter(is_line_valid_new)
-        .count()
+pub fn part2(lines: &[Line]) -> usize {
+    lines.iter().filter(|l| is_line_valid_new(l)).count()
 }
 
-use crate::lib;
+use crate::solution::{Answer, DynSolution, Solution};
 
-pub fn run() {
-    let input = include_str!("input/day2.txt");
-    let lines = &lib::to_lines(input);
-    println!("Day 2/1: {}", part1(lines));
-    println!("Day 2/2: {}", part2(lines));
+struct Day2;
+
+pub const SOLUTION: &dyn DynSolution = &Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    type Parsed = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
@@ -1,9 +1,13 @@
+use crate::explain;
+use crate::grid::Grid;
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::fmt;
 
 #[derive(Debug, Clone)]
-struct Tile {
+pub struct Tile {
     id: i64,
-    data: Vec<Vec<bool>>,
+    data: Grid<bool>,
     all_hashes: Vec<i32>,
     top_hash: i32,
     right_hash: i32,
@@ -12,97 +16,70 @@ struct Tile {
 }
 
 trait Image {
-    fn new(id: i64, data: Vec<Vec<bool>>) -> Self
+    fn new(id: i64, data: Grid<bool>) -> Self
     where
         Self: Sized;
     fn id(&self) -> i64;
-    fn data(&self) -> &Vec<Vec<bool>>;
+    fn data(&self) -> &Grid<bool>;
 
     fn width(&self) -> usize {
-        self.data()[0].len()
+        self.data().width()
     }
     fn height(&self) -> usize {
-        self.data().len()
+        self.data().height()
     }
     fn get(&self, x: usize, y: usize) -> bool {
-        self.data()[y][x]
+        self.data()[(x, y)]
     }
 
     fn rotate_right(&self) -> Self
     where
         Self: Sized,
     {
-        let mut new_data = Vec::new();
-        for x in 0..self.width() {
-            let mut new_row = Vec::new();
-            for y in (0..self.height()).rev() {
-                new_row.push(self.get(x, y));
-            }
-            new_data.push(new_row);
-        }
-        Self::new(self.id(), new_data)
+        Self::new(self.id(), self.data().rotate_right())
     }
 
     fn flip_horizontally(&self) -> Self
     where
         Self: Sized,
     {
-        let mut new_data = Vec::new();
-        for y in 0..self.height() {
-            let mut new_row = Vec::new();
-            for x in (0..self.width()).rev() {
-                new_row.push(self.get(x, y));
-            }
-            new_data.push(new_row);
-        }
-        Self::new(self.id(), new_data)
+        Self::new(self.id(), self.data().flip_horizontally())
     }
 
-    fn contains(&self, inner: &dyn Image) -> bool {
-        let contains_in_location = |ix: usize, iy: usize| {
-            for my in 0..inner.height() {
-                for mx in 0..inner.width() {
-                    if inner.get(mx, my) && !self.get(ix + mx, iy + my) {
-                        return false;
-                    }
+    fn contains_in_location(&self, inner: &dyn Image, ix: usize, iy: usize) -> bool {
+        for my in 0..inner.height() {
+            for mx in 0..inner.width() {
+                if inner.get(mx, my) && !self.get(ix + mx, iy + my) {
+                    return false;
                 }
             }
-            true
-        };
+        }
+        true
+    }
 
-        for iy in 0..self.height() - inner.height() {
-            for ix in 0..self.width() - inner.width() {
-                if contains_in_location(ix, iy) {
+    fn contains(&self, inner: &dyn Image) -> bool {
+        for iy in 0..self.height().saturating_sub(inner.height()) {
+            for ix in 0..self.width().saturating_sub(inner.width()) {
+                if self.contains_in_location(inner, ix, iy) {
                     return true;
                 }
             }
         }
-        return false;
+        false
     }
 
     fn remove(&self, other: &dyn Image) -> Self
     where
         Self: Sized,
     {
-        let contains_in_location = |ix: usize, iy: usize| {
-            for my in 0..other.height() {
-                for mx in 0..other.width() {
-                    if other.get(mx, my) && !self.get(ix + mx, iy + my) {
-                        return false;
-                    }
-                }
-            }
-            true
-        };
-
         let mut new_data = self.data().clone();
-        for iy in 0..self.height() - other.height() {
-            for ix in 0..self.width() - other.width() {
-                if contains_in_location(ix, iy) {
+        for iy in 0..self.height().saturating_sub(other.height()) {
+            for ix in 0..self.width().saturating_sub(other.width()) {
+                if self.contains_in_location(other, ix, iy) {
                     for my in 0..other.height() {
                         for mx in 0..other.width() {
                             if other.get(mx, my) {
-                                new_data[iy + my][ix + mx] = false;
+                                new_data[(ix + mx, iy + my)] = false;
                             }
                         }
                     }
@@ -113,27 +90,7 @@ trait Image {
     }
 
     fn count_by_value(&self, value: bool) -> usize {
-        let mut count = 0;
-        for y in 0..self.height() {
-            for x in 0..self.width() {
-                if self.get(x, y) == value {
-                    count += 1;
-                }
-            }
-        }
-        count
-    }
-
-    fn format_data(&self) -> String {
-        self.data()
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|c| if *c { '#' } else { '.' })
-                    .collect::<String>()
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
+        self.data().iter().filter(|&&v| v == value).count()
     }
 
     fn iter_variations(&self) -> ImageVariationsIter<Self>
@@ -174,12 +131,38 @@ impl<T: Image + Clone> Iterator for ImageVariationsIter<T> {
     }
 }
 
+/// How the variation of the `ImageVariationsIter` at the `index` differs from the original.
+fn variation_name(index: usize) -> &'static str {
+    const NAMES: [&str; 9] = [
+        "rotated 90° clockwise",
+        "rotated 180°",
+        "rotated 270° clockwise",
+        "as drawn",
+        "flipped horizontally",
+        "flipped horizontally, rotated 90° clockwise",
+        "flipped horizontally, rotated 180°",
+        "flipped horizontally, rotated 270° clockwise",
+        "flipped horizontally",
+    ];
+    NAMES[index]
+}
+
+/// States the ids of the corner tiles.
+fn explain_corners(corners: &[&Tile]) {
+    if explain::enabled() {
+        let mut ids: Vec<i64> = corners.iter().map(|tile| tile.id).collect();
+        ids.sort_unstable();
+        let ids: Vec<String> = ids.iter().map(i64::to_string).collect();
+        explain::fact("corners", ids.join(", "));
+    }
+}
+
 impl Image for Tile {
-    fn new(id: i64, data: Vec<Vec<bool>>) -> Tile {
-        let top = &data[0];
-        let right = &data.iter().map(|row| row[row.len() - 1]).collect();
-        let bottom = &data.last().unwrap();
-        let left = &data.iter().map(|row| row[0]).collect();
+    fn new(id: i64, data: Grid<bool>) -> Tile {
+        let top = data.row(0);
+        let right = &data.column(data.width() - 1).copied().collect::<Vec<_>>();
+        let bottom = data.row(data.height() - 1);
+        let left = &data.column(0).copied().collect::<Vec<_>>();
 
         let top_hash = Tile::calc_border_hash(top);
         let right_hash = Tile::calc_border_hash(right);
@@ -212,7 +195,7 @@ impl Image for Tile {
         self.id
     }
 
-    fn data(&self) -> &Vec<Vec<bool>> {
+    fn data(&self) -> &Grid<bool> {
         &self.data
     }
 }
@@ -223,14 +206,14 @@ impl Tile {
         (value as i32) << index
     }
 
-    fn calc_border_rev_hash(vec: &Vec<bool>) -> i32 {
+    fn calc_border_rev_hash(vec: &[bool]) -> i32 {
         vec.iter()
             .rev()
             .enumerate()
             .fold(0, |acc, (i, b)| acc | Tile::set_bit(*b, i))
     }
 
-    fn calc_border_hash(vec: &Vec<bool>) -> i32 {
+    fn calc_border_hash(vec: &[bool]) -> i32 {
         vec.iter()
             .enumerate()
             .fold(0, |acc, (i, b)| acc | Tile::set_bit(*b, i))
@@ -240,7 +223,7 @@ impl Tile {
         self.all_hashes.iter().any(|h| other.all_hashes.contains(h))
     }
 
-    pub fn count_matching_sides(&self, other_tiles: &Vec<Tile>) -> usize {
+    pub fn count_matching_sides(&self, other_tiles: &[Tile]) -> usize {
         other_tiles
             .iter()
             .map(|t| t.id != self.id && self.any_side_matches(t))
@@ -248,158 +231,160 @@ impl Tile {
             .count()
     }
 
-    pub fn match_left_hash(&self, target: i32) -> Tile {
-        for var_tile in self.iter_variations() {
-            if var_tile.left_hash == target {
-                return var_tile;
-            }
-        }
-        panic!("No left_hash match found!");
+    pub fn match_left_hash(&self, target: i32) -> Option<Tile> {
+        self.iter_variations()
+            .find(|var_tile| var_tile.left_hash == target)
     }
-    pub fn match_top_hash(&self, target: i32) -> Tile {
-        for var_tile in self.iter_variations() {
-            if var_tile.top_hash == target {
-                return var_tile;
-            }
-        }
-        panic!("No top_hash match found!");
+    pub fn match_top_hash(&self, target: i32) -> Option<Tile> {
+        self.iter_variations()
+            .find(|var_tile| var_tile.top_hash == target)
     }
 
-    fn parse(input: &str) -> Tile {
-        let mut lines = input.lines();
-        let id = lines
-            .next()
-            .unwrap()
-            .replace(|c| !char::is_numeric(c), "")
-            .parse::<i64>()
-            .unwrap();
-        let data = lines
-            .map(|l| l.chars().map(|c| c == '#').collect())
-            .collect();
+    fn parse(mut tile: Cursor) -> Result<Tile, ParseError> {
+        let start = tile;
+        let mut header = tile.expect_line("missing tile")?;
+        header.expect_literal("Tile ")?;
+        let id = header.take_number::<i64>()?;
+        header.expect_literal(":")?;
+        header.expect_end()?;
+        let data = Grid::parse(tile)?;
+        if data.width() != data.height() {
+            return Err(tile.error_at(start.rest(), "tile is not a square"));
+        }
+        // borders are hashed into bits of i32
+        if data.width() > 31 {
+            return Err(tile.error_at(start.rest(), "tile is too large"));
+        }
 
-        Tile::new(id, data)
+        Ok(Tile::new(id, data))
     }
 }
 
 struct ActualImage {
-    data: Vec<Vec<bool>>,
+    data: Grid<bool>,
 }
 
 impl Image for ActualImage {
-    fn new(_id: i64, data: Vec<Vec<bool>>) -> Self {
+    fn new(_id: i64, data: Grid<bool>) -> Self {
         ActualImage { data }
     }
     fn id(&self) -> i64 {
         -1
     }
-    fn data(&self) -> &Vec<Vec<bool>> {
+    fn data(&self) -> &Grid<bool> {
         &self.data
     }
 }
 
 impl ActualImage {
-    pub fn from_tiles(tiles: &Vec<Vec<Tile>>) -> ActualImage {
-        let mut data = Vec::new();
-
-        for tiles_row in tiles {
-            let width = tiles_row[0].width();
-            let height = tiles_row[0].height();
-
-            for y in 1..height - 1 {
-                let mut img_row = Vec::new();
-                for tile in tiles_row {
-                    for x in 1..width - 1 {
-                        img_row.push(tile.get(x, y));
-                    }
-                }
-                data.push(img_row);
-            }
-        }
-
+    pub fn from_tiles(tiles: &[Vec<Tile>]) -> ActualImage {
+        // borders of the tiles are dropped
+        let inner = tiles[0][0].width().saturating_sub(2);
+        let data = Grid::from_fn(tiles[0].len() * inner, tiles.len() * inner, |x, y| {
+            tiles[y / inner][x / inner].get(x % inner + 1, y % inner + 1)
+        });
         ActualImage::new(-1, data)
     }
 }
 
 #[derive(Clone)]
 struct Monster {
-    data: Vec<Vec<bool>>,
+    data: Grid<bool>,
 }
 
 impl Image for Monster {
-    fn new(_: i64, data: Vec<Vec<bool>>) -> Self {
+    fn new(_: i64, data: Grid<bool>) -> Self {
         Monster { data }
     }
     fn id(&self) -> i64 {
         -2
     }
-    fn data(&self) -> &Vec<Vec<bool>> {
+    fn data(&self) -> &Grid<bool> {
         &self.data
     }
 }
 
 impl Default for Monster {
     fn default() -> Self {
-        const STR: [&'static str; 3] = [
+        const STR: [&str; 3] = [
             "                  # ",
             "#    ##    ##    ###",
             " #  #  #  #  #  #   ",
         ];
-        let data: Vec<Vec<bool>> = STR
-            .iter()
-            .map(|l| l.chars().map(|c| c == '#').collect())
-            .collect();
+        let data = Grid::from_fn(STR[0].len(), STR.len(), |x, y| STR[y].as_bytes()[x] == b'#');
         Self::new(-2, data)
     }
 }
 
 impl fmt::Display for Tile {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "Tile {}:\n{}", self.id, self.format_data())
+        write!(f, "Tile {}:\n{}", self.id, self.data)
     }
 }
 
 impl fmt::Display for ActualImage {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "{}", self.format_data())
+        write!(f, "{}", self.data)
     }
 }
 
 impl fmt::Display for Monster {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "{}", self.format_data())
+        write!(f, "{}", self.data)
     }
 }
 
-fn parse(input: &str) -> Vec<Tile> {
-    input.trim().split("\n\n").map(Tile::parse).collect()
+pub fn parse(input: &str) -> Result<Vec<Tile>, ParseError> {
+    let mut size = None;
+    Cursor::new(input.trim_start())
+        .split_blocks()
+        .map(|block| {
+            let tile = Tile::parse(block)?;
+            if *size.get_or_insert(tile.width()) != tile.width() {
+                return Err(block.error_at(block.rest(), "tiles differ in size"));
+            }
+            Ok(tile)
+        })
+        .collect()
 }
 
-fn part1(data: &Vec<Tile>) -> i64 {
-    data.iter()
+pub fn part1(data: &[Tile]) -> i64 {
+    let corners = data
+        .iter()
         .filter(|t| t.count_matching_sides(data) == 2)
-        .map(|tile| tile.id as i64)
-        .product()
+        .collect::<Vec<&Tile>>();
+    explain_corners(&corners);
+    corners.iter().map(|tile| tile.id).product()
 }
 
-fn part2(data: &Vec<Tile>) -> usize {
+/// The roughness of the sea, none when the tiles do not form an image with a sea monster.
+pub fn part2(data: &[Tile]) -> Option<usize> {
     let corners = data
         .iter()
         .filter(|t| t.count_matching_sides(data) == 2)
         .collect::<Vec<&Tile>>();
+    explain_corners(&corners);
 
     let (top_left, mut rest) = {
-        let mut corner_tile = corners[0].clone();
+        let mut corner_tile = (*corners.first()?).clone();
         let others = data
-            .into_iter()
+            .iter()
             .filter(|x| x.id != corner_tile.id)
             .collect::<Vec<_>>();
 
         let is_matching_others_by_hash = |h| others.iter().any(|o| o.all_hashes.contains(&h));
 
+        // the matching sides of a corner are adjacent, one of the rotations has them right
+        // and at the bottom
+        let mut rotations = 0;
         while is_matching_others_by_hash(corner_tile.top_hash)
             || is_matching_others_by_hash(corner_tile.left_hash)
         {
+            if rotations == 3 {
+                return None;
+            }
             corner_tile = corner_tile.rotate_right();
+            rotations += 1;
         }
 
         (corner_tile, others)
@@ -414,7 +399,7 @@ fn part2(data: &Vec<Tile>) -> usize {
             .find(|x| x.all_hashes.contains(&prev_right_hash));
 
         if let Some(next) = next_opt {
-            let next = next.match_left_hash(prev_right_hash);
+            let next = next.match_left_hash(prev_right_hash)?;
             prev_right_hash = next.right_hash;
             rest.retain(|x| x.id != next.id);
             first_row.push(next);
@@ -428,14 +413,13 @@ fn part2(data: &Vec<Tile>) -> usize {
 
     while !rest.is_empty() {
         let mut row = Vec::new();
-        for i in 0..prev_row.len() {
-            let prev_hash = prev_row[i].bottom_hash;
+        for prev in &prev_row {
+            let prev_hash = prev.bottom_hash;
 
             let next = rest
                 .iter()
-                .find(|x| x.all_hashes.contains(&prev_hash))
-                .unwrap();
-            let next = next.match_top_hash(prev_hash);
+                .find(|x| x.all_hashes.contains(&prev_hash))?
+                .match_top_hash(prev_hash)?;
             rest.retain(|x| x.id != next.id);
             row.push(next);
         }
@@ -448,39 +432,93 @@ fn part2(data: &Vec<Tile>) -> usize {
 
     let img_without_monster = {
         let mut result = None;
-        for var_monster in Monster::default().iter_variations() {
+        for (index, var_monster) in Monster::default().iter_variations().enumerate() {
             if img.contains(&var_monster) {
+                explain::fact("monster", variation_name(index));
                 result = Some(img.remove(&var_monster));
                 break;
             }
         }
         result
-    }
-    .unwrap();
+    }?;
 
-    let habitat = img_without_monster.count_by_value(true);
-    habitat
+    Some(img_without_monster.count_by_value(true))
 }
 
-pub fn run() {
-    let input = include_str!("input/day20.txt");
-    let parsed = &parse(input);
-    println!("Day 20/1: {}", part1(parsed));
-    println!("Day 20/2: {}", part2(parsed));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day20;
+
+pub const SOLUTION: &dyn DynSolution = &Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+    type Parsed = Vec<Tile>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    fn sentinel(part: u8) -> Option<Answer> {
+        if part == 2 {
+            Some(Answer::None)
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::explain::Fact;
+
+    #[test]
+    fn test_day20_explain() {
+        let tiles = parse(include_str!("samples/day20.txt")).unwrap();
+        let (answer, facts) = explain::collect(|| part2(&tiles));
+        assert_eq!(Some(273), answer);
+        assert_eq!(
+            vec!["corners: 1171, 1951, 2971, 3079", "monster: as drawn"],
+            facts.iter().map(Fact::to_string).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_day20_parse_errors() {
+        let err = parse("Tile 1:\n#.\n.#\n\nTile x:\n#.\n.#\n").unwrap_err();
+        assert_eq!((5, 6, "x"), (err.line, err.column, err.text.as_str()));
+        let err = parse("Tile 1:\n#.\n.\n").unwrap_err();
+        assert_eq!((3, 1, "."), (err.line, err.column, err.text.as_str()));
+        let err = parse("Tile 1:\n#.\n.o\n").unwrap_err();
+        assert_eq!((3, 2, "o"), (err.line, err.column, err.text.as_str()));
+        assert!(parse("Tile 1:\n#.\n").is_err());
+        let err = parse("Tile 1:\n#.\n.#\n\nTile 2:\n#..\n.#.\n..#\n").unwrap_err();
+        assert_eq!(
+            (5, 1, "tiles differ in size"),
+            (err.line, err.column, err.message.as_str())
+        );
+    }
 
     #[test]
-    fn test_day20_part2_sample1() {
-        assert_eq!(273, part2(&parse(SAMPLE1)));
+    fn test_day20_part2_no_image() {
+        assert_eq!(None, part2(&[]));
+        // two tiles without corners and a tile without a monster
+        assert_eq!(
+            None,
+            part2(&parse("Tile 1:\n#.\n.#\n\nTile 2:\n..\n##\n").unwrap())
+        );
+        assert_eq!(None, part2(&parse(TINY_TILE).unwrap()));
     }
 
     #[test]
     fn test_day20_image_rotate_right() {
-        let t = Tile::parse(TINY_TILE);
+        let t = Tile::parse(Cursor::new(TINY_TILE)).unwrap();
 
         assert_eq!(
             "\
@@ -495,7 +533,7 @@ Tile 123:
 
     #[test]
     fn test_day20_image_flip_horizontally() {
-        let t = Tile::parse(TINY_TILE);
+        let t = Tile::parse(Cursor::new(TINY_TILE)).unwrap();
 
         assert_eq!(
             "\
@@ -522,125 +560,10 @@ Tile 123:
         );
     }
 
-    #[test]
-    fn test_day20_part1_sample1() {
-        assert_eq!(20899048083289, part1(&parse(SAMPLE1)));
-    }
-
     static TINY_TILE: &'static str = "\
 Tile 123:
 #..
 #..
 ###
-";
-
-    static SAMPLE1: &'static str = "\
-Tile 2311:
-..##.#..#.
-##..#.....
-#...##..#.
-####.#...#
-##.##.###.
-##...#.###
-.#.#.#..##
-..#....#..
-###...#.#.
-..###..###
-
-Tile 1951:
-#.##...##.
-#.####...#
-.....#..##
-#...######
-.##.#....#
-.###.#####
-###.##.##.
-.###....#.
-..#.#..#.#
-#...##.#..
-
-Tile 1171:
-####...##.
-#..##.#..#
-##.#..#.#.
-.###.####.
-..###.####
-.##....##.
-.#...####.
-#.##.####.
-####..#...
-.....##...
-
-Tile 1427:
-###.##.#..
-.#..#.##..
-.#.##.#..#
-#.#.#.##.#
-....#...##
-...##..##.
-...#.#####
-.#.####.#.
-..#..###.#
-..##.#..#.
-
-Tile 1489:
-##.#.#....
-..##...#..
-.##..##...
-..#...#...
-#####...#.
-#..#.#.#.#
-...#.#.#..
-##.#...##.
-..##.##.##
-###.##.#..
-
-Tile 2473:
-#....####.
-#..#.##...
-#.##..#...
-######.#.#
-.#...#.#.#
-.#########
-.###.#..#.
-########.#
-##...##.#.
-..###.#.#.
-
-Tile 2971:
-..#.#....#
-#...###...
-#.#.###...
-##.##..#..
-.#####..##
-.#..####.#
-#..#.#..#.
-..####.###
-..#.#.###.
-...#.#.#.#
-
-Tile 2729:
-...#.#.#.#
-####.#....
-..#.#.....
-....#..#.#
-.##..##.#.
-.#.####...
-####.#.#..
-##.####...
-##..#.##..
-#.##...##.
-
-Tile 3079:
-#.#.#####.
-.#..######
-..#.......
-######....
-####.#..#.
-.#...#.##.
-#.#####.##
-..#.###...
-..#.......
-..#.###...
 ";
 }
@@ -1,6 +1,9 @@
+use crate::explain;
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::collections::*;
 
-type Foods = Vec<Food>;
+pub type Foods = Vec<Food>;
 
 #[derive(PartialEq, Eq, Hash, Debug)]
 struct Ingredient(String);
@@ -9,33 +12,26 @@ struct Ingredient(String);
 struct Allergen(String);
 
 #[derive(PartialEq, Eq, Hash, Debug)]
-struct Food(Vec<Ingredient>, Vec<Allergen>);
-
-fn parse(input: &str) -> Foods {
-    let contains_len = "contains ".len();
-    input
-        .lines()
-        .map(|line| {
-            let sep = line.find('(').unwrap();
-            let ingredients_str = &line[..sep - 1];
-            let allergens_str = {
-                let tmp = &line[sep..];
-                &tmp[1 + contains_len..tmp.len() - 1]
-            };
-            let ingredients = ingredients_str
-                .split(' ')
-                .map(|x| Ingredient(x.to_string()))
-                .collect();
-            let allergens = allergens_str
-                .split(", ")
-                .map(|x| Allergen(x.to_string()))
-                .collect();
-            Food(ingredients, allergens)
-        })
-        .collect()
+pub struct Food(Vec<Ingredient>, Vec<Allergen>);
+
+pub fn parse(input: &str) -> Result<Foods, ParseError> {
+    Cursor::new(input).map_lines(|line| {
+        let ingredients_str = line.take_until(" (contains ")?;
+        let allergens_str = line.take_until(")")?;
+        let ingredients = ingredients_str
+            .split(' ')
+            .map(|x| Ingredient(x.to_string()))
+            .collect();
+        let allergens = allergens_str
+            .split(", ")
+            .map(|x| Allergen(x.to_string()))
+            .collect();
+        Ok(Food(ingredients, allergens))
+    })
 }
 
-fn find_matches(foods: &Foods) -> HashMap<&Ingredient, &Allergen> {
+/// The ingredient of every allergen, none when the foods do not tell them apart.
+fn find_matches(foods: &Foods) -> Option<HashMap<&Ingredient, &Allergen>> {
     let mut potentially_allergic_ingredients_map =
         HashMap::<&Allergen, HashMap<&Ingredient, u32>>::new();
     for Food(ingredients, allergens) in foods {
@@ -63,7 +59,7 @@ fn find_matches(foods: &Foods) -> HashMap<&Ingredient, &Allergen> {
                         .map(|(k, v)| (*k, *v))
                         .collect::<HashMap<&Ingredient, u32>>();
 
-                    let max_freq = freq_map_except_already_guessed.values().max().unwrap();
+                    let max_freq = freq_map_except_already_guessed.values().max()?;
 
                     let most_freq_ingredients = freq_map_except_already_guessed
                         .iter()
@@ -82,82 +78,119 @@ fn find_matches(foods: &Foods) -> HashMap<&Ingredient, &Allergen> {
                     }
                 });
 
-        if let Some((ingredient, allergen)) = matching_tuple_opt {
-            potentially_allergic_ingredients_map.remove(allergen);
-            match_map.insert(ingredient, allergen);
-        }
+        let (ingredient, allergen) = matching_tuple_opt?;
+        potentially_allergic_ingredients_map.remove(allergen);
+        match_map.insert(ingredient, allergen);
     }
 
-    match_map
+    if explain::enabled() {
+        let mut pairs: Vec<_> = match_map.iter().collect();
+        pairs.sort_by_key(|(_, allergen)| **allergen);
+        for (Ingredient(ingredient), Allergen(allergen)) in pairs {
+            explain::fact(
+                "allergen",
+                format_args!("{} contains {}", ingredient, allergen),
+            );
+        }
+    }
+    Some(match_map)
 }
 
-fn part1(foods: &Foods) -> usize {
-    let matches = find_matches(foods);
+pub fn part1(foods: &Foods) -> Option<usize> {
+    let matches = find_matches(foods)?;
 
     let all_ingredients: Vec<&Ingredient> = foods
         .iter()
-        .map(|Food(ingredients, _)| ingredients)
-        .flatten()
+        .flat_map(|Food(ingredients, _)| ingredients)
         .collect();
 
-    all_ingredients
+    let count = all_ingredients
         .into_iter()
         .filter(|i| !matches.contains_key(i))
-        .count()
+        .count();
+    Some(count)
 }
 
-fn part2(foods: &Foods) -> String {
-    let matches = find_matches(foods);
+pub fn part2(foods: &Foods) -> Option<String> {
+    let matches = find_matches(foods)?;
 
     let mut ingredient_allergen_vec: Vec<_> = matches.into_iter().collect();
     ingredient_allergen_vec.sort_by_key(|(_, allergen)| *allergen);
-    ingredient_allergen_vec
+    let list = ingredient_allergen_vec
         .iter()
         .map(|(Ingredient(ingredient_string), _)| ingredient_string.clone())
         .collect::<Vec<_>>()
-        .join(",")
+        .join(",");
+    Some(list)
 }
 
-pub fn run() {
-    let input = include_str!("input/day21.txt");
-    let parsed = &parse(input);
-    println!("Day 21/1: {}", part1(parsed));
-    println!("Day 21/2: {}", part2(parsed));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day21;
+
+pub const SOLUTION: &dyn DynSolution = &Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+    type Parsed = Foods;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    fn sentinel(_part: u8) -> Option<Answer> {
+        Some(Answer::None)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static SAMPLE1: &'static str = "\
-mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
-trh fvjkl sbzzf mxmxvkd (contains dairy)
-sqjhc fvjkl (contains soy)
-sqjhc mxmxvkd sbzzf (contains fish)
-";
+    use crate::explain::Fact;
 
     #[test]
     fn test_day21_parse() {
         assert_eq!(
-            vec![Food(
+            Ok(vec![Food(
                 vec![
                     Ingredient("abc".to_string()),
                     Ingredient("def".to_string()),
                     Ingredient("ghi".to_string())
                 ],
                 vec![Allergen("asdf".to_string()), Allergen("qwer".to_string())]
-            )],
+            )]),
             parse("abc def ghi (contains asdf, qwer)")
         );
+        let err = parse("abc (contains asdf)\nabc def\n").unwrap_err();
+        assert_eq!((2, 1, "abc def"), (err.line, err.column, err.text.as_str()));
+        let err = parse("abc (contains asdf").unwrap_err();
+        assert_eq!((1, 15, "asdf"), (err.line, err.column, err.text.as_str()));
     }
 
     #[test]
-    fn test_day21_part1_sample1() {
-        assert_eq!(5, part1(&parse(SAMPLE1)));
+    fn test_day21_explain() {
+        let foods = parse(include_str!("samples/day21.txt")).unwrap();
+        let (answer, facts) = explain::collect(|| part2(&foods));
+        assert_eq!(Some("mxmxvkd,sqjhc,fvjkl".to_string()), answer);
+        assert_eq!(
+            vec![
+                "allergen: mxmxvkd contains dairy",
+                "allergen: sqjhc contains fish",
+                "allergen: fvjkl contains soy",
+            ],
+            facts.iter().map(Fact::to_string).collect::<Vec<_>>()
+        );
     }
 
     #[test]
-    fn test_day21_part2_sample1() {
-        assert_eq!("mxmxvkd,sqjhc,fvjkl", part2(&parse(SAMPLE1)));
+    fn test_day21_ambiguous_allergens() {
+        let foods = parse("abc def ghi (contains asdf, qwer)").unwrap();
+        assert_eq!(None, part1(&foods));
+        assert_eq!(None, part2(&foods));
     }
 }
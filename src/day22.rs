@@ -1,13 +1,27 @@
-use crate::lib::*;
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::collections::BTreeSet;
 
-type Cards = Vec<u8>;
+pub type Cards = Vec<u8>;
 
-fn parse(input: &str) -> (Cards, Cards) {
-    let (p1str, p2str) = split_once_str(input, "\n\n");
-    let p1 = parse_to_vec(p1str.lines().skip(1));
-    let p2 = parse_to_vec(p2str.lines().skip(1));
-    (p1, p2)
+fn parse_deck(mut deck: Cursor, player: u8) -> Result<Cards, ParseError> {
+    let header = format!("Player {}:", player);
+    let line = deck.expect_line("missing player")?;
+    if line.rest() != header {
+        return Err(line.error(format!("expected '{}'", header)));
+    }
+    deck.map_lines(Cursor::take_number)
+}
+
+pub fn parse(input: &str) -> Result<(Cards, Cards), ParseError> {
+    let mut decks = Cursor::new(input).split_blocks();
+    let mut next_deck = |player| {
+        let deck = decks
+            .next()
+            .ok_or_else(|| ParseError::end_of_input(input, "missing deck"))?;
+        parse_deck(deck, player)
+    };
+    Ok((next_deck(1)?, next_deck(2)?))
 }
 
 fn calc_score(cards: &[u8]) -> usize {
@@ -19,9 +33,11 @@ fn calc_score(cards: &[u8]) -> usize {
         .sum()
 }
 
-fn part1(parsed: &(Cards, Cards)) -> usize {
-    let mut player1: Cards = parsed.0.clone().into();
-    let mut player2: Cards = parsed.1.clone().into();
+/// The score of the winner, none when the game repeats a round and so never ends.
+pub fn part1(parsed: &(Cards, Cards)) -> Option<usize> {
+    let mut player1: Cards = parsed.0.clone();
+    let mut player2: Cards = parsed.1.clone();
+    let mut previous_rounds: BTreeSet<(Cards, Cards)> = BTreeSet::new();
     let winning_cards: Cards = loop {
         if player1.is_empty() {
             break player2;
@@ -29,6 +45,9 @@ fn part1(parsed: &(Cards, Cards)) -> usize {
         if player2.is_empty() {
             break player1;
         }
+        if !previous_rounds.insert((player1.clone(), player2.clone())) {
+            return None;
+        }
         let c1 = player1.remove(0);
         let c2 = player2.remove(0);
         if c1 > c2 {
@@ -39,10 +58,10 @@ fn part1(parsed: &(Cards, Cards)) -> usize {
             player2.push(c1);
         }
     };
-    calc_score(&winning_cards)
+    Some(calc_score(&winning_cards))
 }
 
-fn part2(parsed: &(Cards, Cards)) -> usize {
+pub fn part2(parsed: &(Cards, Cards)) -> usize {
     enum Winner {
         Player1(Cards),
         Player2(Cards),
@@ -62,7 +81,7 @@ fn part2(parsed: &(Cards, Cards)) -> usize {
             let card1 = p1.remove(0);
             let card2 = p2.remove(0);
 
-            match {
+            let winner = {
                 let (c1u, c2u) = (card1 as usize, card2 as usize);
                 if p1.len() >= c1u && p2.len() >= c2u {
                     play(&p1[..c1u], &p2[..c2u]) // subgame
@@ -71,7 +90,8 @@ fn part2(parsed: &(Cards, Cards)) -> usize {
                 } else {
                     Winner::Player2(vec![])
                 }
-            } {
+            };
+            match winner {
                 Winner::Player1(_) => {
                     p1.push(card1);
                     p1.push(card2);
@@ -82,7 +102,7 @@ fn part2(parsed: &(Cards, Cards)) -> usize {
                 }
             }
         }
-    };
+    }
 
     let (player1, player2) = parsed;
     let winner = play(player1, player2);
@@ -91,56 +111,54 @@ fn part2(parsed: &(Cards, Cards)) -> usize {
     }
 }
 
-pub fn run() {
-    let input = include_str!("input/day22.txt");
-    let parsed = &parse(input);
-    println!("Day 22/1: {}", part1(parsed));
-    println!("Day 22/2: {}", part2(parsed));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day22;
+
+pub const SOLUTION: &dyn DynSolution = &Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+    type Parsed = (Cards, Cards);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    fn sentinel(part: u8) -> Option<Answer> {
+        if part == 1 {
+            Some(Answer::None)
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static SAMPLE1: &'static str = "\
-Player 1:
-9
-2
-6
-3
-1
-
-Player 2:
-5
-8
-4
-7
-10
-";
-
-    static TINY_SAMPLE: &'static str = "\
-Player 1:
-43
-19
-
-Player 2:
-2
-29
-14
-";
-
-    #[test]
-    fn test_day22_part1_sample1() {
-        assert_eq!(306, part1(&parse(SAMPLE1)));
-    }
-
     #[test]
-    fn test_day22_part2_sample1() {
-        assert_eq!(291, part2(&parse(SAMPLE1)));
+    fn test_day22_parse_errors() {
+        let err = parse("Player 1:\n9\n2\n\nPlayer 2:\n5\n-8\n").unwrap_err();
+        assert_eq!((7, 1, "-8"), (err.line, err.column, err.text.as_str()));
+        let err = parse("Player 1:\n9\n\nPlayer 3:\n5\n").unwrap_err();
+        assert_eq!(
+            "line 4, column 1: expected 'Player 2:' in 'Player 3:'",
+            err.to_string()
+        );
+        assert!(parse("Player 1:\n9\n").is_err());
     }
 
     #[test]
-    fn test_day22_part2_tiny_sample() {
-        assert_eq!(105, part2(&parse(TINY_SAMPLE)));
+    fn test_day22_part1_infinite_game() {
+        let decks = parse("Player 1:\n43\n19\n\nPlayer 2:\n2\n29\n14\n").unwrap();
+        assert_eq!(None, part1(&decks));
+        assert_eq!(105, part2(&decks));
     }
 }
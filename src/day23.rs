@@ -1,21 +1,37 @@
-use crate::lib::parse_unwrap;
+use crate::parser::Cursor;
+use crate::progress;
+use crate::ParseError;
 use std::collections::VecDeque;
 
-fn parse(input: &str) -> Vec<u32> {
-    input
-        .chars()
-        .map(|c| parse_unwrap(&c.to_string()))
-        .collect()
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut line = Cursor::new(input).expect_line("missing cup labels")?;
+    let labels = line.rest();
+    let mut cups = Vec::new();
+    while let Some(c) = line.peek() {
+        let label = c
+            .to_digit(10)
+            .ok_or_else(|| line.error_ahead(1, "expected a digit"))?;
+        cups.push(label);
+        line.take_char();
+    }
+    // every move picks three cups besides the current one and the destination
+    let mut sorted = cups.clone();
+    sorted.sort_unstable();
+    if cups.len() < 5 || sorted.iter().zip(1..).any(|(&cup, label)| cup != label) {
+        return Err(line.error_at(labels, "expected cups labeled from 1 to N, N >= 5"));
+    }
+    Ok(cups)
 }
 
-fn calc_moves(count: usize, parsed: &Vec<u32>) -> u32 {
+fn calc_moves(count: usize, parsed: &[u32]) -> u32 {
     let min = *parsed.iter().min().unwrap();
     let max = *parsed.iter().max().unwrap();
 
-    let mut cups: VecDeque<u32> = parsed.clone().into();
+    let mut cups: VecDeque<u32> = parsed.iter().copied().collect();
     let mut picked = VecDeque::with_capacity(3);
 
     for _ in 0..count {
+        progress::check();
         let current = cups.pop_front().unwrap();
         picked.push_front(cups.pop_front().unwrap());
         picked.push_front(cups.pop_front().unwrap());
@@ -49,23 +65,28 @@ fn calc_moves(count: usize, parsed: &Vec<u32>) -> u32 {
         .fold(0, |acc, (i, x)| acc + x * (10u32.pow(i as u32)))
 }
 
-fn part1(parsed: &Vec<u32>) -> u32 {
-    calc_moves(100, parsed)
+/// Number of moves of the first part.
+const PART1_MOVES: usize = 100;
+/// Number of cups of the second part, the labeled ones are followed by the next numbers.
+const PART2_CUPS: usize = 1_000_000;
+/// Number of moves of the second part.
+const PART2_MOVES: usize = 10_000_000;
+/// Most cups of the second part, each of them has a place in two lists.
+const MAX_CUPS: usize = 10_000_000;
+
+pub fn part1_with_moves(parsed: &[u32], moves: usize) -> u32 {
+    calc_moves(moves, parsed)
 }
 
-fn part2(parsed: &Vec<u32>) -> usize {
-    let extend = 1_000_000_usize;
-    let count = 10_000_000_usize;
+pub fn part1(parsed: &[u32]) -> u32 {
+    part1_with_moves(parsed, PART1_MOVES)
+}
 
-    let cups_list = {
-        let parsed_max = *parsed.iter().max().unwrap() as usize;
-        let mut cups_list: Vec<usize> = parsed.iter().map(|&x| x as usize).collect();
-        for i in parsed_max + 1..=extend {
-            cups_list.push(i);
-        }
-        cups_list
-    };
+/// Number of moves between two reports of the progress.
+const REPORT_EVERY: usize = 1 << 16;
 
+/// Makes `count` moves with the cups kept in a linked list, `rights[cup]` is the cup on its right.
+fn play_linked(cups_list: &[usize], count: usize) -> Vec<usize> {
     let min = *cups_list.iter().min().unwrap();
     let max = *cups_list.iter().max().unwrap();
 
@@ -80,7 +101,10 @@ fn part2(parsed: &Vec<u32>) -> usize {
     rights[last] = first;
 
     let mut current = first;
-    for _ in 1..=count {
+    for i in 1..=count {
+        if i.is_multiple_of(REPORT_EVERY) {
+            progress::report(i, count);
+        }
         let a = rights[current];
         let b = rights[a];
         let c = rights[b];
@@ -104,38 +128,144 @@ fn part2(parsed: &Vec<u32>) -> usize {
 
         current = rights[current];
     }
+    rights
+}
+
+/// Makes `moves` moves with `cups` cups, fewer than the labeled ones add none.
+pub fn part2_with_cups_and_moves(parsed: &[u32], cups: usize, moves: usize) -> usize {
+    let cups_list = {
+        let parsed_max = *parsed.iter().max().unwrap() as usize;
+        let mut cups_list: Vec<usize> = parsed.iter().map(|&x| x as usize).collect();
+        for i in parsed_max + 1..=cups {
+            cups_list.push(i);
+        }
+        cups_list
+    };
 
+    let rights = play_linked(&cups_list, moves);
     let x = rights[1];
     let y = rights[x];
 
     x * y
 }
 
-pub fn run() {
-    let input = include_str!("input/day23.txt");
-    let parsed = &parse(input);
-    println!("Day 23/1: {}", part1(parsed));
-    println!("Day 23/2: {}", part2(parsed));
+pub fn part2(parsed: &[u32]) -> usize {
+    part2_with_cups_and_moves(parsed, PART2_CUPS, PART2_MOVES)
+}
+
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day23;
+
+pub const SOLUTION: &dyn DynSolution = &Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+    type Parsed = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    const PARAMS: &'static [Param] = &[
+        Param::number(
+            "moves",
+            0,
+            u32::MAX as u64,
+            "number of moves [default: 100, 10000000 in part 2]",
+        ),
+        Param::number(
+            "cups",
+            1,
+            MAX_CUPS as u64,
+            "number of cups of part 2, at least the labeled ones [default: 1000000]",
+        ),
+    ];
+    fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part1_with_moves(parsed, params.number("moves", PART1_MOVES)).into()
+    }
+    fn part2_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        let cups = params.number("cups", PART2_CUPS);
+        part2_with_cups_and_moves(parsed, cups, params.number("moves", PART2_MOVES)).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static SAMPLE1: &'static str = "389125467";
+    use crate::differential::{self, Config, Rng};
+    use crate::progress::{self, Cancelled, Context};
+    use std::time::Instant;
 
     #[test]
-    fn test_day23_calc_moves_sample1() {
-        assert_eq!(92658374, calc_moves(10, &parse(SAMPLE1)));
+    fn test_day23_parse_errors() {
+        let err = parse("3892a4\n").unwrap_err();
+        assert_eq!((1, 5, "a"), (err.line, err.column, err.text.as_str()));
+        assert!(parse("3894\n").is_err());
+        assert!(parse("389154\n").is_err());
+        assert!(parse("3892514\n").is_err());
+        assert_eq!(Ok(vec![3, 2, 5, 1, 4]), parse("32514\n"));
+    }
+
+    /// Labels after the cup 1 read clockwise from the linked list, as `calc_moves` gives them.
+    fn labels_after_one(rights: &[usize]) -> u32 {
+        let mut labels = 0;
+        let mut cup = rights[1];
+        while cup != 1 {
+            labels = labels * 10 + cup as u32;
+            cup = rights[cup];
+        }
+        labels
     }
 
     #[test]
-    fn test_day23_part1_sample1() {
-        assert_eq!(67384529, part1(&parse(SAMPLE1)));
+    fn test_day23_differential() {
+        let config = Config::from_env(200, 23);
+        let generate = |rng: &mut Rng| {
+            let mut cups: Vec<u32> = (1..=rng.range(5, 9) as u32).collect();
+            rng.shuffle(&mut cups);
+            (cups, rng.range(0, 300))
+        };
+        let shrink = |(cups, count): &(Vec<u32>, usize)| {
+            let mut smaller: Vec<_> = differential::shrink_number(*count, 0)
+                .into_iter()
+                .map(|c| (cups.clone(), c))
+                .collect();
+            if cups.len() > 5 {
+                let max = cups.len() as u32;
+                let fewer = cups.iter().copied().filter(|&cup| cup != max).collect();
+                smaller.push((fewer, *count));
+            }
+            smaller
+        };
+        let result = differential::check(&config, generate, shrink, |(cups, count)| {
+            let cups_list: Vec<usize> = cups.iter().map(|&cup| cup as usize).collect();
+            differential::agree(
+                calc_moves(*count, cups),
+                labels_after_one(&play_linked(&cups_list, *count)),
+            )
+        });
+        if let Err(counterexample) = result {
+            panic!("{}", counterexample);
+        }
     }
 
     #[test]
-    fn test_day23_part2_sample1() {
-        assert_eq!(149245887792, part2(&parse(SAMPLE1)));
+    fn test_day23_moves_cancelled() {
+        let context = Context {
+            deadline: Some(Instant::now()),
+            ..Context::default()
+        };
+        let cups = parse("389125467\n").unwrap();
+        assert_eq!(
+            Err(Cancelled),
+            progress::with_context(context, || part1_with_moves(&cups, usize::MAX))
+        );
     }
 }
@@ -1,9 +1,13 @@
+use crate::frames::Frame;
+use crate::parser::Cursor;
+use crate::progress;
+use crate::ParseError;
 use std::collections::HashSet;
 
-type Point = crate::lib::point::Point<i32>;
+type Point = crate::point::Point<i32>;
 
 #[derive(PartialEq, Eq, Debug, Clone)]
-enum Direction {
+pub enum Direction {
     East,
     SouthEast,
     SouthWest,
@@ -25,7 +29,7 @@ impl Direction {
         .into()
     }
     fn all_as_points() -> Vec<Point> {
-        vec![
+        [
             Direction::East,
             Direction::SouthEast,
             Direction::SouthWest,
@@ -39,34 +43,31 @@ impl Direction {
     }
 }
 
-type ParsedData = Vec<Vec<Direction>>;
-
-fn parse(input: &str) -> ParsedData {
-    input
-        .lines()
-        .map(|line| {
-            let mut result = Vec::new();
-            let mut it = line.chars();
-            while let Some(c) = it.next() {
-                match c {
-                    'e' => result.push(Direction::East),
-                    'w' => result.push(Direction::West),
-                    'n' => match it.next() {
-                        Some('e') => result.push(Direction::NorthEast),
-                        Some('w') => result.push(Direction::NorthWest),
-                        _ => panic!(),
-                    },
-                    's' => match it.next() {
-                        Some('e') => result.push(Direction::SouthEast),
-                        Some('w') => result.push(Direction::SouthWest),
-                        _ => panic!(),
-                    },
-                    _ => panic!(format!("unexpected character '{}'", c)),
-                };
-            }
-            result
-        })
-        .collect()
+pub type ParsedData = Vec<Vec<Direction>>;
+
+pub fn parse(input: &str) -> Result<ParsedData, ParseError> {
+    Cursor::new(input).map_lines(|line| {
+        let mut result = Vec::new();
+        while !line.is_empty() {
+            let direction = if line.eat("e") {
+                Direction::East
+            } else if line.eat("w") {
+                Direction::West
+            } else if line.eat("ne") {
+                Direction::NorthEast
+            } else if line.eat("nw") {
+                Direction::NorthWest
+            } else if line.eat("se") {
+                Direction::SouthEast
+            } else if line.eat("sw") {
+                Direction::SouthWest
+            } else {
+                return Err(line.error_ahead(2, "unknown direction"));
+            };
+            result.push(direction);
+        }
+        Ok(result)
+    })
 }
 
 fn locate_black_points(data: &ParsedData) -> HashSet<Point> {
@@ -84,15 +85,16 @@ fn locate_black_points(data: &ParsedData) -> HashSet<Point> {
     black_points_set
 }
 
-fn part1(data: &ParsedData) -> usize {
+pub fn part1(data: &ParsedData) -> usize {
     let black_points_set = locate_black_points(data);
     black_points_set.len()
 }
 
-fn part2(data: &ParsedData) -> usize {
-    let mut black_points_set = locate_black_points(data);
-
-    let all_directions_as_points = Direction::all_as_points();
+/// Flips the tiles according to the daily rules of the exhibit.
+fn flip_tiles(
+    black_points_set: &HashSet<Point>,
+    all_directions_as_points: &[Point],
+) -> HashSet<Point> {
     let get_adjacent_points = |source| -> HashSet<Point> {
         all_directions_as_points
             .iter()
@@ -100,104 +102,154 @@ fn part2(data: &ParsedData) -> usize {
             .collect()
     };
 
-    for _ in 0..100 {
-        let needs_flipping_from_black_to_white: HashSet<Point> = black_points_set
+    let needs_flipping_from_black_to_white: HashSet<Point> = black_points_set
+        .iter()
+        .filter(|pt| {
+            let adj = get_adjacent_points(**pt);
+            let adjacent_blacks = black_points_set.intersection(&adj).count();
+            0 == adjacent_blacks || adjacent_blacks > 2
+        })
+        .copied()
+        .collect();
+
+    let needs_flipping_from_white_to_black = {
+        let all_adjacent_points: HashSet<Point> = black_points_set
             .iter()
+            .flat_map(|black_pt| get_adjacent_points(*black_pt))
+            .collect();
+
+        let white_points = all_adjacent_points
+            .difference(black_points_set)
+            .copied()
+            .collect::<HashSet<Point>>();
+
+        white_points
+            .into_iter()
             .filter(|pt| {
-                let adj = get_adjacent_points(**pt);
+                let adj = get_adjacent_points(*pt);
                 let adjacent_blacks = black_points_set.intersection(&adj).count();
-                0 == adjacent_blacks || adjacent_blacks > 2
+                adjacent_blacks == 2
             })
-            .map(|p| p.clone())
-            .collect();
+            .collect::<HashSet<Point>>()
+    };
 
-        let needs_flipping_from_white_to_black = {
-            let all_adjacent_points: HashSet<Point> = black_points_set
-                .iter()
-                .map(|black_pt| get_adjacent_points(*black_pt))
-                .flatten()
-                .collect();
-
-            let white_points = all_adjacent_points
-                .difference(&black_points_set)
-                .map(|p| p.clone())
-                .collect::<HashSet<Point>>();
-
-            white_points
-                .into_iter()
-                .filter(|pt| {
-                    let adj = get_adjacent_points(*pt);
-                    let adjacent_blacks = black_points_set.intersection(&adj).count();
-                    adjacent_blacks == 2
-                })
-                .collect::<HashSet<Point>>()
-        };
-
-        for flip_pt in needs_flipping_from_black_to_white {
-            black_points_set.remove(&flip_pt);
-        }
-        for flip_pt in needs_flipping_from_white_to_black {
-            black_points_set.insert(flip_pt);
-        }
+    let mut black_points_set = black_points_set.clone();
+    for flip_pt in needs_flipping_from_black_to_white {
+        black_points_set.remove(&flip_pt);
+    }
+    for flip_pt in needs_flipping_from_white_to_black {
+        black_points_set.insert(flip_pt);
     }
+    black_points_set
+}
 
-    black_points_set.len()
+/// Black tiles of the exhibit day by day, starting with the initial arrangement.
+fn exhibit(data: &ParsedData) -> impl Iterator<Item = HashSet<Point>> {
+    let all_directions_as_points = Direction::all_as_points();
+    std::iter::successors(Some(locate_black_points(data)), move |black_points_set| {
+        Some(flip_tiles(black_points_set, &all_directions_as_points))
+    })
+}
+
+/// Number of days of the exhibit.
+const DAYS: usize = 100;
+
+pub fn part2_with_days(data: &ParsedData, days: usize) -> usize {
+    exhibit(data)
+        .enumerate()
+        .map(|(day, black_points_set)| {
+            progress::report(day, days);
+            black_points_set
+        })
+        .nth(days)
+        .unwrap()
+        .len()
 }
 
-pub fn run() {
-    let input = include_str!("input/day24.txt");
-    let parsed = &parse(input);
-    println!("Day 24/1: {}", part1(parsed));
-    println!("Day 24/2: {}", part2(parsed));
+pub fn part2(data: &ParsedData) -> usize {
+    part2_with_days(data, DAYS)
+}
+
+/// The initial arrangement for the `part` 1, and the 100 days of the exhibit for the `part` 2.
+pub fn frames(data: &ParsedData, part: u8) -> Vec<Frame> {
+    let days = if part == 1 { 0 } else { DAYS };
+    exhibit(data)
+        .take(days + 1)
+        .map(|black_points_set| {
+            let mut tiles: Vec<Point> = black_points_set.into_iter().collect();
+            tiles.sort();
+            Frame::Hex(tiles)
+        })
+        .collect()
+}
+
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day24;
+
+pub const SOLUTION: &dyn DynSolution = &Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+    type Parsed = ParsedData;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    const PARAMS: &'static [Param] = &[Param::number(
+        "days",
+        0,
+        u32::MAX as u64,
+        "number of days of the exhibit of part 2 [default: 100]",
+    )];
+    fn part2_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part2_with_days(parsed, params.number("days", DAYS)).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static SAMPLE1: &'static str = "\
-sesenwnenenewseeswwswswwnenewsewsw
-neeenesenwnwwswnenewnwwsewnenwseswesw
-seswneswswsenwwnwse
-nwnwneseeswswnenewneswwnewseswneseene
-swweswneswnenwsewnwneneseenw
-eesenwseswswnenwswnwnwsewwnwsene
-sewnenenenesenwsewnenwwwse
-wenwwweseeeweswwwnwwe
-wsweesenenewnwwnwsenewsenwwsesesenwne
-neeswseenwwswnwswswnw
-nenwswwsewswnenenewsenwsenwnesesenew
-enewnwewneswsewnwswenweswnenwsenwsw
-sweneswneswneneenwnewenewwneswswnese
-swwesenesewenwneswnwwneseswwne
-enesenwswwswneneswsenwnewswseenwsese
-wnwnesenesenenwwnenwsewesewsesesew
-nenewswnwewswnenesenwnesewesw
-eneswnwswnwsenenwnwnwwseeswneewsenese
-neswnwewnwnwseenwseesewsenwsweewe
-wseweeenwnesenwwwswnew
-";
+    static SAMPLE1: &str = include_str!("samples/day24.txt");
 
     #[test]
     fn test_day24_parse() {
         assert_eq!(
-            vec![vec![
+            Ok(vec![vec![
                 Direction::East,
                 Direction::SouthEast,
                 Direction::NorthWest,
                 Direction::West,
-            ]],
+            ]]),
             parse("esenww")
         );
+        let err = parse("esenww\neswx\n").unwrap_err();
+        assert_eq!((2, 4, "x"), (err.line, err.column, err.text.as_str()));
+        let err = parse("esenww\nnnw\n").unwrap_err();
+        assert_eq!((2, 1, "nn"), (err.line, err.column, err.text.as_str()));
+        let err = parse("es").unwrap_err();
+        assert_eq!((1, 2, "s"), (err.line, err.column, err.text.as_str()));
     }
 
     #[test]
-    fn test_day24_part1_sample1() {
-        assert_eq!(10, part1(&parse(SAMPLE1)));
-    }
-
-    #[test]
-    fn test_day24_part2_sample1() {
-        assert_eq!(2208, part2(&parse(SAMPLE1)));
+    fn test_day24_frames() {
+        let data = parse(SAMPLE1).unwrap();
+        assert_eq!(1, frames(&data, 1).len());
+        let frames = frames(&data, 2);
+        assert_eq!(101, frames.len());
+        match (&frames[0], &frames[10]) {
+            (Frame::Hex(first), Frame::Hex(tenth)) => {
+                assert_eq!((10, 37), (first.len(), tenth.len()))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
     }
 }
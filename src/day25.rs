@@ -1,20 +1,40 @@
-use crate::lib::parse_to_vec;
+use crate::parser::Cursor;
+use crate::progress;
+use crate::ParseError;
 
-fn parse(input: &str) -> (u64, u64) {
-    let keys = parse_to_vec(input.lines());
-    (keys[0], keys[1])
-}
+static REMDIV: u64 = 20201227;
+/// Subject number transformed into the public keys.
+const SUBJECT: u64 = 7;
 
-fn solve(card_pubkey: u64, door_pubkey: u64) -> u64 {
-    static REMDIV: u64 = 20201227;
+pub fn parse(input: &str) -> Result<(u64, u64), ParseError> {
+    let mut cursor = Cursor::new(input);
+    let mut next_key = || {
+        let mut line = cursor.expect_line("missing public key")?;
+        let start = line;
+        let key = line.take_number::<u64>()?;
+        line.expect_end()?;
+        // the loop size of other keys cannot be found
+        if !(1..REMDIV).contains(&key) {
+            return Err(line.error_at(start.rest(), "public key out of range"));
+        }
+        Ok(key)
+    };
+    Ok((next_key()?, next_key()?))
+}
 
+fn solve(card_pubkey: u64, door_pubkey: u64, subject: u64, modulus: u64) -> Option<u64> {
     let (loop_size, other_pubkey) = {
-        let subject_number = 7;
+        let subject_number = subject;
         let mut loop_size = 0;
         let mut value = 1;
         loop {
+            // the values repeat after at most `modulus` steps
+            if loop_size == modulus {
+                return None;
+            }
+            progress::check();
             loop_size += 1;
-            value = (value * subject_number) % REMDIV;
+            value = (value * subject_number) % modulus;
             if value == card_pubkey {
                 break (loop_size, door_pubkey);
             }
@@ -24,38 +44,84 @@ fn solve(card_pubkey: u64, door_pubkey: u64) -> u64 {
         }
     };
 
-    let encryption_key = {
+    {
         let subject_number = other_pubkey;
         let mut value = 1;
         for _ in 0..loop_size {
-            value = (value * subject_number) % REMDIV;
+            value = (value * subject_number) % modulus;
         }
-        value
-    };
+        Some(value)
+    }
+}
 
-    encryption_key
+/// Finds the encryption key with the keys transformed from the `subject` modulo the `modulus`,
+/// none when no loop size transforms the `subject` into either of the keys.
+pub fn part1_with_subject_and_modulus(
+    keys: &(u64, u64),
+    subject: u64,
+    modulus: u64,
+) -> Option<u64> {
+    solve(keys.0, keys.1, subject, modulus)
 }
 
-pub fn run() {
-    let input = include_str!("input/day25.txt");
-    let (key1, key2) = parse(input);
-    println!("Day 25: {}", solve(key1, key2));
+pub fn part1(keys: &(u64, u64)) -> u64 {
+    part1_with_subject_and_modulus(keys, SUBJECT, REMDIV).expect("no loop size of the keys")
+}
+
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day25;
+
+pub const SOLUTION: &dyn DynSolution = &Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const PARTS: u8 = 1;
+    type Parsed = (u64, u64);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1_with_subject_and_modulus(parsed, SUBJECT, REMDIV).into()
+    }
+    // the moduli fit in 32 bits so that the products of the values do not overflow
+    const PARAMS: &'static [Param] = &[
+        Param::number(
+            "subject",
+            1,
+            u32::MAX as u64,
+            "subject number of the public keys [default: 7]",
+        ),
+        Param::number(
+            "modulus",
+            2,
+            u32::MAX as u64,
+            "modulus of the transformations [default: 20201227]",
+        ),
+    ];
+    fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        let subject = params.number("subject", SUBJECT);
+        part1_with_subject_and_modulus(parsed, subject, params.number("modulus", REMDIV)).into()
+    }
+    fn sentinel(_part: u8) -> Option<Answer> {
+        Some(Answer::None)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static SAMPLE: &'static str = "5764801\n17807724";
+    static SAMPLE: &str = include_str!("samples/day25.txt");
 
     #[test]
     fn test_day25_parse() {
-        assert_eq!((5764801, 17807724), parse(SAMPLE));
-    }
-
-    #[test]
-    fn test_day25_part1_sample() {
-        let (key1, key2) = parse(SAMPLE);
-        assert_eq!(14897079, solve(key1, key2));
+        assert_eq!(Ok((5764801, 17807724)), parse(SAMPLE));
+        let err = parse("5764801\n").unwrap_err();
+        assert_eq!("line 2, column 1: missing public key", err.to_string());
+        let err = parse("5764801\n20201227\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
     }
 }
@@ -1,82 +1,61 @@
-use crate::lib;
-use std::cmp::max;
-use std::collections::HashMap;
+use crate::grid::{Cell, Grid};
+use crate::parser::Cursor;
+use crate::point::Point;
+use crate::ParseError;
 
-#[derive(PartialEq)]
-enum LocationType {
+#[derive(PartialEq, Debug)]
+pub enum LocationType {
     Open,
     Tree,
 }
 
-#[derive(PartialEq, Eq, Hash)]
-struct Point(usize, usize);
+impl Cell for LocationType {
+    const EXPECTED: &'static str = "'#' or '.'";
 
-struct Map {
-    width: usize,
-    height: usize,
-    points: HashMap<Point, LocationType>,
-}
-
-impl Map {
-    fn new(points: HashMap<Point, LocationType>) -> Map {
-        let mut max_w = 0;
-        let mut max_h = 0;
-        for (Point(x, y), _) in &points {
-            max_w = max(max_w, *x);
-            max_h = max(max_h, *y);
-        }
-        Map {
-            width: max_w + 1,
-            height: max_h + 1,
-            points: points,
+    fn from_char(c: char) -> Option<LocationType> {
+        match c {
+            '#' => Some(LocationType::Tree),
+            '.' => Some(LocationType::Open),
+            _ => None,
         }
     }
-    fn get(&self, x: usize, y: usize) -> &LocationType {
-        let x = x % self.width;
-        self.points.get(&Point(x, y)).unwrap()
+    fn to_char(&self) -> char {
+        match self {
+            LocationType::Tree => '#',
+            LocationType::Open => '.',
+        }
     }
 }
 
-fn parse_map(lines: &Vec<String>) -> Map {
-    let mut points = HashMap::new();
-    for (y, line) in lines.iter().enumerate() {
-        for (x, ch) in line.char_indices() {
-            let pt = Point(x, y);
-            let typ = if ch == '#' {
-                LocationType::Tree
-            } else {
-                LocationType::Open
-            };
-            points.insert(pt, typ);
-        }
-    }
-    Map::new(points)
+/// The map repeats to the right infinitely.
+pub type Map = Grid<LocationType>;
+
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    Grid::parse(Cursor::new(input))
 }
 
-fn count_trees(slope_right: usize, slope_down: usize, map: &Map) -> usize {
+fn count_trees(slope_right: isize, slope_down: isize, map: &Map) -> usize {
+    let slope = Point::new(slope_right, slope_down);
     let mut count = 0;
-    let mut x = 0;
-    let mut y = 0;
+    let mut pos = Point::zero();
     loop {
-        x += slope_right;
-        y += slope_down;
-        let t = map.get(x, y);
-        if *t == LocationType::Tree {
+        pos += slope;
+        if *map.get_wrapping(pos.x(), pos.y()) == LocationType::Tree {
             count += 1;
         }
-        if y == map.height - 1 {
+        if pos.y() == map.height() as isize - 1 {
             break;
         }
     }
     count
 }
 
-fn part1(map: &Map) -> usize {
+pub fn part1(map: &Map) -> usize {
     count_trees(3, 1, map)
 }
 
-fn part2(map: &Map) -> usize {
-    vec![
+pub fn part2(map: &Map) -> usize {
+    [
         count_trees(1, 1, map),
         count_trees(3, 1, map),
         count_trees(5, 1, map),
@@ -87,10 +66,36 @@ fn part2(map: &Map) -> usize {
     .product()
 }
 
-pub fn run() {
-    let input = include_str!("input/day3.txt");
-    let lines = &lib::to_lines(input);
-    let map = &parse_map(lines);
-    println!("Day 3/1: {}", part1(map));
-    println!("Day 3/2: {}", part2(map));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day3;
+
+pub const SOLUTION: &dyn DynSolution = &Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    type Parsed = Map;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day3_parse_errors() {
+        let err = parse("..#\n.o.\n").unwrap_err();
+        assert_eq!((2, 2, "o"), (err.line, err.column, err.text.as_str()));
+        assert!(parse("..#\n..\n").is_err());
+        assert!(parse("").is_err());
+    }
 }
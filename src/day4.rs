@@ -1,7 +1,8 @@
-use crate::lib::split_once_char;
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::collections::HashMap;
 
-struct Passport {
+pub struct Passport {
     byr: Option<String>,
     iyr: Option<String>,
     eyr: Option<String>,
@@ -12,28 +13,23 @@ struct Passport {
     cid: Option<String>,
 }
 
-fn parse_passport(data: &str) -> Passport {
-    let fields: Vec<(String, String)> = data
-        .split_whitespace()
-        .map(|field| split_once_char(field, ':'))
-        .map(|(k, v)| (String::from(k), String::from(v)))
-        .collect();
-
+fn parse_passport(data: Cursor) -> Result<Passport, ParseError> {
     let mut h = HashMap::new();
-    for (k, v) in fields {
-        h.insert(k, v);
+    for mut field in data.words() {
+        let k = field.take_until(":")?;
+        h.insert(k, field.take_rest());
     }
 
-    Passport {
-        byr: h.get("byr").map(String::from),
-        iyr: h.get("iyr").map(String::from),
-        eyr: h.get("eyr").map(String::from),
-        hgt: h.get("hgt").map(String::from),
-        hcl: h.get("hcl").map(String::from),
-        ecl: h.get("ecl").map(String::from),
-        pid: h.get("pid").map(String::from),
-        cid: h.get("cid").map(String::from),
-    }
+    Ok(Passport {
+        byr: h.get("byr").map(|v| String::from(*v)),
+        iyr: h.get("iyr").map(|v| String::from(*v)),
+        eyr: h.get("eyr").map(|v| String::from(*v)),
+        hgt: h.get("hgt").map(|v| String::from(*v)),
+        hcl: h.get("hcl").map(|v| String::from(*v)),
+        ecl: h.get("ecl").map(|v| String::from(*v)),
+        pid: h.get("pid").map(|v| String::from(*v)),
+        cid: h.get("cid").map(|v| String::from(*v)),
+    })
 }
 
 fn is_valid(p: &Passport) -> bool {
@@ -47,11 +43,14 @@ fn is_valid(p: &Passport) -> bool {
         && (p.cid.is_some() || p.cid.is_none())
 }
 
-fn parse_input(input: &str) -> Vec<Passport> {
-    input.split("\n\n").map(parse_passport).collect()
+pub fn parse(input: &str) -> Result<Vec<Passport>, ParseError> {
+    Cursor::new(input)
+        .split_blocks()
+        .map(parse_passport)
+        .collect()
 }
 
-fn part1(passports: &Vec<Passport>) -> usize {
+pub fn part1(passports: &[Passport]) -> usize {
     passports.iter().map(is_valid).map(|b| b as usize).sum()
 }
 
@@ -59,7 +58,8 @@ fn has_valid_fields(p: &Passport) -> bool {
     static VALID_ECL: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
     let allowed_color_chars: Vec<char> = "0123456789abcdef".chars().collect();
     let is_between = |n, a, b| a <= n && n <= b;
-    let to_i32 = |x: &String| x.parse::<i32>().unwrap();
+    // values which are not numbers are out of any range
+    let to_i32 = |x: &str| x.parse::<i32>().unwrap_or(-1);
 
     p.byr
         .as_ref()
@@ -93,8 +93,7 @@ fn has_valid_fields(p: &Passport) -> bool {
                     && x.starts_with('#')
                     && x[1..]
                         .replace(|c: char| allowed_color_chars.contains(&c), "")
-                        .len()
-                        == 0
+                        .is_empty()
             })
             .unwrap_or_default()
         && p.pid
@@ -107,7 +106,7 @@ fn has_valid_fields(p: &Passport) -> bool {
             .unwrap_or_default()
 }
 
-fn part2(passports: &Vec<Passport>) -> usize {
+pub fn part2(passports: &[Passport]) -> usize {
     passports
         .iter()
         .map(has_valid_fields)
@@ -115,9 +114,23 @@ fn part2(passports: &Vec<Passport>) -> usize {
         .sum()
 }
 
-pub fn run() {
-    let input = include_str!("input/day4.txt");
-    let passports = &parse_input(input);
-    println!("Day 4/1: {}", part1(passports));
-    println!("Day 4/2: {}", part2(passports));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day4;
+
+pub const SOLUTION: &dyn DynSolution = &Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    type Parsed = Vec<Passport>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
@@ -1,6 +1,15 @@
-use crate::lib::to_lines;
+use crate::parser::Cursor;
+use crate::ParseError;
 
-fn seat_id(l: &String) -> u32 {
+fn seat_id(line: &mut Cursor) -> Result<u32, ParseError> {
+    let l = line.take_rest();
+    let is_code = |c: u8, i: usize| match i {
+        0..=6 => c == b'F' || c == b'B',
+        _ => c == b'L' || c == b'R',
+    };
+    if l.len() != 10 || !l.bytes().enumerate().all(|(i, c)| is_code(c, i)) {
+        return Err(line.error_at(l, "expected 7 of 'F'/'B' and 3 of 'L'/'R'"));
+    }
     let row_code = &l[..7];
     let col_code = &l[7..];
     let mut row = 0b0111_1111; // 127
@@ -19,14 +28,22 @@ fn seat_id(l: &String) -> u32 {
         };
         x /= 2;
     }
-    row * 8 + col
+    Ok(row * 8 + col)
+}
+
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    let seat_ids = Cursor::new(input).map_lines(seat_id)?;
+    if seat_ids.is_empty() {
+        return Err(ParseError::end_of_input(input, "no boarding passes"));
+    }
+    Ok(seat_ids)
 }
 
-fn part1(seat_ids: &Vec<u32>) -> u32 {
+pub fn part1(seat_ids: &[u32]) -> u32 {
     *seat_ids.iter().max().unwrap()
 }
 
-fn part2(seat_ids: &Vec<u32>) -> u32 {
+pub fn part2(seat_ids: &[u32]) -> u32 {
     let max_id = *seat_ids.iter().max().unwrap() as usize;
     let mut v: Vec<bool> = vec![false; max_id + 1];
     for id in seat_ids.iter() {
@@ -42,10 +59,30 @@ fn part2(seat_ids: &Vec<u32>) -> u32 {
     my_seat_id as u32
 }
 
-pub fn run() {
-    let input = include_str!("input/day5.txt");
-    let lines = &to_lines(input);
-    let seat_ids = &lines.iter().map(seat_id).collect();
-    println!("Day 5/1: {}", part1(seat_ids));
-    println!("Day 5/2: {}", part2(seat_ids));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day5;
+
+pub const SOLUTION: &dyn DynSolution = &Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    type Parsed = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    fn sentinel(part: u8) -> Option<Answer> {
+        if part == 2 {
+            Some(Answer::UInt(0))
+        } else {
+            None
+        }
+    }
 }
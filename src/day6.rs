@@ -1,11 +1,24 @@
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::collections::HashSet;
 
-fn parse_input(input: &str) -> Vec<&str> {
-    input.split("\n\n").collect()
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    let mut groups = Vec::new();
+    for mut group in Cursor::new(input).split_blocks() {
+        if group.rest().trim().is_empty() {
+            return Err(group.error("empty group"));
+        }
+        let answers = group.take_while(|c| c.is_ascii_lowercase() || c == '\n');
+        if !group.is_empty() {
+            return Err(group.error_ahead(1, "expected a question letter"));
+        }
+        groups.push(String::from(answers));
+    }
+    Ok(groups)
 }
 
-fn part1(groups: &Vec<&str>) -> usize {
-    let count_questions_in_group = |group: &&str| {
+pub fn part1(groups: &[String]) -> usize {
+    let count_questions_in_group = |group: &String| {
         let mut group_answers = HashSet::new();
         for ch in group.chars() {
             if char::is_alphabetic(ch) {
@@ -17,8 +30,8 @@ fn part1(groups: &Vec<&str>) -> usize {
     groups.iter().map(count_questions_in_group).sum()
 }
 
-fn part2(groups: &Vec<&str>) -> usize {
-    let count_questions_in_group = |group: &&str| {
+pub fn part2(groups: &[String]) -> usize {
+    let count_questions_in_group = |group: &String| {
         let answers_vec = group
             .lines()
             .map(|line| {
@@ -40,29 +53,34 @@ fn part2(groups: &Vec<&str>) -> usize {
     groups.iter().map(count_questions_in_group).sum()
 }
 
-pub fn run() {
-    let input = include_str!("input/day6.txt");
-    let groups = &parse_input(input);
-    println!("Day 6/1: {}", part1(groups));
-    println!("Day 6/2: {}", part2(groups));
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day6;
+
+pub const SOLUTION: &dyn DynSolution = &Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    fn sample() -> Vec<&'static str> {
-        vec!["abc", "a\nb\nc", "ab\nac", "a\na\na\na", "b"]
-    }
-
-    #[test]
-    fn test_example_part1() {
-        let groups = &sample();
-        assert_eq!(11, part1(groups));
-    }
 
     #[test]
-    fn test_example_part2() {
-        let groups = &sample();
-        assert_eq!(6, part2(groups));
+    fn test_day6_parse_error() {
+        let err = parse("abc\n\na\nB\n").unwrap_err();
+        assert_eq!((4, 1, "B"), (err.line, err.column, err.text.as_str()));
     }
 }
@@ -1,39 +1,56 @@
+use crate::parser::Cursor;
+use crate::ParseError;
 use std::collections::HashMap;
 
-static SHINY_GOLD: &'static str = "shiny gold";
-
-fn parse_input<'a>(lines: impl Iterator<Item = &'a str>) -> HashMap<String, Vec<(u8, String)>> {
-    lines.fold(HashMap::new(), |mut acc, line| {
-        let split = line.split(" contain ").collect::<Vec<&str>>();
-        let key = split[0].trim_end_matches(" bags").to_string();
-        let value = split[1]
-            .trim_end_matches('.')
-            .split(", ")
-            .map(|s| s.trim_end_matches(" bags").trim_end_matches(" bag"))
-            .filter(|s| s != &"no other")
-            .map(|s| {
-                let i = s[..1].parse::<u8>().unwrap();
-                let n = String::from(&s[2..]);
-                (i, n)
-            })
-            .collect::<Vec<_>>();
+static SHINY_GOLD: &str = "shiny gold";
+
+pub fn parse(input: &str) -> Result<HashMap<String, Vec<(u8, String)>>, ParseError> {
+    let mut acc = HashMap::new();
+    let mut contained = Vec::new();
+    let cursor = Cursor::new(input);
+    for mut line in cursor.lines() {
+        let key = line.take_until(" bags contain ")?.to_string();
+        let mut value = Vec::new();
+        if !line.eat("no other bags") {
+            value = line.separated(", ", |item| {
+                let count = item.take_number::<u8>()?;
+                item.expect_literal(" ")?;
+                let name = item.take_until(" bag")?;
+                item.eat("s");
+                contained.push(name);
+                Ok((count, String::from(name)))
+            })?;
+        }
+        line.expect_literal(".")?;
+        line.expect_end()?;
 
         acc.insert(key, value);
-        acc
-    })
+    }
+    if let Some(n) = contained.into_iter().find(|n| !acc.contains_key(*n)) {
+        return Err(cursor.error_at(n, "no rule for the bag"));
+    }
+    Ok(acc)
 }
 
-fn part1(map: &HashMap<String, Vec<(u8, String)>>) -> usize {
-    fn can_reach_shiny_gold(map: &HashMap<String, Vec<(u8, String)>>, name: &String) -> bool {
+/// Number of bags which can contain the `bag`, none when there is no rule for it.
+pub fn part1_with_bag(map: &HashMap<String, Vec<(u8, String)>>, bag: &str) -> Option<usize> {
+    fn can_reach_bag(map: &HashMap<String, Vec<(u8, String)>>, name: &String, bag: &str) -> bool {
         let v = map.get(name).unwrap();
-        v.iter().any(|(_, n)| n == SHINY_GOLD)
-            || v.into_iter().any(|(_, n)| can_reach_shiny_gold(map, n))
-    };
+        v.iter().any(|(_, n)| n == bag) || v.iter().any(|(_, n)| can_reach_bag(map, n, bag))
+    }
+
+    if !map.contains_key(bag) {
+        return None;
+    }
+    Some(map.keys().filter(|k| can_reach_bag(map, k, bag)).count())
+}
 
-    map.keys().filter(|k| can_reach_shiny_gold(map, k)).count()
+pub fn part1(map: &HashMap<String, Vec<(u8, String)>>) -> usize {
+    part1_with_bag(map, SHINY_GOLD).expect("no rule for the shiny gold bag")
 }
 
-fn part2(map: &HashMap<String, Vec<(u8, String)>>) -> u32 {
+/// Number of bags inside the `bag`, none when there is no rule for it.
+pub fn part2_with_bag(map: &HashMap<String, Vec<(u8, String)>>, bag: &str) -> Option<u32> {
     fn count_items_in_bag(
         map: &HashMap<String, Vec<(u8, String)>>,
         name: &String,
@@ -48,56 +65,65 @@ fn part2(map: &HashMap<String, Vec<(u8, String)>>) -> u32 {
             })
             .sum();
         sum + sub
-    };
+    }
 
-    count_items_in_bag(map, &String::from(SHINY_GOLD), 0)
+    if !map.contains_key(bag) {
+        return None;
+    }
+    Some(count_items_in_bag(map, &String::from(bag), 0))
 }
 
-pub fn run() {
-    let input = include_str!("input/day7.txt");
-    let map = &parse_input(input.lines());
-    println!("Day 7/1: {}", part1(map));
-    println!("Day 7/2: {}", part2(map));
+pub fn part2(map: &HashMap<String, Vec<(u8, String)>>) -> u32 {
+    part2_with_bag(map, SHINY_GOLD).expect("no rule for the shiny gold bag")
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
 
-    const SAMPLE: &'static str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
-dark orange bags contain 3 bright white bags, 4 muted yellow bags.
-bright white bags contain 1 shiny gold bag.
-muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
-shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
-dark olive bags contain 3 faded blue bags, 4 dotted black bags.
-vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
-faded blue bags contain no other bags.
-dotted black bags contain no other bags.
-";
-    const SAMPLE2: &'static str = "shiny gold bags contain 2 dark red bags.
-dark red bags contain 2 dark orange bags.
-dark orange bags contain 2 dark yellow bags.
-dark yellow bags contain 2 dark green bags.
-dark green bags contain 2 dark blue bags.
-dark blue bags contain 2 dark violet bags.
-dark violet bags contain no other bags.
-";
+struct Day7;
 
-    #[test]
-    fn test_part1_sample() {
-        let map = parse_input(SAMPLE.lines());
-        assert_eq!(4, part1(&map));
-    }
+pub const SOLUTION: &dyn DynSolution = &Day7;
 
-    #[test]
-    fn test_part2_sample() {
-        let map = parse_input(SAMPLE.lines());
-        assert_eq!(32, part2(&map));
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    type Parsed = HashMap<String, Vec<(u8, String)>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1_with_bag(parsed, SHINY_GOLD).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2_with_bag(parsed, SHINY_GOLD).into()
+    }
+    const PARAMS: &'static [Param] = &[Param::text(
+        "bag",
+        "the bag to carry, like 'dark red' [default: shiny gold]",
+    )];
+    fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part1_with_bag(parsed, params.text("bag", SHINY_GOLD)).into()
+    }
+    fn part2_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part2_with_bag(parsed, params.text("bag", SHINY_GOLD)).into()
     }
+    fn sentinel(_part: u8) -> Option<Answer> {
+        Some(Answer::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn test_part2_sample2() {
-        let map = parse_input(SAMPLE2.lines());
-        assert_eq!(126, part2(&map));
+    fn test_parse_errors() {
+        let err = parse("dark red bags contain two shiny gold bags.\n").unwrap_err();
+        assert_eq!((1, 23, "two"), (err.line, err.column, err.text.as_str()));
+        let err = parse("dark red bags contain 2 shiny gold bags.\n").unwrap_err();
+        assert_eq!(
+            (1, 25, "shiny gold"),
+            (err.line, err.column, err.text.as_str())
+        );
     }
 }
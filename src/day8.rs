@@ -1,5 +1,8 @@
 mod code_runner {
-    use crate::lib::split_once_char;
+    use crate::explain;
+    use crate::parser::Cursor;
+    use crate::ParseError;
+    use std::fmt;
 
     #[derive(Copy, Clone)]
     enum Op {
@@ -8,6 +11,16 @@ mod code_runner {
         Jmp(i32),
     }
 
+    impl fmt::Display for Op {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Op::Nop(x) => write!(f, "nop {:+}", x),
+                Op::Acc(x) => write!(f, "acc {:+}", x),
+                Op::Jmp(x) => write!(f, "jmp {:+}", x),
+            }
+        }
+    }
+
     pub struct BootCode {
         ops: Vec<Op>,
     }
@@ -19,10 +32,10 @@ mod code_runner {
 
     impl ExitCode {
         pub fn value(&self) -> i32 {
-            return match self {
+            match self {
                 ExitCode::Success(x) => *x,
                 ExitCode::LoopDetected(x) => *x,
-            };
+            }
         }
     }
 
@@ -73,6 +86,16 @@ mod code_runner {
                 };
 
                 if let ExitCode::Success(exit) = BootCode::do_run(self.ops.len(), get_op) {
+                    explain::fact(
+                        "patched",
+                        format_args!(
+                            "instruction {} (line {}), {} became {}",
+                            fix_index,
+                            fix_index + 1,
+                            self.ops[fix_index],
+                            get_op(fix_index)
+                        ),
+                    );
                     return exit;
                 }
             }
@@ -80,65 +103,83 @@ mod code_runner {
         }
     }
 
-    pub fn parse_input<'a>(lines: impl Iterator<Item = &'a str>) -> BootCode {
-        let v = lines
-            .map(|line| {
-                let (o, p) = split_once_char(line, ' ');
-                let i = p.parse::<i32>().unwrap();
-
-                match o {
-                    "acc" => Op::Acc(i),
-                    "jmp" => Op::Jmp(i),
-                    "nop" => Op::Nop(i),
-                    _ => panic!(),
-                }
-            })
-            .collect::<Vec<_>>();
-        BootCode { ops: v }
+    pub fn parse(input: &str) -> Result<BootCode, ParseError> {
+        let v = Cursor::new(input).map_lines(|line| {
+            let o = line.take_until(" ")?;
+            let i = line.take_number::<i32>()?;
+
+            match o {
+                "acc" => Ok(Op::Acc(i)),
+                "jmp" => Ok(Op::Jmp(i)),
+                "nop" => Ok(Op::Nop(i)),
+                _ => Err(line.error_at(o, "unknown operation")),
+            }
+        })?;
+        Ok(BootCode { ops: v })
     }
 }
 
-use code_runner::{parse_input, BootCode};
+pub use code_runner::{parse, BootCode};
 
-fn part1(code: &BootCode) -> i32 {
+pub fn part1(code: &BootCode) -> i32 {
     code.run()
 }
 
-fn part2(code: &BootCode) -> i32 {
+pub fn part2(code: &BootCode) -> i32 {
     code.run_with_fixing()
 }
 
-pub fn run() {
-    let input = include_str!("input/day8.txt");
-    let code = &parse_input(input.lines());
-    println!("Day 8/1: {}", part1(code));
-    println!("Day 8/2: {}", part2(code));
+use crate::solution::{Answer, DynSolution, Solution};
+use crate::ParseError;
+
+struct Day8;
+
+pub const SOLUTION: &dyn DynSolution = &Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    type Parsed = BootCode;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    fn sentinel(part: u8) -> Option<Answer> {
+        if part == 2 {
+            Some(Answer::Int(-1))
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::code_runner::*;
-    use super::*;
-
-    const SAMPLE: &'static str = "nop +0
-acc +1
-jmp +4
-acc +3
-jmp -3
-acc -99
-acc +1
-jmp -4
-acc +6
-";
+    use super::part2;
+    use crate::explain::{self, Fact};
 
     #[test]
-    fn test_part1_sample() {
-        let code = parse_input(SAMPLE.lines());
-        assert_eq!(5, part1(&code));
+    fn test_parse_errors() {
+        let err = parse("nop +0\nadd +1\n").err().unwrap();
+        assert_eq!((2, 1, "add"), (err.line, err.column, err.text.as_str()));
+        let err = parse("nop +0\njmp -x\n").err().unwrap();
+        assert_eq!((2, 5, "-x"), (err.line, err.column, err.text.as_str()));
     }
+
     #[test]
-    fn test_part2_sample() {
-        let code = parse_input(SAMPLE.lines());
-        assert_eq!(8, part2(&code));
+    fn test_day8_explain() {
+        let code = parse(include_str!("samples/day8.txt")).unwrap();
+        let (answer, facts) = explain::collect(|| part2(&code));
+        assert_eq!(8, answer);
+        assert_eq!(
+            vec!["patched: instruction 7 (line 8), jmp -4 became nop -4"],
+            facts.iter().map(Fact::to_string).collect::<Vec<_>>()
+        );
     }
 }
@@ -1,12 +1,22 @@
-fn parse_input<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<u64> {
-    lines.map(|s| s.parse::<u64>().unwrap()).collect::<Vec<_>>()
+use crate::explain;
+use crate::parser::Cursor;
+use crate::progress;
+use crate::ParseError;
+
+pub fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+    let cypher = Cursor::new(input).map_lines(Cursor::take_number)?;
+    // the shortest cypher is a number after a preamble of one
+    if cypher.len() < 2 {
+        return Err(ParseError::end_of_input(input, "fewer than two numbers"));
+    }
+    Ok(cypher)
 }
 
-fn find_invalid_number(vec: &Vec<u64>, preamble_len: usize) -> u64 {
-    let is_valid = |num, slice: &[_]| {
+fn find_invalid_number(vec: &[u64], preamble_len: usize) -> u64 {
+    let is_valid = |num, slice: &[u64]| {
         for (i, a) in slice.iter().enumerate() {
             for (j, b) in slice.iter().enumerate() {
-                if i != j && a + b == num {
+                if i != j && a.checked_add(*b) == Some(num) {
                     return true;
                 }
             }
@@ -14,36 +24,56 @@ fn find_invalid_number(vec: &Vec<u64>, preamble_len: usize) -> u64 {
         false
     };
     for win in vec.windows(preamble_len + 1) {
+        progress::check();
         let num = win[preamble_len];
         let slice = &win[..preamble_len];
 
         if !is_valid(num, slice) {
+            explain::fact(
+                "invalid",
+                format_args!(
+                    "{} is not a sum of two of the {} numbers before it",
+                    num, preamble_len
+                ),
+            );
             return num;
         }
     }
     0
 }
 
-fn part1(cypher: &Vec<u64>) -> u64 {
-    find_invalid_number(cypher, 25)
+/// Length of the preamble of the real input, the sample uses a shorter one.
+const PREAMBLE_LEN: usize = 25;
+
+pub fn part1_with_preamble(cypher: &[u64], preamble_len: usize) -> u64 {
+    find_invalid_number(cypher, preamble_len)
+}
+
+pub fn part1(cypher: &[u64]) -> u64 {
+    part1_with_preamble(cypher, PREAMBLE_LEN)
 }
 
-fn part2(cypher: &Vec<u64>, invalid_number: u64) -> u64 {
-    let len = cypher.len();
-    for i in 0..len - 1 {
-        let a = cypher[i];
+fn find_weakness(cypher: &[u64], invalid_number: u64) -> u64 {
+    for (i, &a) in cypher.iter().enumerate() {
+        progress::check();
         let mut sum = a;
         let mut min = a;
         let mut max = a;
-        for j in (i + 1)..len {
-            let b = cypher[j];
-            sum += b;
-            if sum > invalid_number {
-                break;
-            }
+        for (j, &b) in cypher.iter().enumerate().skip(i + 1) {
+            sum = match sum.checked_add(b) {
+                Some(sum) if sum <= invalid_number => sum,
+                _ => break,
+            };
             min = std::cmp::min(min, b);
             max = std::cmp::max(max, b);
             if sum == invalid_number {
+                explain::fact(
+                    "range",
+                    format_args!(
+                        "numbers at indices {} to {} add up to {}, smallest {}, largest {}",
+                        i, j, invalid_number, min, max
+                    ),
+                );
                 return min + max;
             }
         }
@@ -51,49 +81,81 @@ fn part2(cypher: &Vec<u64>, invalid_number: u64) -> u64 {
     0
 }
 
-pub fn run() {
-    let input = include_str!("input/day9.txt");
-    let cypher = &parse_input(input.lines());
-    let invalid_number = part1(cypher);
-    println!("Day 9/1: {}", invalid_number);
-    println!("Day 9/2: {}", part2(cypher, invalid_number));
+pub fn part2_with_preamble(cypher: &[u64], preamble_len: usize) -> u64 {
+    find_weakness(cypher, part1_with_preamble(cypher, preamble_len))
+}
+
+pub fn part2(cypher: &[u64]) -> u64 {
+    part2_with_preamble(cypher, PREAMBLE_LEN)
+}
+
+use crate::params::{Param, Params};
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day9;
+
+pub const SOLUTION: &dyn DynSolution = &Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    type Parsed = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+    const PARAMS: &'static [Param] = &[Param::number(
+        "preamble",
+        1,
+        u32::MAX as u64,
+        "length of the preamble [default: 25]",
+    )];
+    fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part1_with_preamble(parsed, params.number("preamble", PREAMBLE_LEN)).into()
+    }
+    fn part2_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+        part2_with_preamble(parsed, params.number("preamble", PREAMBLE_LEN)).into()
+    }
+    fn sentinel(_part: u8) -> Option<Answer> {
+        Some(Answer::UInt(0))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::explain::Fact;
 
-    const SAMPLE: &'static str = "35
-20
-15
-25
-47
-40
-62
-55
-65
-95
-102
-117
-150
-182
-127
-219
-299
-277
-309
-576
-";
+    #[test]
+    fn test_day9_explain() {
+        let cypher = parse(include_str!("samples/day9.txt")).unwrap();
+        let (answer, facts) = explain::collect(|| part2_with_preamble(&cypher, 5));
+        assert_eq!(62, answer);
+        assert_eq!(
+            vec![
+                "invalid: 127 is not a sum of two of the 5 numbers before it",
+                "range: numbers at indices 2 to 5 add up to 127, smallest 15, largest 47",
+            ],
+            facts.iter().map(Fact::to_string).collect::<Vec<_>>()
+        );
+    }
 
     #[test]
-    fn test_day9_part1_sample() {
-        let cypher = &parse_input(SAMPLE.lines());
-        assert_eq!(127, find_invalid_number(cypher, 5));
+    fn test_day9_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("35\n").is_err());
+        assert!(parse("35\n20\n").is_ok());
     }
 
     #[test]
-    fn test_day9_part2_sample() {
-        let cypher = &parse_input(SAMPLE.lines());
-        assert_eq!(62, part2(cypher, 127));
+    fn test_day9_large_numbers() {
+        let cypher = [1, 2, u64::MAX, 3, 100];
+        assert_eq!(100, part1_with_preamble(&cypher, 3));
+        assert_eq!(0, part2_with_preamble(&cypher, 3));
     }
 }
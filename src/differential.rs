@@ -0,0 +1,223 @@
+//! Randomized differential testing of two implementations of the same process.
+//!
+//! [`check`] generates cases with a seeded [`Rng`] and evaluates a property comparing the
+//! implementations on each of them. The first failing case is shrunk to a minimal one which
+//! still fails and reported together with the seed that reproduces it.
+//!
+//! The number of cases and the seed can be overridden with the `AOC_DIFF_CASES` and
+//! `AOC_DIFF_SEED` environment variables, e.g. to run a longer search or to replay a failure.
+
+use std::env;
+use std::fmt::{self, Debug};
+
+/// Small pseudo random generator (SplitMix64), deterministic for a seed.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed number from `low` to `high` inclusive.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        assert!(low <= high, "empty range");
+        low + (self.next_u64() % (high - low + 1) as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i));
+        }
+    }
+}
+
+pub struct Config {
+    pub cases: usize,
+    pub seed: u64,
+}
+
+impl Config {
+    /// The `cases` and `seed` unless overridden by the environment.
+    pub fn from_env(cases: usize, seed: u64) -> Config {
+        let var = |name| env::var(name).ok().and_then(|v| v.parse().ok());
+        Config {
+            cases: var("AOC_DIFF_CASES").map_or(cases, |v: u64| v as usize),
+            seed: var("AOC_DIFF_SEED").unwrap_or(seed),
+        }
+    }
+}
+
+/// Case on which the implementations disagree.
+#[derive(Debug)]
+pub struct Counterexample<T> {
+    pub seed: u64,
+    /// Index of the failing case among the cases generated for the seed.
+    pub case: usize,
+    pub original: T,
+    pub shrunk: T,
+    pub shrink_steps: usize,
+    pub message: String,
+}
+
+impl<T: Debug> fmt::Display for Counterexample<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "implementations disagree: {}", self.message)?;
+        writeln!(
+            f,
+            "  minimal case: {:?} (after {} shrinking steps)",
+            self.shrunk, self.shrink_steps
+        )?;
+        writeln!(f, "  original case: {:?}", self.original)?;
+        write!(
+            f,
+            "  case {} of AOC_DIFF_SEED={} reproduces it",
+            self.case, self.seed
+        )
+    }
+}
+
+/// Checks the `property` on cases made by `generate`, every case with its own seed.
+///
+/// A failing case is repeatedly replaced by the first of its `shrink` candidates which
+/// still fails, until none of them does.
+pub fn check<T, G, S, P>(
+    config: &Config,
+    generate: G,
+    shrink: S,
+    property: P,
+) -> Result<(), Counterexample<T>>
+where
+    T: Clone + Debug,
+    G: Fn(&mut Rng) -> T,
+    S: Fn(&T) -> Vec<T>,
+    P: Fn(&T) -> Result<(), String>,
+{
+    let mut seeds = Rng::new(config.seed);
+    for case in 0..config.cases {
+        let original = generate(&mut Rng::new(seeds.next_u64()));
+        let mut message = match property(&original) {
+            Ok(()) => continue,
+            Err(message) => message,
+        };
+        let mut shrunk = original.clone();
+        let mut shrink_steps = 0;
+        'shrinking: loop {
+            for candidate in shrink(&shrunk) {
+                if let Err(m) = property(&candidate) {
+                    shrunk = candidate;
+                    message = m;
+                    shrink_steps += 1;
+                    continue 'shrinking;
+                }
+            }
+            break;
+        }
+        return Err(Counterexample {
+            seed: config.seed,
+            case,
+            original,
+            shrunk,
+            shrink_steps,
+            message,
+        });
+    }
+    Ok(())
+}
+
+/// Property comparing the results of two implementations.
+pub fn agree<R: PartialEq + Debug>(expected: R, actual: R) -> Result<(), String> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(format!("{:?} != {:?}", expected, actual))
+    }
+}
+
+/// Smaller numbers down to `low`: `low` itself, the halfway point and the predecessor.
+pub fn shrink_number(n: usize, low: usize) -> Vec<usize> {
+    if n <= low {
+        return Vec::new();
+    }
+    let mut smaller = vec![low, low + (n - low) / 2, n - 1];
+    smaller.retain(|&m| m >= low && m < n);
+    smaller.dedup();
+    smaller
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_differential_rng() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..100).map(|_| rng.range(3, 7)).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(1), numbers(1));
+        assert_ne!(numbers(1), numbers(2));
+        assert!(numbers(1).iter().all(|n| (3..=7).contains(n)));
+        assert!((3..=7).all(|n| numbers(1).contains(&n)));
+        let mut items = (0..10).collect::<Vec<_>>();
+        Rng::new(5).shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!((0..10).collect::<Vec<_>>(), items);
+    }
+
+    #[test]
+    fn test_differential_shrinks_counterexample() {
+        let config = Config {
+            cases: 100,
+            seed: 1,
+        };
+        let result = check(
+            &config,
+            |rng| rng.range(0, 1000),
+            |&n| shrink_number(n, 0),
+            |&n| agree(n < 37, true),
+        );
+        let counterexample = result.unwrap_err();
+        assert_eq!(37, counterexample.shrunk);
+        assert!(counterexample.original >= 37);
+        assert_eq!("false != true", counterexample.message);
+        assert!(counterexample.to_string().contains("minimal case: 37"));
+
+        // the same seed finds the same case
+        let again = check(
+            &config,
+            |rng| rng.range(0, 1000),
+            |_| vec![],
+            |&n| agree(n < 37, true),
+        );
+        let again = again.unwrap_err();
+        assert_eq!(
+            (counterexample.case, counterexample.original),
+            (again.case, again.shrunk)
+        );
+    }
+
+    #[test]
+    fn test_differential_passes() {
+        let config = Config { cases: 50, seed: 7 };
+        let result = check(
+            &config,
+            |rng| rng.range(0, 10),
+            |&n| shrink_number(n, 0),
+            |&n| agree(n * 2, n + n),
+        );
+        assert!(result.is_ok());
+        assert_eq!(vec![0, 5, 9], shrink_number(10, 0));
+        assert_eq!(vec![3], shrink_number(4, 3));
+        assert!(shrink_number(3, 3).is_empty());
+        assert!(shrink_number(0, 0).is_empty());
+        assert!(shrink_number(2, 5).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+//! Intermediate facts of the solvers, explaining how they came to their answers.
+//!
+//! The solvers call [`fact`] with what they found on the way, like the entries of day 1 which
+//! add up to 2020. Outside of [`collect`] the facts are dropped without being formatted, so
+//! that solving stays as fast as without them.
+
+use std::cell::RefCell;
+use std::fmt;
+
+/// Named intermediate result of a solver.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fact {
+    pub name: &'static str,
+    pub value: String,
+}
+
+impl fmt::Display for Fact {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+thread_local! {
+    static FACTS: RefCell<Option<Vec<Fact>>> = const { RefCell::new(None) };
+}
+
+/// Puts back the facts collected before [`collect`], also when `f` unwinds.
+struct Restore(Option<Vec<Fact>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        FACTS.with(|facts| *facts.borrow_mut() = previous);
+    }
+}
+
+/// Runs `f` and returns its result with the facts it stated, in their order.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<Fact>) {
+    let restore = Restore(FACTS.with(|facts| facts.replace(Some(Vec::new()))));
+    let value = f();
+    let collected = FACTS
+        .with(|facts| facts.borrow_mut().take())
+        .unwrap_or_default();
+    drop(restore);
+    (value, collected)
+}
+
+/// Whether the facts are collected, worth checking before computing an expensive one.
+pub fn enabled() -> bool {
+    FACTS.with(|facts| facts.borrow().is_some())
+}
+
+/// States the fact `name` with the `value`, formatted only when the facts are collected.
+pub fn fact(name: &'static str, value: impl fmt::Display) {
+    FACTS.with(|facts| {
+        if let Some(facts) = facts.borrow_mut().as_mut() {
+            facts.push(Fact {
+                name,
+                value: value.to_string(),
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    struct Expensive;
+
+    impl fmt::Display for Expensive {
+        fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+            panic!("formatted without being collected");
+        }
+    }
+
+    #[test]
+    fn test_explain_collect() {
+        fact("dropped", Expensive);
+        assert!(!enabled());
+        let (answer, facts) = collect(|| {
+            fact("pair", format_args!("{} + {}", 1721, 299));
+            let (_, inner) = collect(|| fact("inner", 1));
+            assert_eq!(1, inner.len());
+            fact("enabled", enabled());
+            42
+        });
+        assert_eq!(42, answer);
+        let facts: Vec<String> = facts.iter().map(Fact::to_string).collect();
+        assert_eq!(vec!["pair: 1721 + 299", "enabled: true"], facts);
+        assert!(!enabled());
+    }
+
+    #[test]
+    fn test_explain_unwind() {
+        let result = panic::catch_unwind(|| collect(|| panic!("boom")));
+        assert!(result.is_err());
+        assert!(!enabled());
+    }
+}
@@ -0,0 +1,216 @@
+//! Frames of the cellular automata, one per generation, exported as text and images.
+//!
+//! Days 11, 17 and 24 evolve their state generation by generation, their `frames` functions
+//! record every generation of a part. A [`Frame`] is written as a char map to a `.txt` file
+//! and as an image, PGM for square cells and SVG for the hexagonal tiles of day 24.
+
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::{day11, day17, day24, ParseError};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Days whose generations can be recorded.
+pub const DAYS: [u8; 3] = [11, 17, 24];
+
+/// Pixels per side of a square cell in the PGM images.
+const SCALE: usize = 4;
+/// Radius of a hexagonal tile in the SVG images.
+const HEX_RADIUS: f64 = 10.0;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Frame {
+    /// Char map of square cells, `#` is drawn black, `.` white and any other char gray.
+    Map(Grid<char>),
+    /// Axial coordinates of the black hexagonal tiles, `(1, 0)` is east and `(0, 1)` north west.
+    Hex(Vec<Point<i32>>),
+}
+
+fn shade(c: char) -> u8 {
+    match c {
+        '#' => 0,
+        '.' => 255,
+        ' ' => 224,
+        _ => 160,
+    }
+}
+
+impl Frame {
+    /// The extension of the image format of the frame.
+    pub fn image_extension(&self) -> &'static str {
+        match self {
+            Frame::Map(_) => "pgm",
+            Frame::Hex(_) => "svg",
+        }
+    }
+
+    /// The frame as a char map.
+    ///
+    /// Rows of hexagonal tiles are shifted by half a tile, so every other char is a space.
+    pub fn to_text(&self) -> String {
+        match self {
+            Frame::Map(grid) => grid.to_string(),
+            Frame::Hex(tiles) => {
+                let cells: Vec<_> = tiles.iter().map(|&tile| hex_cell(tile)).collect();
+                let (min, max) = bounds(&cells);
+                let grid = Grid::from_fn(
+                    (max.x() - min.x() + 1) as usize,
+                    (max.y() - min.y() + 1) as usize,
+                    |x, y| {
+                        let cell = min + Point::new(x as i32, y as i32);
+                        if cells.contains(&cell) {
+                            '#'
+                        } else if (cell.x() + cell.y()) % 2 == 0 {
+                            '.'
+                        } else {
+                            ' '
+                        }
+                    },
+                );
+                grid.to_string()
+            }
+        }
+    }
+
+    pub fn write_image(&self, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            Frame::Map(grid) => {
+                write!(
+                    out,
+                    "P5\n{} {}\n255\n",
+                    grid.width() * SCALE,
+                    grid.height() * SCALE
+                )?;
+                for y in 0..grid.height() {
+                    let row: Vec<u8> = grid
+                        .row(y)
+                        .iter()
+                        .flat_map(|&c| std::iter::repeat_n(shade(c), SCALE))
+                        .collect();
+                    for _ in 0..SCALE {
+                        out.write_all(&row)?;
+                    }
+                }
+                Ok(())
+            }
+            Frame::Hex(tiles) => write_svg(tiles, out),
+        }
+    }
+}
+
+/// Position of the tile in the char map, the columns are half a tile wide.
+fn hex_cell(tile: Point<i32>) -> Point<i32> {
+    Point::new(2 * tile.x() - tile.y(), -tile.y())
+}
+
+fn bounds(cells: &[Point<i32>]) -> (Point<i32>, Point<i32>) {
+    let min = |axis| cells.iter().map(|c| c[axis]).min().unwrap_or(0);
+    let max = |axis| cells.iter().map(|c| c[axis]).max().unwrap_or(0);
+    (Point::new(min(0), min(1)), Point::new(max(0), max(1)))
+}
+
+fn write_svg(tiles: &[Point<i32>], out: &mut impl Write) -> io::Result<()> {
+    // pointy topped hexagons, half a tile wide columns and rows 3/4 of a tile high
+    let half_width = HEX_RADIUS * 3f64.sqrt() / 2.0;
+    let row_height = HEX_RADIUS * 1.5;
+    let cells: Vec<_> = tiles.iter().map(|&tile| hex_cell(tile)).collect();
+    let (min, max) = bounds(&cells);
+    let width = (max.x() - min.x() + 2) as f64 * half_width;
+    let height = (max.y() - min.y()) as f64 * row_height + 2.0 * HEX_RADIUS;
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}">"#,
+        width.ceil(),
+        height.ceil()
+    )?;
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+    for cell in cells {
+        let cx = (cell.x() - min.x() + 1) as f64 * half_width;
+        let cy = (cell.y() - min.y()) as f64 * row_height + HEX_RADIUS;
+        let corners: Vec<String> = (0..6)
+            .map(|i| {
+                let angle = std::f64::consts::PI / 3.0 * i as f64 + std::f64::consts::PI / 6.0;
+                format!(
+                    "{:.1},{:.1}",
+                    cx + HEX_RADIUS * angle.cos(),
+                    cy + HEX_RADIUS * angle.sin()
+                )
+            })
+            .collect();
+        writeln!(out, r#"<polygon points="{}"/>"#, corners.join(" "))?;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// Parses the `input` and records the generations of the `part` of the `day`.
+///
+/// Returns `None` for the days without frames, see [`DAYS`].
+pub fn record(day: u8, part: u8, input: &str) -> Option<Result<Vec<Frame>, ParseError>> {
+    let frames = match day {
+        11 => day11::parse(input).map(|seats| day11::frames(&seats, part)),
+        17 => day17::parse(input).map(|cube| day17::frames(&cube, part)),
+        24 => day24::parse(input).map(|data| day24::frames(&data, part)),
+        _ => return None,
+    };
+    Some(frames)
+}
+
+/// Writes every frame as `PREFIX-NNN.txt` and an image with the same name into the `dir`,
+/// returns the paths of the written files.
+pub fn export(frames: &[Frame], dir: &Path, prefix: &str) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut paths = Vec::new();
+    for (i, frame) in frames.iter().enumerate() {
+        let name = format!("{}-{:03}", prefix, i);
+        let text_path = dir.join(format!("{}.txt", name));
+        fs::write(&text_path, frame.to_text())?;
+        let image_path = dir.join(format!("{}.{}", name, frame.image_extension()));
+        let mut image = io::BufWriter::new(fs::File::create(&image_path)?);
+        frame.write_image(&mut image)?;
+        image.flush()?;
+        paths.push(text_path);
+        paths.push(image_path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Cursor;
+
+    #[test]
+    fn test_frames_map() {
+        let frame = Frame::Map(Grid::parse(Cursor::new("#L\n..\n")).unwrap());
+        assert_eq!("#L\n..\n", frame.to_text());
+        let mut image = Vec::new();
+        frame.write_image(&mut image).unwrap();
+        let header = "P5\n8 8\n255\n";
+        assert_eq!(header.as_bytes(), &image[..header.len()]);
+        let pixels = &image[header.len()..];
+        assert_eq!(64, pixels.len());
+        assert_eq!([0, 0, 0, 0, 160, 160, 160, 160], pixels[..8]);
+        assert!(pixels[32..].iter().all(|&p| p == 255));
+    }
+
+    #[test]
+    fn test_frames_hex() {
+        let tiles = vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)];
+        let frame = Frame::Hex(tiles);
+        assert_eq!("# . \n # #\n", frame.to_text());
+        let mut svg = Vec::new();
+        frame.write_image(&mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(3, svg.matches("<polygon ").count());
+    }
+
+    #[test]
+    fn test_frames_record() {
+        assert!(record(1, 1, "1\n").is_none());
+        let frames = record(11, 1, "L.\nLL\n").unwrap().unwrap();
+        assert_eq!(2, frames.len());
+        assert!(record(11, 1, "Lx\n").unwrap().is_err());
+    }
+}
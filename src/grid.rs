@@ -0,0 +1,349 @@
+//! Rectangular grid of cells, stored row by row in a single vector.
+//!
+//! Positions are `(x, y)` pairs, `x` is the column and `y` the row counted from the top left
+//! corner. Indexing with a position panics outside of the grid, [`Grid::get`] and
+//! [`Grid::get_wrapping`] accept any signed coordinates.
+
+use crate::parser::Cursor;
+use crate::ParseError;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// Steps to the 4 orthogonal neighbours.
+pub const DIRECTIONS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Steps to all 8 neighbours, including the diagonal ones.
+pub const DIRECTIONS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Cell which can be read from and written as a single character of a char map.
+pub trait Cell: Sized {
+    /// Description of the accepted characters used in parse errors, e.g. `'#' or '.'`.
+    const EXPECTED: &'static str;
+
+    fn from_char(c: char) -> Option<Self>;
+    fn to_char(&self) -> char;
+}
+
+/// `#` is `true`, `.` is `false`.
+impl Cell for bool {
+    const EXPECTED: &'static str = "'#' or '.'";
+
+    fn from_char(c: char) -> Option<bool> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+    fn to_char(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+/// Any char stands for itself.
+impl Cell for char {
+    const EXPECTED: &'static str = "any character";
+
+    fn from_char(c: char) -> Option<char> {
+        Some(c)
+    }
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells listed row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the number of cells doesn't match the dimensions.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(width * height, cells.len(), "cells don't fit the grid");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Creates a grid with cells given by `f(x, y)`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Grid::from_vec(width, height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the cell at the position or `None` outside of the grid.
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        self.index_of(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    /// Returns the cell at the position with the grid repeated infinitely in all directions.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize);
+        let y = y.rem_euclid(self.height as isize);
+        &self[(x as usize, y as usize)]
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(x < self.width, "column out of the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Iterates over the cells row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.cells.iter()
+    }
+
+    /// Iterates over all positions row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Positions of the orthogonal neighbours inside of the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &DIRECTIONS4)
+    }
+
+    /// Positions of all neighbours inside of the grid, including the diagonal ones.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &DIRECTIONS8)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        directions: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        directions
+            .iter()
+            .filter_map(move |&(dx, dy)| self.step((x, y), (dx, dy)))
+    }
+
+    /// Positions from the one next to `(x, y)` in the `direction` up to the edge of the grid.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut pos = Some((x, y));
+        std::iter::from_fn(move || {
+            pos = self.step(pos?, direction);
+            pos
+        })
+    }
+
+    /// Creates a grid of the same size with cells mapped by `f`.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    fn step(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let x = x as isize + dx;
+        let y = y as isize + dy;
+        self.index_of(x, y).map(|_| (x as usize, y as usize))
+    }
+
+    fn index_of(&self, x: isize, y: isize) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(y as usize * self.width + x as usize)
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with all cells set to the `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid::from_vec(width, height, vec![value; width * height])
+    }
+
+    /// Returns the grid rotated by 90 degrees clockwise.
+    pub fn rotate_right(&self) -> Grid<T> {
+        let height = self.height;
+        Grid::from_fn(height, self.width, |x, y| self[(y, height - 1 - x)].clone())
+    }
+
+    /// Returns the grid mirrored along the vertical axis.
+    pub fn flip_horizontally(&self) -> Grid<T> {
+        let width = self.width;
+        Grid::from_fn(width, self.height, |x, y| self[(width - 1 - x, y)].clone())
+    }
+}
+
+impl<T: Cell> Grid<T> {
+    /// Parses the char map with one row per line.
+    pub fn parse(text: Cursor) -> Result<Grid<T>, ParseError> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for mut line in text.lines() {
+            let len = line.rest().chars().count();
+            if len == 0 || *width.get_or_insert(len) != len {
+                return Err(line.error("rows of the grid differ in width"));
+            }
+            while let Some(c) = line.peek() {
+                let cell = T::from_char(c)
+                    .ok_or_else(|| line.error_ahead(1, format!("expected {}", T::EXPECTED)))?;
+                cells.push(cell);
+                line.take_char();
+            }
+        }
+        match width {
+            Some(width) => Ok(Grid::from_vec(width, cells.len() / width, cells)),
+            None => Err(text.error_at(&text.rest()[text.rest().len()..], "empty grid")),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "position out of the grid"
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "position out of the grid"
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Writes the char map, every row is terminated by a newline.
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            let row: String = self.row(y).iter().map(Cell::to_char).collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SAMPLE: &str = "\
+#..
+#.#
+";
+
+    fn sample() -> Grid<bool> {
+        Grid::parse(Cursor::new(SAMPLE)).unwrap()
+    }
+
+    #[test]
+    fn test_grid_parse_and_display() {
+        let grid = sample();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert!(grid[(0, 1)] && !grid[(1, 1)] && grid[(2, 1)]);
+        assert_eq!(SAMPLE, grid.to_string());
+    }
+
+    #[test]
+    fn test_grid_parse_errors() {
+        let input = "#.\n#x\n";
+        let err = Grid::<bool>::parse(Cursor::new(input)).unwrap_err();
+        assert_eq!((2, 2, "x"), (err.line, err.column, err.text.as_str()));
+        assert_eq!("expected '#' or '.'", err.message);
+        let input = "#.\n#\n";
+        let err = Grid::<bool>::parse(Cursor::new(input)).unwrap_err();
+        assert_eq!((2, 1, "#"), (err.line, err.column, err.text.as_str()));
+        let input = "#.\n\n";
+        assert!(Grid::<bool>::parse(Cursor::new(input)).is_err());
+        let input = "";
+        assert!(Grid::<bool>::parse(Cursor::new(input)).is_err());
+    }
+
+    #[test]
+    fn test_grid_get() {
+        let grid = sample();
+        assert_eq!(Some(&true), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 1));
+        assert_eq!(None, grid.get(0, -1));
+        assert_eq!(&true, grid.get_wrapping(5, 3));
+        assert_eq!(&false, grid.get_wrapping(-2, -2));
+        assert_eq!(vec![&true, &true], grid.column(0).collect::<Vec<_>>());
+        assert_eq!(&[true, false, true], grid.row(1));
+    }
+
+    #[test]
+    fn test_grid_neighbours() {
+        let grid = sample();
+        let mut n4 = grid.neighbours4(0, 0).collect::<Vec<_>>();
+        n4.sort_unstable();
+        assert_eq!(vec![(0, 1), (1, 0)], n4);
+        assert_eq!(5, grid.neighbours8(1, 0).count());
+        assert_eq!(5, grid.neighbours8(1, 1).count());
+    }
+
+    #[test]
+    fn test_grid_ray() {
+        let grid = Grid::filled(4, 3, 0);
+        let ray = grid.ray(0, 0, (1, 1)).collect::<Vec<_>>();
+        assert_eq!(vec![(1, 1), (2, 2)], ray);
+        assert_eq!(3, grid.ray(0, 2, (1, 0)).count());
+        assert_eq!(0, grid.ray(0, 2, (0, 1)).count());
+    }
+
+    #[test]
+    fn test_grid_rotate_and_flip() {
+        let grid = sample();
+        assert_eq!("##\n..\n#.\n", grid.rotate_right().to_string());
+        assert_eq!("..#\n#.#\n", grid.flip_horizontally().to_string());
+        let rotated = (0..4).fold(grid.clone(), |g, _| g.rotate_right());
+        assert_eq!(grid, rotated);
+    }
+}
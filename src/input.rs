@@ -0,0 +1,142 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_DIR: &str = "inputs";
+
+#[derive(PartialEq, Debug)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    pub fn from_arg(arg: &str) -> Source {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+}
+
+pub fn embedded(day: u8) -> &'static str {
+    match day {
+        1 => include_str!("input/day1.txt"),
+        2 => include_str!("input/day2.txt"),
+        3 => include_str!("input/day3.txt"),
+        4 => include_str!("input/day4.txt"),
+        5 => include_str!("input/day5.txt"),
+        6 => include_str!("input/day6.txt"),
+        7 => include_str!("input/day7.txt"),
+        8 => include_str!("input/day8.txt"),
+        9 => include_str!("input/day9.txt"),
+        10 => include_str!("input/day10.txt"),
+        11 => include_str!("input/day11.txt"),
+        12 => include_str!("input/day12.txt"),
+        13 => include_str!("input/day13.txt"),
+        14 => include_str!("input/day14.txt"),
+        15 => include_str!("input/day15.txt"),
+        16 => include_str!("input/day16.txt"),
+        17 => include_str!("input/day17.txt"),
+        18 => include_str!("input/day18.txt"),
+        19 => include_str!("input/day19.txt"),
+        20 => include_str!("input/day20.txt"),
+        21 => include_str!("input/day21.txt"),
+        22 => include_str!("input/day22.txt"),
+        23 => include_str!("input/day23.txt"),
+        24 => include_str!("input/day24.txt"),
+        25 => include_str!("input/day25.txt"),
+        _ => unreachable!("no input embedded for day {}", day),
+    }
+}
+
+/// Source file of the embedded input, present when the program runs from its repository.
+pub fn embedded_path(day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/input")
+        .join(format!("day{}.txt", day))
+}
+
+pub fn path_in_dir(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}.txt", day))
+}
+
+fn read_raw(source: &Source) -> Result<String, String> {
+    match source {
+        Source::Stdin => {
+            let mut buf = String::new();
+            io::stdin()
+                .read_to_string(&mut buf)
+                .map_err(|e| format!("cannot read input from stdin: {}", e))?;
+            Ok(buf)
+        }
+        Source::File(path) => fs::read_to_string(path)
+            .map_err(|e| format!("cannot read input file '{}': {}", path.display(), e)),
+    }
+}
+
+// inputs saved on Windows may come with CRLF line endings, which would break
+// the parsers splitting on blank lines
+pub fn normalize(input: String) -> String {
+    if input.contains('\r') {
+        input.replace("\r\n", "\n")
+    } else {
+        input
+    }
+}
+
+pub fn read(source: &Source) -> Result<String, String> {
+    read_raw(source).map(normalize)
+}
+
+/// Loads the input of the given day from `dayN.txt` in the lookup directory,
+/// falling back to the input embedded in the binary when there is no such file.
+pub fn load(day: u8, dir: &Path) -> Result<String, String> {
+    let path = path_in_dir(dir, day);
+    if path.is_file() {
+        read(&Source::File(path))
+    } else {
+        Ok(embedded(day).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_source_from_arg() {
+        assert_eq!(Source::Stdin, Source::from_arg("-"));
+        assert_eq!(
+            Source::File(PathBuf::from("my/day1.txt")),
+            Source::from_arg("my/day1.txt")
+        );
+    }
+
+    #[test]
+    fn test_input_normalize_line_endings() {
+        assert_eq!("a\nb\n\nc\n", normalize("a\r\nb\r\n\r\nc\r\n".to_string()));
+    }
+
+    #[test]
+    fn test_input_load_falls_back_to_embedded() {
+        let dir = Path::new("no-such-directory");
+        assert_eq!(Ok(embedded(8).to_string()), load(8, dir));
+    }
+
+    #[test]
+    fn test_input_load_from_dir() {
+        let dir = std::env::temp_dir().join(format!("aoc_2020_input_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(path_in_dir(&dir, 1), "1721\r\n979\r\n").unwrap();
+        let loaded = load(1, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(Ok("1721\n979\n".to_string()), loaded);
+    }
+
+    #[test]
+    fn test_input_read_missing_file() {
+        let source = Source::File(PathBuf::from("no-such-directory/day1.txt"));
+        assert!(read(&source).is_err());
+    }
+}
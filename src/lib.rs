@@ -1,64 +1,195 @@
-pub fn to_lines(input: &str) -> Vec<String> {
-    input.lines().map(String::from).collect()
+//! Solutions of the Advent of Code 2020 puzzles.
+//!
+//! Every day has its own module with a `parse` function turning the puzzle input into the
+//! day's data and `part1`/`part2` solvers working on that data:
+//!
+//! ```
+//! let code = aoc_2020::day8::parse("nop +0\nacc +1\njmp -2\n").unwrap();
+//! assert_eq!(1, aoc_2020::day8::part1(&code));
+//! ```
+//!
+//! All days are also available through the [`registry`], and [`solve`] runs any of them
+//! directly on the input text. Generations of the cellular automata can be recorded with
+//! [`frames`]. The sample inputs of the puzzle descriptions and their expected answers are
+//! listed in [`samples`]. The long loops of the solvers report their [`progress`] and can be
+//! cancelled by it. Values of the puzzles like the number of turns of day 15 are [`params`]
+//! which can be changed to explore variants of them. The solvers [`explain`] their answers with
+//! the intermediate facts they find.
+//!
+//! The parsers are built on the [`parser::Cursor`], which reports malformed input as a
+//! [`ParseError`] pointing at the offending text.
+
+// the tests of the days are kept as they were written, before these lints
+#![cfg_attr(
+    test,
+    allow(
+        clippy::bool_assert_comparison,
+        clippy::needless_borrow,
+        clippy::redundant_closure,
+        clippy::redundant_static_lifetimes,
+        clippy::useless_vec
+    )
+)]
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+#[cfg(test)]
+mod differential;
+pub mod explain;
+pub mod frames;
+pub mod grid;
+pub mod params;
+pub mod parser;
+pub mod point;
+pub mod progress;
+pub mod registry;
+pub mod samples;
+pub mod solution;
+
+pub use solution::Answer;
+use std::fmt;
+use std::str::FromStr;
+
+/// Parses the `input` and solves the `part` of the puzzle of the `day`.
+///
+/// # Panics
+///
+/// Panics when there is no such day or part, see [`registry::find`].
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, ParseError> {
+    solve_with(day, part, input, &params::Params::default())
 }
 
-pub fn to_i32s(input: &str) -> Vec<i32> {
-    parse_to_vec(input.trim().split_whitespace())
+/// Like [`solve`], with the `params` of the day set to other values than the puzzle's.
+///
+/// # Panics
+///
+/// Panics when there is no such day or part.
+pub fn solve_with(
+    day: u8,
+    part: u8,
+    input: &str,
+    params: &params::Params,
+) -> Result<Answer, ParseError> {
+    let solution = registry::find(day).unwrap_or_else(|| panic!("no solution for day {}", day));
+    let parsed = solution.parse(input)?;
+    Ok(solution.solve_with(parsed.as_ref(), part, params))
 }
 
-pub fn parse_unwrap<T: std::str::FromStr>(s: &str) -> T {
-    s.parse::<T>().ok().unwrap()
+/// Error of parsing puzzle input, pointing at the offending text.
+///
+/// Line and column are 1-based; column counts characters, not bytes.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
 }
 
-pub fn parse_to_vec<'a, T: std::str::FromStr>(it: impl Iterator<Item = &'a str>) -> Vec<T> {
-    it.map(parse_unwrap).collect::<Vec<T>>()
+impl ParseError {
+    /// Creates an error for `fragment` which has to be a slice of the `input`,
+    /// its location in the input gives the line and column.
+    pub fn at(input: &str, fragment: &str, message: impl Into<String>) -> ParseError {
+        let start = input.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&offset| offset <= input.len())
+            .expect("fragment is not a part of the input");
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: fragment.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn end_of_input(input: &str, message: impl Into<String>) -> ParseError {
+        ParseError::at(input, &input[input.len()..], message)
+    }
 }
 
-// poor man's implementation of the str::split_once since it's experimental
-pub fn split_once_char(input: &str, delimiter: char) -> (&str, &str) {
-    vec2tuple(&input.splitn(2, delimiter).collect())
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if !self.text.is_empty() {
+            write!(f, " in '{}'", self.text)?;
+        }
+        Ok(())
+    }
 }
-pub fn split_once_str<'a>(input: &'a str, delimiter: &str) -> (&'a str, &'a str) {
-    vec2tuple(&input.splitn(2, delimiter).collect())
+
+impl std::error::Error for ParseError {}
+
+pub fn to_lines(input: &str) -> Vec<String> {
+    input.lines().map(String::from).collect()
 }
 
-fn vec2tuple<'a>(v: &Vec<&'a str>) -> (&'a str, &'a str) {
-    if v.len() != 2 {
-        panic!("Expected 2 values in a vector");
-    }
-    (v[0], v[1])
+/// Parses the `fragment` of the `input`.
+pub fn try_parse<T: FromStr>(input: &str, fragment: &str) -> Result<T, ParseError> {
+    fragment
+        .parse::<T>()
+        .map_err(|_| ParseError::at(input, fragment, "invalid value"))
 }
 
-pub mod point {
-    use std::ops::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
-    pub struct Point<T> {
-        x: T,
-        y: T,
-    }
-    impl<T: Default> Point<T> {
-        pub fn new(x: T, y: T) -> Point<T> {
-            Point { x, y }
-        }
-        pub fn zero() -> Point<T> {
-            Point::new(Default::default(), Default::default())
-        }
-    }
-    impl<T: From<T> + Default> From<(T, T)> for Point<T> {
-        fn from((x, y): (T, T)) -> Self {
-            Point::new(x, y)
-        }
+    const INPUT: &str = "abc\nde fgh\n\nźx 12";
+
+    #[test]
+    fn test_lib_parse_error_location() {
+        let err = ParseError::at(INPUT, &INPUT[7..10], "bad");
+        assert_eq!((2, 4, "fgh"), (err.line, err.column, err.text.as_str()));
+        assert_eq!("line 2, column 4: bad in 'fgh'", err.to_string());
+
+        let err = ParseError::at(INPUT, &INPUT[16..], "bad");
+        assert_eq!((4, 4, "12"), (err.line, err.column, err.text.as_str()));
+
+        let err = ParseError::end_of_input(INPUT, "missing");
+        assert_eq!((4, 6), (err.line, err.column));
+        assert_eq!("line 4, column 6: missing", err.to_string());
     }
-    impl<T: AddAssign<T> + Add<Output = T> + Default + Copy> AddAssign for Point<T> {
-        fn add_assign(&mut self, other: Self) {
-            *self = Self::new(self.x + other.x, self.y + other.y)
-        }
+
+    #[test]
+    #[should_panic]
+    fn test_lib_parse_error_outside_of_input() {
+        ParseError::at(INPUT, "abc", "bad");
     }
-    impl<T: Add<Output = T> + Default> Add for Point<T> {
-        type Output = Self;
-        fn add(self, other: Self) -> Self::Output {
-            Self::new(self.x + other.x, self.y + other.y)
-        }
+
+    #[test]
+    fn test_lib_try_parse() {
+        assert_eq!(Ok(12), try_parse::<u8>(INPUT, &INPUT[16..]));
+        let err = try_parse::<u8>(INPUT, &INPUT[4..6]).unwrap_err();
+        assert_eq!((2, 1, "de"), (err.line, err.column, err.text.as_str()));
     }
 }
@@ -1,56 +1,439 @@
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod lib;
+mod answers;
+mod cli;
+mod input;
+mod matrix;
+mod output;
+mod repl;
+mod report;
+mod runner;
+mod scaffold;
+mod server;
+mod watch;
 
-fn main() {
-    println!("Advent Of Code 2020");
+use answers::Verdict;
+use aoc_2020::samples::{self, Sample};
+use aoc_2020::solution::DynSolution;
+use aoc_2020::{frames, registry, ParseError};
+use cli::{Args, Command, Parts};
+use output::{Format, Record};
+use runner::{format_duration, Supervision};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::Instant;
+
+fn exit_with_error(err: &str) -> ! {
+    eprintln!("error: {}", err);
+    std::process::exit(1);
+}
+
+/// Solves every selected day with `solve` and passes the results to `report` in day order.
+///
+/// Days are solved concurrently with `--jobs`. Days whose input fails to parse are reported
+/// and skipped, returns whether all of them succeeded.
+fn for_each_selected<R, F, G>(args: &Args, solve: F, mut report: G) -> bool
+where
+    R: Send,
+    F: Fn(&dyn DynSolution, &str, Parts) -> Result<R, ParseError> + Sync,
+    G: FnMut(R),
+{
+    // explicitly given input is read only once, stdin cannot be read twice
+    let explicit_input = args.input.as_ref().map(input::read);
+    let jobs = args
+        .selections
+        .iter()
+        .map(|selection| {
+            let loaded = match &explicit_input {
+                Some(read) => read.clone(),
+                None => input::load(selection.day, &args.input_dir),
+            };
+            let input = loaded.unwrap_or_else(|err| exit_with_error(&err));
+            (selection, input)
+        })
+        .collect();
+    let mut success = true;
+    runner::map_in_order(
+        jobs,
+        args.jobs,
+        |(selection, input)| {
+            let solution = registry::find(selection.day).expect("selected day is registered");
+            (selection.day, solve(solution, &input, selection.parts))
+        },
+        |(day, result)| match result {
+            Ok(result) => report(result),
+            Err(err) => {
+                eprintln!("error: day {}: {}", day, err);
+                success = false;
+            }
+        },
+    );
+    success
+}
+
+fn run(args: &Args) -> bool {
+    let answers = if args.verify {
+        Some(answers::load(&args.input_dir).unwrap_or_else(|err| exit_with_error(&err)))
+    } else {
+        None
+    };
+    let mut writer = match args.format {
+        Format::Text => None,
+        format => Some(output::Writer::new(format, args.verify)),
+    };
+    if let Some(writer) = &writer {
+        writer.begin();
+    }
+    let supervision = Supervision {
+        timeout: args.timeout,
+        // progress lines of concurrently solved days would overwrite each other
+        show_progress: args.jobs == 1 && io::stderr().is_terminal(),
+        explain: args.explain,
+    };
+    let solve = |solution: &dyn DynSolution, input: &str, parts| {
+        let params = args.params.for_day(solution.day());
+        runner::run(solution, input, parts, params, &supervision)
+    };
+    let mut verdicts = Vec::new();
+    let mut timed_out = 0;
+    let start = Instant::now();
+    let success = for_each_selected(args, solve, |result| {
+        if args.time && writer.is_none() {
+            println!(
+                "Day {} parse: {}",
+                result.day,
+                format_duration(result.parse_elapsed)
+            );
+        }
+        for part in &result.parts {
+            let answer = match &part.answer {
+                Ok(answer) => answer,
+                Err(_) => {
+                    timed_out += 1;
+                    let msg = format!(
+                        "{}: timed out after {}",
+                        result.label(part.part),
+                        format_duration(part.elapsed)
+                    );
+                    if writer.is_some() {
+                        eprintln!("error: {}", msg);
+                    } else {
+                        println!("{}", msg);
+                    }
+                    continue;
+                }
+            };
+            let verdict = answers
+                .as_ref()
+                .map(|answers| answers.check(result.day, part.part, answer));
+            if let Some(writer) = &mut writer {
+                writer.record(&Record {
+                    day: result.day,
+                    part: part.part,
+                    answer,
+                    elapsed: part.elapsed,
+                    verdict: verdict.as_ref(),
+                    facts: Some(&part.facts[..]).filter(|_| args.explain),
+                });
+            } else {
+                let mut line = format!("{}: {}", result.label(part.part), answer);
+                if args.time {
+                    line += &format!(" ({})", format_duration(part.elapsed));
+                }
+                line += &match &verdict {
+                    Some(Verdict::Correct) => " [ok]".to_string(),
+                    Some(Verdict::Mismatch { expected }) => {
+                        format!(" [wrong, expected {}]", expected)
+                    }
+                    Some(Verdict::Unknown) => " [no recorded answer]".to_string(),
+                    None => String::new(),
+                };
+                println!("{}", line);
+                for fact in &part.facts {
+                    println!("    {}", fact);
+                }
+            }
+            verdicts.extend(verdict);
+        }
+    });
+    let wrong = verdicts
+        .iter()
+        .filter(|v| matches!(v, Verdict::Mismatch { .. }))
+        .count();
+    if let Some(writer) = &writer {
+        writer.finish();
+        return success && wrong == 0 && timed_out == 0;
+    }
+    if args.time || args.jobs > 1 {
+        println!();
+        println!("Total: {}", format_duration(start.elapsed()));
+    }
+    if args.verify {
+        let count = |verdict| verdicts.iter().filter(|v| **v == verdict).count();
+        println!();
+        println!(
+            "Verified: {} correct, {} wrong, {} without recorded answer",
+            count(Verdict::Correct),
+            wrong,
+            count(Verdict::Unknown)
+        );
+    }
+    success && wrong == 0 && timed_out == 0
+}
+
+fn bench(args: &Args, runs: usize) -> bool {
+    println!("{:<14} {:>10} {:>10} {:>10}", "", "min", "median", "max");
+    let bench = |solution: &dyn DynSolution, input: &str, parts| {
+        let params = args.params.for_day(solution.day());
+        runner::bench(solution, input, parts, params, runs)
+    };
+    for_each_selected(args, bench, |result| {
+        let print_stats = |label: String, stats: &runner::Stats| {
+            println!(
+                "{:<14} {:>10} {:>10} {:>10}",
+                label,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.max)
+            );
+        };
+        print_stats(format!("Day {} parse", result.day), &result.parse);
+        for (part, stats) in &result.parts {
+            print_stats(runner::label(result.day, *part, result.single_part), stats);
+        }
+    })
+}
+
+/// Solves the samples of the selected parts, returns whether all of them give the expected
+/// answers.
+fn check_samples(args: &Args) -> bool {
+    let selected: Vec<&Sample> = samples::SAMPLES
+        .iter()
+        .filter(|sample| {
+            args.selections
+                .iter()
+                .any(|s| s.day == sample.day && s.parts.contains(sample.part))
+        })
+        .collect();
+    let (mut correct, mut wrong) = (0, 0);
+    // panics of the solvers are reported as failed samples
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    runner::map_in_order(
+        selected,
+        args.jobs,
+        |sample| {
+            let start = Instant::now();
+            let answer = runner::catch_panic(|| sample.solve());
+            (sample, answer, start.elapsed())
+        },
+        |(sample, answer, elapsed)| {
+            let single_part = registry::find(sample.day).is_some_and(|s| s.parts() == 1);
+            let mut line = format!(
+                "{} {}",
+                runner::label(sample.day, sample.part, single_part),
+                sample.name
+            );
+            for (name, value) in sample.params {
+                line += &format!(" {}={}", name, value);
+            }
+            line += ": ";
+            let passed = match answer {
+                Ok(Ok(answer)) if answer.to_string() == sample.expected => {
+                    line += &format!("{} [ok]", answer);
+                    true
+                }
+                Ok(Ok(answer)) => {
+                    line += &format!("{} [wrong, expected {}]", answer, sample.expected);
+                    false
+                }
+                Ok(Err(err)) => {
+                    line += &format!("[error: {}]", err);
+                    false
+                }
+                Err(msg) => {
+                    line += &format!("[panicked: {}]", msg);
+                    false
+                }
+            };
+            if args.time {
+                line += &format!(" ({})", format_duration(elapsed));
+            }
+            println!("{}", line);
+            if passed {
+                correct += 1;
+            } else {
+                wrong += 1;
+            }
+        },
+    );
+    std::panic::set_hook(hook);
     println!();
-    day1::run();
-    day2::run();
-    day3::run();
-    day4::run();
-    day5::run();
-    day6::run();
-    day7::run();
-    day8::run();
-    day9::run();
-    day10::run();
-    day11::run();
-    day12::run();
-    day13::run();
-    day14::run();
-    day15::run();
-    day16::run();
-    day17::run();
-    day18::run();
-    day19::run();
-    day20::run();
-    day21::run();
-    day22::run();
-    day23::run();
-    day24::run();
-    day25::run();
+    println!("Samples: {} correct, {} wrong", correct, wrong);
+    wrong == 0
+}
+
+/// Solves the selected parts and writes the report to the `output`, and as HTML next to it
+/// with `html`. Returns whether all of them were solved and the report written.
+fn write_report(args: &Args, output: &Path, html: bool) -> bool {
+    let supervision = Supervision {
+        timeout: args.timeout,
+        show_progress: io::stderr().is_terminal(),
+        explain: false,
+    };
+    let solve = |solution: &dyn DynSolution, input: &str, parts| {
+        let params = args.params.for_day(solution.day());
+        let result = runner::run(solution, input, parts, params, &supervision)?;
+        Ok(report::Entry {
+            result,
+            input_bytes: input.len(),
+            input_lines: input.lines().count(),
+        })
+    };
+    let mut entries = Vec::new();
+    let solved = for_each_selected(args, solve, |entry| entries.push(entry));
+    let timed_out = entries
+        .iter()
+        .flat_map(|entry| &entry.result.parts)
+        .filter(|part| part.answer.is_err())
+        .count();
+    let notes = report::notes_path(output);
+    let mut pages = vec![(output.to_path_buf(), report::markdown(&entries, &notes))];
+    if html {
+        pages.push((
+            output.with_extension("html"),
+            report::html(&entries, &notes),
+        ));
+    }
+    let mut written = true;
+    for (path, page) in pages {
+        match std::fs::write(&path, page) {
+            Ok(()) => println!("Report written to {}", path.display()),
+            Err(e) => {
+                eprintln!("error: cannot write '{}': {}", path.display(), e);
+                written = false;
+            }
+        }
+    }
+    solved && written && timed_out == 0
+}
+
+fn export_frames(args: &Args, output: &Path) -> bool {
+    let export = |solution: &dyn DynSolution, input: &str, parts: Parts| {
+        let day = solution.day();
+        (1..=solution.parts())
+            .filter(|&part| parts.contains(part))
+            .map(|part| {
+                let frames = frames::record(day, part, input).expect("selected day has frames")?;
+                let prefix = format!("day{}-part{}", day, part);
+                let exported = frames::export(&frames, output, &prefix)
+                    .map(|_| frames.len())
+                    .map_err(|e| format!("cannot write frames to '{}': {}", output.display(), e));
+                Ok((runner::label(day, part, false), prefix, exported))
+            })
+            .collect::<Result<Vec<_>, ParseError>>()
+    };
+    let mut written = true;
+    let parsed = for_each_selected(args, export, |parts| {
+        for (label, prefix, exported) in parts {
+            match exported {
+                Ok(count) => println!(
+                    "{}: {} frames written as {}",
+                    label,
+                    count,
+                    output.join(format!("{}-NNN.*", prefix)).display()
+                ),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    written = false;
+                }
+            }
+        }
+    });
+    parsed && written
+}
+
+/// Reads the parameters of the `--config` file, the ones given with `--param` override them.
+fn load_config(args: &mut Args) {
+    let path = match &args.config {
+        Some(path) => path,
+        None => return,
+    };
+    let text = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| exit_with_error(&format!("cannot read '{}': {}", path.display(), e)));
+    let mut params = aoc_2020::params::Settings::parse(&text)
+        .unwrap_or_else(|err| exit_with_error(&format!("{}: {}", path.display(), err)));
+    params.merge(&args.params);
+    args.params = params;
+}
+
+/// Scaffolds the `day` of the `year` in the repository of the program.
+fn new_day(root: &Path, year: u16, day: u8) -> bool {
+    let changed = scaffold::create(root, year, day).unwrap_or_else(|err| exit_with_error(&err));
+    for path in changed {
+        println!(
+            "Wrote {}",
+            path.strip_prefix(root).unwrap_or(&path).display()
+        );
+    }
+    println!();
+    println!(
+        "Paste the input into src/y{0}/input/day{1}.txt and the sample into the test of",
+        year, day
+    );
+    println!(
+        "src/y{0}/day{1}.rs, then try the solvers with 'cargo test y{0}::day{1}'.",
+        year, day
+    );
+    true
+}
+
+fn main() {
+    let mut command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            print!("{}{}", cli::USAGE, cli::params_help());
+            return;
+        }
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            eprintln!("Try 'aoc_2020 --help' for more information.");
+            std::process::exit(2);
+        }
+    };
+
+    if let Command::Run(args)
+    | Command::Bench { args, .. }
+    | Command::Matrix(args)
+    | Command::Report { args, .. } = &mut command
+    {
+        load_config(args);
+    }
+    let machine_readable = matches!(&command, Command::Run(args) if args.format != Format::Text);
+    if !machine_readable {
+        println!("Advent Of Code 2020");
+        println!();
+    }
+    let success = match command {
+        Command::Run(args) if args.watch => watch::run(&args)
+            .map(|()| true)
+            .unwrap_or_else(|err| exit_with_error(&err)),
+        Command::Run(args) if args.samples => check_samples(&args),
+        Command::Run(args) => run(&args),
+        Command::Bench { args, runs } => {
+            println!("Benchmark of {} runs", runs);
+            println!();
+            bench(&args, runs)
+        }
+        Command::Serve { port, limits } => server::serve(port, limits)
+            .map(|()| true)
+            .unwrap_or_else(|err| exit_with_error(&err)),
+        Command::Repl { input_dir } => repl::run(&input_dir),
+        Command::Frames { args, output } => export_frames(&args, &output),
+        Command::Report { args, output, html } => write_report(&args, &output, html),
+        Command::Matrix(args) => matrix::run(&args).unwrap_or_else(|err| exit_with_error(&err)),
+        Command::New { root, year, day } => new_day(&root, year, day),
+        Command::Help => unreachable!(),
+    };
+    if !success {
+        std::process::exit(1);
+    }
 }
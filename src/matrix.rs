@@ -0,0 +1,294 @@
+//! Runs the selected days on the inputs of several users, found as `DIR/USER/dayN.txt`.
+
+use crate::cli::{Args, Parts};
+use crate::input::{self, Source};
+use crate::runner::{self, catch_panic, format_duration};
+use aoc_2020::params::Params;
+use aoc_2020::registry;
+use aoc_2020::solution::{Answer, DynSolution};
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, PartialEq, Debug)]
+enum Outcome {
+    /// The user has no input for the day.
+    Missing,
+    Solved {
+        answer: Answer,
+        elapsed: Duration,
+        sentinel: bool,
+    },
+    /// The input cannot be read or parsed.
+    Failed(String),
+    Panicked(String),
+}
+
+impl Outcome {
+    fn cell(&self) -> String {
+        match self {
+            Outcome::Missing => "-".to_string(),
+            Outcome::Solved {
+                answer,
+                elapsed,
+                sentinel,
+            } => {
+                let flag = if *sentinel { " [sentinel]" } else { "" };
+                format!("{} ({}){}", answer, format_duration(*elapsed), flag)
+            }
+            Outcome::Failed(_) => "[error]".to_string(),
+            Outcome::Panicked(_) => "[panic]".to_string(),
+        }
+    }
+
+    /// Explanation of a flagged outcome.
+    fn flag(&self) -> Option<String> {
+        match self {
+            Outcome::Missing => None,
+            Outcome::Solved {
+                sentinel: false, ..
+            } => None,
+            Outcome::Solved { answer, .. } => {
+                Some(format!("gave up with {}, found no solution", answer))
+            }
+            Outcome::Failed(err) => Some(err.clone()),
+            Outcome::Panicked(msg) => Some(format!("panicked: {}", msg)),
+        }
+    }
+}
+
+struct Row {
+    label: String,
+    outcomes: Vec<Outcome>,
+}
+
+fn selected_parts(solution: &dyn DynSolution, parts: Parts) -> Vec<u8> {
+    (1..=solution.parts())
+        .filter(|&p| parts.contains(p))
+        .collect()
+}
+
+/// Solves the selected parts on the input, a panic or a failure of parsing applies to all of them.
+fn solve(solution: &dyn DynSolution, input: &str, parts: Parts, params: &Params) -> Vec<Outcome> {
+    let selected = selected_parts(solution, parts);
+    let parsed = match catch_panic(|| solution.parse(input)) {
+        Ok(Ok(parsed)) => parsed,
+        Ok(Err(err)) => return failed(&selected, Outcome::Failed(err.to_string())),
+        Err(msg) => return failed(&selected, Outcome::Panicked(msg)),
+    };
+    selected
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            match catch_panic(|| solution.solve_with(parsed.as_ref(), part, params)) {
+                Ok(answer) => Outcome::Solved {
+                    elapsed: start.elapsed(),
+                    sentinel: solution.sentinel(part).as_ref() == Some(&answer),
+                    answer,
+                },
+                Err(msg) => Outcome::Panicked(msg),
+            }
+        })
+        .collect()
+}
+
+/// The same outcome for all of the parts.
+fn failed(parts: &[u8], outcome: Outcome) -> Vec<Outcome> {
+    vec![outcome; parts.len()]
+}
+
+/// Names of the subdirectories of the `dir`, in alphabetical order.
+fn users(dir: &Path) -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("cannot read input directory '{}': {}", dir.display(), e))?;
+    let mut users: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    if users.is_empty() {
+        return Err(format!("no user directories in '{}'", dir.display()));
+    }
+    users.sort();
+    Ok(users)
+}
+
+fn format_table(users: &[String], rows: &[Row]) -> String {
+    let mut lines: Vec<Vec<String>> = vec![std::iter::once(String::new())
+        .chain(users.iter().cloned())
+        .collect()];
+    for row in rows {
+        let cells = row.outcomes.iter().map(Outcome::cell);
+        lines.push(std::iter::once(row.label.clone()).chain(cells).collect());
+    }
+    let widths: Vec<usize> = (0..=users.len())
+        .map(|i| lines.iter().map(|l| l[i].chars().count()).max().unwrap())
+        .collect();
+    let mut table = String::new();
+    for line in lines {
+        let padded: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:<1$}", cell, width))
+            .collect();
+        table += padded.join("  ").trim_end();
+        table.push('\n');
+    }
+    table
+}
+
+/// Prints the matrix of the answers, returns whether none of the outcomes is flagged.
+pub fn run(args: &Args) -> Result<bool, String> {
+    let users = users(&args.input_dir)?;
+    let jobs: Vec<_> = args
+        .selections
+        .iter()
+        .flat_map(|selection| users.iter().map(move |user| (selection, user)))
+        .collect();
+
+    // panics of the solvers are reported in the matrix instead
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut results = Vec::new();
+    runner::map_in_order(
+        jobs,
+        args.jobs,
+        |(selection, user)| {
+            let solution = registry::find(selection.day).expect("selected day is registered");
+            let path = input::path_in_dir(&args.input_dir.join(user), selection.day);
+            let selected = selected_parts(solution, selection.parts);
+            if !path.is_file() {
+                return failed(&selected, Outcome::Missing);
+            }
+            match input::read(&Source::File(path)) {
+                Ok(input) => solve(
+                    solution,
+                    &input,
+                    selection.parts,
+                    args.params.for_day(selection.day),
+                ),
+                Err(err) => failed(&selected, Outcome::Failed(err)),
+            }
+        },
+        |outcomes| results.push(outcomes),
+    );
+    panic::set_hook(hook);
+
+    let mut rows = Vec::new();
+    for (selection, outcomes) in args.selections.iter().zip(results.chunks(users.len())) {
+        let solution = registry::find(selection.day).expect("selected day is registered");
+        for (i, part) in selected_parts(solution, selection.parts)
+            .into_iter()
+            .enumerate()
+        {
+            rows.push(Row {
+                label: runner::label(selection.day, part, solution.parts() == 1),
+                outcomes: outcomes.iter().map(|o| o[i].clone()).collect(),
+            });
+        }
+    }
+
+    print!("{}", format_table(&users, &rows));
+    let mut flagged = Vec::new();
+    for row in &rows {
+        for (user, outcome) in users.iter().zip(&row.outcomes) {
+            if let Some(flag) = outcome.flag() {
+                flagged.push(format!("{}, {}: {}", user, row.label, flag));
+            }
+        }
+    }
+    if !flagged.is_empty() {
+        println!();
+        println!("Flagged:");
+        for line in &flagged {
+            println!("    {}", line);
+        }
+    }
+    Ok(flagged.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved(answer: Answer, sentinel: bool) -> Outcome {
+        Outcome::Solved {
+            answer,
+            elapsed: Duration::from_micros(5),
+            sentinel,
+        }
+    }
+
+    #[test]
+    fn test_matrix_solve_flags_sentinels() {
+        let day8 = registry::find(8).unwrap();
+        // neither of the jumps can be fixed to terminate the program
+        let outcomes = solve(day8, "jmp +0\njmp +0\n", Parts::Both, &Params::default());
+        assert!(matches!(
+            &outcomes[..],
+            [
+                Outcome::Solved {
+                    sentinel: false,
+                    ..
+                },
+                Outcome::Solved {
+                    sentinel: true,
+                    answer: Answer::Int(-1),
+                    ..
+                },
+            ]
+        ));
+        let outcomes = solve(day8, "nop +0\nacc +1\n", Parts::Only(2), &Params::default());
+        assert!(matches!(
+            &outcomes[..],
+            [Outcome::Solved {
+                sentinel: false,
+                answer: Answer::Int(1),
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_matrix_solve_failures() {
+        let day8 = registry::find(8).unwrap();
+        let outcomes = solve(day8, "add +1\n", Parts::Both, &Params::default());
+        assert_eq!(2, outcomes.len());
+        assert_eq!(
+            Outcome::Failed("line 1, column 1: unknown operation in 'add'".to_string()),
+            outcomes[1]
+        );
+        assert_eq!(
+            Some("panicked: x".to_string()),
+            Outcome::Panicked("x".to_string()).flag()
+        );
+    }
+
+    #[test]
+    fn test_matrix_format_table() {
+        let users = vec!["alice".to_string(), "bob".to_string()];
+        let rows = vec![
+            Row {
+                label: "Day 8/2".to_string(),
+                outcomes: vec![
+                    solved(Answer::Int(1234), false),
+                    solved(Answer::Int(-1), true),
+                ],
+            },
+            Row {
+                label: "Day 25".to_string(),
+                outcomes: vec![Outcome::Panicked("x".to_string()), Outcome::Missing],
+            },
+        ];
+        // the header has no label
+        let expected = "         alice          bob
+Day 8/2  1234 (5.00µs)  -1 (5.00µs) [sentinel]
+Day 25   [panic]        -
+";
+        assert_eq!(expected, format_table(&users, &rows));
+        assert_eq!(None, rows[0].outcomes[0].flag());
+        assert_eq!(None, rows[1].outcomes[1].flag());
+        assert!(rows[0].outcomes[1].flag().is_some());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::answers::Verdict;
+use aoc_2020::explain::Fact;
+use aoc_2020::Answer;
+use std::time::Duration;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_arg(arg: &str) -> Result<Format, String> {
+        match arg {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "unknown format '{}' (expected text, json or csv)",
+                arg
+            )),
+        }
+    }
+}
+
+/// Result of a single solved part in the machine readable output.
+pub struct Record<'a> {
+    pub day: u8,
+    pub part: u8,
+    pub answer: &'a Answer,
+    pub elapsed: Duration,
+    pub verdict: Option<&'a Verdict>,
+    /// Facts of the solver when the answer is explained, only in JSON.
+    pub facts: Option<&'a [Fact]>,
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+impl Record<'_> {
+    // answers are kept as strings, 64-bit numbers do not survive JSON readers using doubles
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"type\": \"{}\", \"elapsed_ns\": {}",
+            self.day,
+            self.part,
+            json_string(&self.answer.to_string()),
+            self.answer.type_name(),
+            self.elapsed.as_nanos()
+        );
+        if let Some(verdict) = self.verdict {
+            json += &format!(", \"status\": \"{}\"", verdict.status());
+        }
+        if let Some(facts) = self.facts {
+            let facts: Vec<String> = facts
+                .iter()
+                .map(|fact| {
+                    format!(
+                        "{{\"name\": {}, \"value\": {}}}",
+                        json_string(fact.name),
+                        json_string(&fact.value)
+                    )
+                })
+                .collect();
+            json += &format!(", \"facts\": [{}]", facts.join(", "));
+        }
+        json.push('}');
+        json
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!(
+            "{},{},{},{},{}",
+            self.day,
+            self.part,
+            csv_field(&self.answer.to_string()),
+            self.answer.type_name(),
+            self.elapsed.as_nanos()
+        );
+        if let Some(verdict) = self.verdict {
+            csv += &format!(",{}", verdict.status());
+        }
+        csv
+    }
+}
+
+/// Prints records in the JSON or CSV format as they come.
+pub struct Writer {
+    format: Format,
+    verify: bool,
+    count: usize,
+}
+
+impl Writer {
+    pub fn new(format: Format, verify: bool) -> Writer {
+        assert!(format != Format::Text, "text output is printed directly");
+        Writer {
+            format,
+            verify,
+            count: 0,
+        }
+    }
+
+    pub fn begin(&self) {
+        match self.format {
+            Format::Json => println!("["),
+            Format::Csv if self.verify => println!("day,part,answer,type,elapsed_ns,status"),
+            Format::Csv => println!("day,part,answer,type,elapsed_ns"),
+            Format::Text => unreachable!(),
+        }
+    }
+
+    pub fn record(&mut self, record: &Record) {
+        match self.format {
+            Format::Json if self.count > 0 => print!(",\n  {}", record.to_json()),
+            Format::Json => print!("  {}", record.to_json()),
+            Format::Csv => println!("{}", record.to_csv()),
+            Format::Text => unreachable!(),
+        }
+        self.count += 1;
+    }
+
+    pub fn finish(&self) {
+        if self.format == Format::Json {
+            if self.count > 0 {
+                println!();
+            }
+            println!("]");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format_from_arg() {
+        assert_eq!(Ok(Format::Json), Format::from_arg("json"));
+        assert_eq!(Ok(Format::Csv), Format::from_arg("csv"));
+        assert!(Format::from_arg("xml").is_err());
+    }
+
+    #[test]
+    fn test_output_json_string() {
+        assert_eq!(r#""a,b""#, json_string("a,b"));
+        assert_eq!(r#""q\"\\\n\u0001""#, json_string("q\"\\\n\u{1}"));
+    }
+
+    #[test]
+    fn test_output_csv_field() {
+        assert_eq!("123", csv_field("123"));
+        assert_eq!(r#""a,b""#, csv_field("a,b"));
+        assert_eq!(r#""say ""hi""""#, csv_field(r#"say "hi""#));
+    }
+
+    #[test]
+    fn test_output_record() {
+        let answer = Answer::Text("a,b".to_string());
+        let mut record = Record {
+            day: 21,
+            part: 2,
+            answer: &answer,
+            elapsed: Duration::from_micros(15),
+            verdict: None,
+            facts: None,
+        };
+        assert_eq!(
+            r#"{"day": 21, "part": 2, "answer": "a,b", "type": "text", "elapsed_ns": 15000}"#,
+            record.to_json()
+        );
+        assert_eq!(r#"21,2,"a,b",text,15000"#, record.to_csv());
+
+        let verdict = Verdict::Correct;
+        record.verdict = Some(&verdict);
+        assert!(record
+            .to_json()
+            .ends_with(r#""elapsed_ns": 15000, "status": "ok"}"#));
+        assert!(record.to_csv().ends_with(",15000,ok"));
+
+        let facts = [Fact {
+            name: "allergen",
+            value: "mxmxvkd contains \"dairy\"".to_string(),
+        }];
+        record.facts = Some(&facts);
+        assert!(record.to_json().ends_with(
+            r#""status": "ok", "facts": [{"name": "allergen", "value": "mxmxvkd contains \"dairy\""}]}"#
+        ));
+    }
+}
@@ -0,0 +1,239 @@
+//! Named parameters of the puzzles, like the number of turns of day 15.
+//!
+//! Every solver declares the values of its puzzle which can be changed in
+//! [`Solution::PARAMS`](crate::solution::Solution::PARAMS). [`Params`] hold the values set for
+//! one day and [`Settings`] those of all days, given as `dayN.NAME=VALUE` assignments. Unset
+//! parameters keep the values of the puzzle.
+
+use crate::registry;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// Values a parameter accepts.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Kind {
+    Number { min: u64, max: u64 },
+    Text,
+}
+
+/// Parameter of a puzzle.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Param {
+    pub name: &'static str,
+    pub kind: Kind,
+    /// What the parameter changes, with the value of the puzzle.
+    pub about: &'static str,
+}
+
+impl Param {
+    pub const fn number(name: &'static str, min: u64, max: u64, about: &'static str) -> Self {
+        Param {
+            name,
+            kind: Kind::Number { min, max },
+            about,
+        }
+    }
+
+    pub const fn text(name: &'static str, about: &'static str) -> Self {
+        Param {
+            name,
+            kind: Kind::Text,
+            about,
+        }
+    }
+
+    fn check(&self, value: &str) -> Result<(), String> {
+        match self.kind {
+            Kind::Number { min, max } => match value.parse::<u64>() {
+                Ok(n) if (min..=max).contains(&n) => Ok(()),
+                _ => Err(format!(
+                    "invalid value '{}' of '{}', expected a number from {} to {}",
+                    value, self.name, min, max
+                )),
+            },
+            Kind::Text if value.is_empty() => Err(format!("empty value of '{}'", self.name)),
+            Kind::Text => Ok(()),
+        }
+    }
+}
+
+/// Values set for the parameters of a day.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+static NO_PARAMS: Params = Params {
+    values: BTreeMap::new(),
+};
+
+impl Params {
+    /// Sets the parameter `name`, one of the `declared` ones, to the `value`.
+    pub fn set(&mut self, declared: &[Param], name: &str, value: &str) -> Result<(), String> {
+        let param = declared.iter().find(|p| p.name == name).ok_or_else(|| {
+            if declared.is_empty() {
+                format!("unknown parameter '{}', the day has none", name)
+            } else {
+                let names: Vec<&str> = declared.iter().map(|p| p.name).collect();
+                format!(
+                    "unknown parameter '{}', expected {}",
+                    name,
+                    names.join(", ")
+                )
+            }
+        })?;
+        param.check(value)?;
+        self.values.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The numeric parameter `name`, or the `default` when it is not set.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the value does not fit in `T`, [`Param::number`] should not allow such.
+    pub fn number<T: TryFrom<u64>>(&self, name: &str, default: T) -> T {
+        match self.values.get(name) {
+            Some(value) => value
+                .parse::<u64>()
+                .ok()
+                .and_then(|n| T::try_from(n).ok())
+                .unwrap_or_else(|| panic!("value '{}' of '{}' out of range", value, name)),
+            None => default,
+        }
+    }
+
+    /// The text parameter `name`, or the `default` when it is not set.
+    pub fn text<'a>(&'a self, name: &str, default: &'a str) -> &'a str {
+        self.values.get(name).map_or(default, String::as_str)
+    }
+}
+
+/// Values set for the parameters of all days.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Settings {
+    days: BTreeMap<u8, Params>,
+}
+
+impl Settings {
+    /// Sets the parameter of the `key`, like `day15.turns`, to the `value`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let (day, name) = key
+            .strip_prefix("day")
+            .and_then(|key| key.split_once('.'))
+            .ok_or_else(|| format!("invalid parameter '{}', expected dayN.NAME", key))?;
+        let solution = day
+            .parse()
+            .ok()
+            .and_then(registry::find)
+            .ok_or_else(|| format!("invalid parameter '{}', there is no day {}", key, day))?;
+        self.days
+            .entry(solution.day())
+            .or_default()
+            .set(solution.params(), name, value)
+            .map_err(|err| format!("day {}: {}", day, err))
+    }
+
+    /// Applies the `KEY=VALUE` assignment.
+    pub fn assign(&mut self, assignment: &str) -> Result<(), String> {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| format!("invalid assignment '{}', expected KEY=VALUE", assignment))?;
+        self.set(key.trim(), value.trim())
+    }
+
+    /// Parses the `KEY=VALUE` lines of a config file, blank lines and lines starting with `#`
+    /// are skipped.
+    pub fn parse(text: &str) -> Result<Settings, String> {
+        let mut settings = Settings::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            settings
+                .assign(line)
+                .map_err(|err| format!("line {}: {}", i + 1, err))?;
+        }
+        Ok(settings)
+    }
+
+    /// Overrides the values with those set in `other`.
+    pub fn merge(&mut self, other: &Settings) {
+        for (day, params) in &other.days {
+            let values = &mut self.days.entry(*day).or_default().values;
+            values.extend(params.values.clone());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days.values().all(Params::is_empty)
+    }
+
+    pub fn for_day(&self, day: u8) -> &Params {
+        self.days.get(&day).unwrap_or(&NO_PARAMS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_set() {
+        let declared = [
+            Param::number("turns", 1, 100, "number of turns"),
+            Param::text("bag", "the bag"),
+        ];
+        let mut params = Params::default();
+        assert_eq!(2020, params.number("turns", 2020usize));
+        params.set(&declared, "turns", "10").unwrap();
+        params.set(&declared, "bag", "dark red").unwrap();
+        assert_eq!(10, params.number("turns", 2020usize));
+        assert_eq!("dark red", params.text("bag", "shiny gold"));
+
+        let err = params.set(&declared, "turn", "10").unwrap_err();
+        assert_eq!("unknown parameter 'turn', expected turns, bag", err);
+        let err = params.set(&declared, "turns", "0").unwrap_err();
+        assert_eq!(
+            "invalid value '0' of 'turns', expected a number from 1 to 100",
+            err
+        );
+        assert!(params.set(&declared, "turns", "ten").is_err());
+        assert!(params.set(&declared, "bag", "").is_err());
+        assert!(params.set(&[], "turns", "1").is_err());
+    }
+
+    #[test]
+    fn test_params_settings() {
+        let config = "\
+# variants of the games
+day15.turns = 10
+
+day23.moves=10
+";
+        let mut settings = Settings::parse(config).unwrap();
+        let mut overrides = Settings::default();
+        overrides.assign("day15.turns=20").unwrap();
+        settings.merge(&overrides);
+        assert_eq!(20, settings.for_day(15).number("turns", 0usize));
+        assert_eq!(10, settings.for_day(23).number("moves", 0usize));
+        assert!(settings.for_day(1).is_empty());
+        assert!(!settings.is_empty());
+
+        let err = Settings::parse("day15.turns=10\nday15.turns\n").unwrap_err();
+        assert_eq!(
+            "line 2: invalid assignment 'day15.turns', expected KEY=VALUE",
+            err
+        );
+        let err = overrides.assign("day15.rounds=1").unwrap_err();
+        assert_eq!("day 15: unknown parameter 'rounds', expected turns", err);
+        assert!(overrides.assign("day26.turns=1").is_err());
+        assert!(overrides.assign("turns=1").is_err());
+        assert!(overrides.assign("day2.x=1").is_err());
+    }
+}
@@ -0,0 +1,303 @@
+//! Small toolkit for parsing the puzzle inputs.
+//!
+//! A [`Cursor`] consumes a fragment of the input from the front. All of its errors point into
+//! the whole input, so cursors over lines or blocks still report the line and column within
+//! the puzzle input.
+
+use crate::{try_parse, ParseError};
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a cursor over the whole `input`.
+    pub fn new(input: &'a str) -> Cursor<'a> {
+        Cursor::over(input, input)
+    }
+
+    /// Creates a cursor over the `fragment`, which has to be a slice of the `input`.
+    pub fn over(input: &'a str, fragment: &'a str) -> Cursor<'a> {
+        Cursor {
+            input,
+            rest: fragment,
+        }
+    }
+
+    /// The not yet consumed text.
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Cursors over the remaining lines.
+    pub fn lines(self) -> impl Iterator<Item = Cursor<'a>> {
+        let input = self.input;
+        self.rest.lines().map(move |line| Cursor::over(input, line))
+    }
+
+    /// Parses every remaining line with `f`, which has to consume the whole line.
+    pub fn map_lines<T>(
+        self,
+        mut f: impl FnMut(&mut Cursor<'a>) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.lines()
+            .map(|mut line| {
+                let item = f(&mut line)?;
+                line.expect_end()?;
+                Ok(item)
+            })
+            .collect()
+    }
+
+    /// Cursors over the remaining words separated by whitespace.
+    pub fn words(self) -> impl Iterator<Item = Cursor<'a>> {
+        let input = self.input;
+        self.rest
+            .split_whitespace()
+            .map(move |word| Cursor::over(input, word))
+    }
+
+    /// Cursors over the remaining blocks separated by empty lines.
+    pub fn split_blocks(self) -> impl Iterator<Item = Cursor<'a>> {
+        let input = self.input;
+        self.rest
+            .trim_end_matches('\n')
+            .split("\n\n")
+            .map(move |block| Cursor::over(input, block))
+    }
+
+    /// Error pointing at the `fragment` of the input.
+    pub fn error_at(&self, fragment: &str, message: impl Into<String>) -> ParseError {
+        ParseError::at(self.input, fragment, message)
+    }
+
+    /// Error pointing at the rest of the current line.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        let end = self.rest.find('\n').unwrap_or(self.rest.len());
+        self.error_at(&self.rest[..end], message)
+    }
+
+    /// Error pointing at up to `chars` following characters.
+    pub fn error_ahead(&self, chars: usize, message: impl Into<String>) -> ParseError {
+        let end = self
+            .rest
+            .char_indices()
+            .nth(chars)
+            .map_or(self.rest.len(), |(i, _)| i);
+        self.error_at(&self.rest[..end], message)
+    }
+
+    /// The text consumed since the `earlier` copy of this cursor.
+    pub fn consumed_since(&self, earlier: &Cursor<'a>) -> &'a str {
+        &earlier.rest[..earlier.rest.len() - self.rest.len()]
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    pub fn starts_with(&self, literal: &str) -> bool {
+        self.rest.starts_with(literal)
+    }
+
+    /// Consumes the `literal` if the rest starts with it.
+    pub fn eat(&mut self, literal: &str) -> bool {
+        match self.rest.strip_prefix(literal) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn expect_literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        if self.eat(literal) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", literal.escape_default())))
+        }
+    }
+
+    /// Consumes the text up to the end of the current line, and the line break.
+    pub fn expect_line(&mut self, message: &str) -> Result<Cursor<'a>, ParseError> {
+        if self.is_empty() {
+            return Err(self.error(message));
+        }
+        let end = self.rest.find('\n').unwrap_or(self.rest.len());
+        let line = self.take(end);
+        self.eat("\n");
+        Ok(Cursor::over(self.input, line))
+    }
+
+    /// Consumes a number, an optional sign followed by the alphanumeric characters.
+    pub fn take_number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let sign = self.rest.starts_with(['+', '-']) as usize;
+        let len = self.rest[sign..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(self.rest.len() - sign);
+        if len == 0 {
+            return Err(self.error_ahead(0, "expected a number"));
+        }
+        let number = try_parse(self.input, &self.rest[..sign + len])?;
+        self.take(sign + len);
+        Ok(number)
+    }
+
+    pub fn take_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.take(c.len_utf8());
+        Some(c)
+    }
+
+    /// Consumes the text before the `delimiter` and the delimiter.
+    pub fn take_until(&mut self, delimiter: &str) -> Result<&'a str, ParseError> {
+        match self.rest.find(delimiter) {
+            Some(i) => {
+                let taken = self.take(i);
+                self.take(delimiter.len());
+                Ok(taken)
+            }
+            None => Err(self.error(format!("expected '{}'", delimiter.escape_default()))),
+        }
+    }
+
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let end = self.rest.find(|c| !predicate(c)).unwrap_or(self.rest.len());
+        self.take(end)
+    }
+
+    pub fn take_rest(&mut self) -> &'a str {
+        self.take(self.rest.len())
+    }
+
+    /// Checks that everything has been consumed.
+    pub fn expect_end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected text"))
+        }
+    }
+
+    /// Consumes items with `f` as long as they are separated by the `delimiter`.
+    pub fn separated<T>(
+        &mut self,
+        delimiter: &str,
+        mut f: impl FnMut(&mut Cursor<'a>) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![f(self)?];
+        while self.eat(delimiter) {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+
+    fn take(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "abc: 12, -3\nde fgh\n\nźx +12";
+
+    fn location(err: &ParseError) -> (usize, usize, &str) {
+        (err.line, err.column, err.text.as_str())
+    }
+
+    #[test]
+    fn test_parser_literals() {
+        let mut cursor = Cursor::new(INPUT);
+        assert!(cursor.starts_with("ab") && !cursor.eat("bc"));
+        assert_eq!(Ok("abc"), cursor.take_until(": "));
+        let err = cursor.expect_literal("x").unwrap_err();
+        assert_eq!((1, 6, "12, -3"), location(&err));
+        assert_eq!(
+            "line 1, column 6: expected 'x' in '12, -3'",
+            err.to_string()
+        );
+        let err = cursor.take_until("\t").unwrap_err();
+        assert_eq!("expected '\\t'", err.message);
+    }
+
+    #[test]
+    fn test_parser_numbers() {
+        let mut cursor = Cursor::new(INPUT).lines().next().unwrap();
+        let err = cursor.take_number::<u8>().unwrap_err();
+        assert_eq!((1, 1, "abc"), location(&err));
+        cursor.take_until(": ").unwrap();
+        let start = cursor;
+        assert_eq!(
+            Ok(vec![12, -3]),
+            cursor.separated(", ", |c| c.take_number::<i8>())
+        );
+        assert_eq!("12, -3", cursor.consumed_since(&start));
+        let err = Cursor::new(",1").take_number::<u8>().unwrap_err();
+        assert_eq!((1, 1, ""), location(&err));
+
+        let mut last = Cursor::new(INPUT).split_blocks().nth(1).unwrap();
+        assert_eq!(Some('ź'), last.take_char());
+        let err = last.take_number::<u8>().unwrap_err();
+        assert_eq!((4, 2, "x"), location(&err));
+        last.take_while(|c| c != '+');
+        assert_eq!(Ok(12), last.take_number::<u8>());
+        assert!(last.is_empty());
+    }
+
+    #[test]
+    fn test_parser_map_lines() {
+        let input = "1 2\n3\n";
+        let cursor = Cursor::new(input);
+        let words = cursor.words().map(|w| w.rest()).collect::<Vec<_>>();
+        assert_eq!(vec!["1", "2", "3"], words);
+        let err = cursor.map_lines(Cursor::take_number::<u8>).unwrap_err();
+        assert_eq!((1, 2, " 2"), location(&err));
+        let err = Cursor::new("ab\ncd")
+            .lines()
+            .nth(1)
+            .unwrap()
+            .error_ahead(1, "bad");
+        assert_eq!((2, 1, "c"), location(&err));
+        let numbers = Cursor::new("1\n3").map_lines(Cursor::take_number::<u8>);
+        assert_eq!(Ok(vec![1, 3]), numbers);
+    }
+
+    #[test]
+    fn test_parser_lines_and_blocks() {
+        let lines = Cursor::new(INPUT)
+            .lines()
+            .map(|c| c.rest())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["abc: 12, -3", "de fgh", "", "źx +12"], lines);
+        let blocks = Cursor::new("a\nb\n\nc\n").split_blocks();
+        assert_eq!(
+            vec!["a\nb", "c"],
+            blocks.map(|c| c.rest()).collect::<Vec<_>>()
+        );
+
+        let mut cursor = Cursor::new(INPUT);
+        assert_eq!("abc: 12, -3", cursor.expect_line("missing").unwrap().rest());
+        let mut line = cursor.expect_line("missing").unwrap();
+        assert_eq!("de", line.take_while(|c| c != ' '));
+        let err = line.expect_end().unwrap_err();
+        assert_eq!((2, 3, " fgh"), location(&err));
+        cursor.take_rest();
+        let err = cursor.expect_line("missing").unwrap_err();
+        assert_eq!(
+            (4, 7, "missing"),
+            (err.line, err.column, err.message.as_str())
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Points in `D` dimensional space with the common vector arithmetic.
+//!
+//! Rotations of 2D points assume `x` growing to the right (east) and `y` growing up (north).
+
+use std::ops::*;
+
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct PointN<T, const D: usize>(pub [T; D]);
+
+pub type Point<T> = PointN<T, 2>;
+pub type Point3<T> = PointN<T, 3>;
+pub type Point4<T> = PointN<T, 4>;
+
+impl<T: Copy, const D: usize> PointN<T, D> {
+    pub fn coords(&self) -> [T; D] {
+        self.0
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(T, T) -> T) -> Self {
+        PointN(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+
+    fn map(self, f: impl Fn(T) -> T) -> Self {
+        PointN(self.0.map(f))
+    }
+}
+
+impl<T: Copy + Default, const D: usize> PointN<T, D> {
+    pub fn zero() -> Self {
+        PointN([T::default(); D])
+    }
+}
+
+impl<T, const D: usize> PointN<T, D>
+where
+    T: Copy + Default + PartialOrd + Add<Output = T> + Sub<Output = T>,
+{
+    /// Sum of the distances along all axes.
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        self.axis_distances(other)
+            .fold(T::default(), |acc, distance| acc + distance)
+    }
+
+    /// The largest of the distances along the axes.
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        self.axis_distances(other).fold(
+            T::default(),
+            |acc, distance| if distance > acc { distance } else { acc },
+        )
+    }
+
+    fn axis_distances<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = T> + 'a {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(&a, &b)| if a > b { a - b } else { b - a })
+    }
+}
+
+impl<T, const D: usize> PointN<T, D>
+where
+    T: Copy + Add<Output = T> + From<i8>,
+{
+    /// All `3^D - 1` points differing by at most one in each coordinate.
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        let count = 3usize.pow(D as u32);
+        // the middle index has all offsets zero, that's the point itself
+        (0..count)
+            .filter(move |&k| k != count / 2)
+            .map(move |mut k| {
+                let mut coords = self.0;
+                for c in coords.iter_mut() {
+                    *c = *c + T::from((k % 3) as i8 - 1);
+                    k /= 3;
+                }
+                PointN(coords)
+            })
+    }
+
+    /// The `2 * D` points differing by one in a single coordinate.
+    pub fn orthogonal_neighbours(self) -> impl Iterator<Item = Self> {
+        (0..D).flat_map(move |axis| {
+            [-1, 1].iter().map(move |&offset| {
+                let mut coords = self.0;
+                coords[axis] = coords[axis] + T::from(offset);
+                PointN(coords)
+            })
+        })
+    }
+}
+
+impl<T: Copy> PointN<T, 2> {
+    pub fn new(x: T, y: T) -> Self {
+        PointN([x, y])
+    }
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+}
+
+impl<T: Copy + Neg<Output = T>> PointN<T, 2> {
+    /// Rotates by 90 degrees clockwise around the origin.
+    pub fn rotate_right(self) -> Self {
+        Self::new(self.y(), -self.x())
+    }
+    /// Rotates by 90 degrees counterclockwise around the origin.
+    pub fn rotate_left(self) -> Self {
+        Self::new(-self.y(), self.x())
+    }
+}
+
+impl<T: Copy> PointN<T, 3> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        PointN([x, y, z])
+    }
+}
+
+impl<T, const D: usize> From<[T; D]> for PointN<T, D> {
+    fn from(coords: [T; D]) -> Self {
+        PointN(coords)
+    }
+}
+
+impl<T> From<(T, T)> for PointN<T, 2> {
+    fn from((x, y): (T, T)) -> Self {
+        PointN([x, y])
+    }
+}
+
+impl<T, const D: usize> Index<usize> for PointN<T, D> {
+    type Output = T;
+    fn index(&self, axis: usize) -> &T {
+        &self.0[axis]
+    }
+}
+
+impl<T: Copy + Add<Output = T>, const D: usize> Add for PointN<T, D> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<T: Copy + Add<Output = T>, const D: usize> AddAssign for PointN<T, D> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other
+    }
+}
+
+impl<T: Copy + Sub<Output = T>, const D: usize> Sub for PointN<T, D> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl<T: Copy + Sub<Output = T>, const D: usize> SubAssign for PointN<T, D> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other
+    }
+}
+
+impl<T: Copy + Neg<Output = T>, const D: usize> Neg for PointN<T, D> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.map(|a| -a)
+    }
+}
+
+/// Multiplication by a scalar.
+impl<T: Copy + Mul<Output = T>, const D: usize> Mul<T> for PointN<T, D> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        self.map(|a| a * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let mut p = Point::new(1, -2);
+        p += Point::new(3, 4);
+        assert_eq!(Point::new(4, 2), p);
+        assert_eq!(Point::new(3, 5), p - Point::new(1, -3));
+        assert_eq!(Point::new(-8, -4), -p * 2);
+        assert_eq!(Point::new(5, 7), (5, 7).into());
+        let q = PointN([1, 2, 3, 4]) + PointN([1, 1, 1, 1]);
+        assert_eq!([2, 3, 4, 5], q.coords());
+        assert_eq!(4, q[2]);
+        assert_eq!(Point3::new(0, 0, 0), Point3::zero());
+    }
+
+    #[test]
+    fn test_point_distances() {
+        let origin = Point::zero();
+        assert_eq!(17 + 8, Point::new(-17, -8).manhattan_distance(&origin));
+        assert_eq!(17 + 8, Point::new(17, -8).manhattan_distance(&origin));
+        assert_eq!(17, Point::new(-17, 8).chebyshev_distance(&origin));
+        let (a, b) = (Point3::new(1u8, 5, 2), Point3::new(4u8, 1, 2));
+        assert_eq!(7, a.manhattan_distance(&b));
+        assert_eq!(4, b.chebyshev_distance(&a));
+    }
+
+    #[test]
+    fn test_point_neighbours() {
+        let p = Point::new(0i8, 0);
+        let mut neighbours = p.neighbours().collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(8, neighbours.len());
+        assert!(!neighbours.contains(&p));
+        assert_eq!(Point::new(-1, -1), neighbours[0]);
+        assert!(neighbours.iter().all(|n| n.chebyshev_distance(&p) == 1));
+        assert_eq!(26, Point3::new(1i32, 2, 3).neighbours().count());
+        assert_eq!(80, PointN([0i64; 4]).neighbours().count());
+
+        let mut orthogonal = p.orthogonal_neighbours().collect::<Vec<_>>();
+        orthogonal.sort();
+        let expected: Vec<Point<i8>> =
+            vec![(-1, 0).into(), (0, -1).into(), (0, 1).into(), (1, 0).into()];
+        assert_eq!(expected, orthogonal);
+    }
+
+    #[test]
+    fn test_point_rotate() {
+        let p = Point::new(10, 4);
+        assert_eq!(Point::new(4, -10), p.rotate_right());
+        assert_eq!(Point::new(-10, -4), p.rotate_right().rotate_right());
+        assert_eq!(Point::new(-4, 10), p.rotate_left());
+        assert_eq!(p, p.rotate_left().rotate_right());
+        assert_eq!(p, (0..4).fold(p, |p, _| p.rotate_left()));
+    }
+}
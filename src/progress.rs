@@ -0,0 +1,177 @@
+//! Progress reporting and cancellation of long running solvers.
+//!
+//! The long loops of the solvers call [`report`] every few thousand iterations, or [`check`]
+//! as often as they like. Outside of [`with_context`] they do nothing. Inside, [`report`]
+//! passes the progress to the reporter of the [`Context`], and both of them abandon the work
+//! by unwinding when the context is cancelled or past its deadline, which [`with_context`]
+//! turns into [`Cancelled`].
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Number of calls of [`check`] between two looks at the clock.
+const CALLS_PER_CHECK: u32 = 1024;
+
+/// The work was cancelled before it finished.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Cancelled;
+
+#[derive(Default)]
+pub struct Context {
+    /// The work is cancelled when it runs past the deadline.
+    pub deadline: Option<Instant>,
+    /// The work is cancelled when another thread sets the flag.
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// Receives the done fraction of the work, at most once per `interval`.
+    pub reporter: Option<Box<dyn FnMut(f64)>>,
+    pub interval: Duration,
+}
+
+struct State {
+    context: Context,
+    calls: u32,
+    last_report: Instant,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with the `context` of the current thread.
+///
+/// Returns [`Cancelled`] when `f` is cancelled by the context, other panics are resumed.
+pub fn with_context<T>(context: Context, f: impl FnOnce() -> T) -> Result<T, Cancelled> {
+    let state = State {
+        context,
+        calls: 0,
+        last_report: Instant::now(),
+    };
+    let previous = CURRENT.with(|current| current.replace(Some(state)));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+    match result {
+        Ok(value) => Ok(value),
+        Err(payload) if payload.is::<Cancelled>() => Err(Cancelled),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Reports that `done` of the `total` work is done, abandons the work when it is cancelled.
+pub fn report(done: usize, total: usize) {
+    visit(false, |state| {
+        let reporter = match &mut state.context.reporter {
+            Some(reporter) => reporter,
+            None => return,
+        };
+        if state.last_report.elapsed() >= state.context.interval {
+            state.last_report = Instant::now();
+            reporter(done as f64 / total.max(1) as f64);
+        }
+    });
+}
+
+/// Abandons the work when it is cancelled, cheap enough to be called in every iteration.
+pub fn check() {
+    visit(true, |_| ());
+}
+
+/// Passes the state of the current context to `f` unless the context is cancelled, in which
+/// case it unwinds. A `counted` visit does so only once per [`CALLS_PER_CHECK`] calls.
+fn visit(counted: bool, f: impl FnOnce(&mut State)) {
+    let cancelled = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let state = match current.as_mut() {
+            Some(state) => state,
+            None => return false,
+        };
+        if counted {
+            state.calls += 1;
+            if state.calls < CALLS_PER_CHECK {
+                return false;
+            }
+            state.calls = 0;
+        }
+        let context = &state.context;
+        let past_deadline = context.deadline.is_some_and(|d| Instant::now() >= d);
+        let flagged = context
+            .cancel
+            .as_ref()
+            .is_some_and(|c| c.load(Ordering::Relaxed));
+        if past_deadline || flagged {
+            return true;
+        }
+        f(state);
+        false
+    });
+    if cancelled {
+        // unlike a panic it does not run the panic hook
+        panic::resume_unwind(Box::new(Cancelled));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn count_to(n: usize) -> usize {
+        let mut i = 0;
+        while i < n {
+            report(i, n);
+            i += 1;
+        }
+        i
+    }
+
+    #[test]
+    fn test_progress_without_context() {
+        assert_eq!(10_000, count_to(10_000));
+        assert_eq!(Ok(5), with_context(Context::default(), || count_to(5)));
+    }
+
+    #[test]
+    fn test_progress_cancelled() {
+        let context = Context {
+            deadline: Some(Instant::now()),
+            ..Context::default()
+        };
+        assert_eq!(
+            Err(Cancelled),
+            with_context(context, || count_to(usize::MAX))
+        );
+
+        let cancel = Arc::new(AtomicBool::new(true));
+        let context = Context {
+            cancel: Some(cancel),
+            ..Context::default()
+        };
+        let result = with_context(context, || loop {
+            check();
+        });
+        assert_eq!(Err(Cancelled), result);
+        // the context is gone after the work
+        assert_eq!(10_000, count_to(10_000));
+    }
+
+    #[test]
+    fn test_progress_reports() {
+        let reported = Rc::new(Cell::new(None));
+        let last = reported.clone();
+        let context = Context {
+            reporter: Some(Box::new(move |fraction| last.set(Some(fraction)))),
+            ..Context::default()
+        };
+        assert_eq!(Ok(4), with_context(context, || count_to(4)));
+        assert_eq!(Some(0.75), reported.get());
+    }
+
+    #[test]
+    fn test_progress_resumes_other_panics() {
+        let result = panic::catch_unwind(|| with_context(Context::default(), || panic!("boom")));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::solution::DynSolution;
+use crate::*;
+
+static SOLUTIONS: [&dyn DynSolution; 25] = [
+    day1::SOLUTION,
+    day2::SOLUTION,
+    day3::SOLUTION,
+    day4::SOLUTION,
+    day5::SOLUTION,
+    day6::SOLUTION,
+    day7::SOLUTION,
+    day8::SOLUTION,
+    day9::SOLUTION,
+    day10::SOLUTION,
+    day11::SOLUTION,
+    day12::SOLUTION,
+    day13::SOLUTION,
+    day14::SOLUTION,
+    day15::SOLUTION,
+    day16::SOLUTION,
+    day17::SOLUTION,
+    day18::SOLUTION,
+    day19::SOLUTION,
+    day20::SOLUTION,
+    day21::SOLUTION,
+    day22::SOLUTION,
+    day23::SOLUTION,
+    day24::SOLUTION,
+    day25::SOLUTION,
+];
+
+pub fn all() -> &'static [&'static dyn DynSolution] {
+    &SOLUTIONS
+}
+
+pub fn find(day: u8) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS.iter().find(|s| s.day() == day).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_days_in_order() {
+        let days = all().iter().map(|s| s.day()).collect::<Vec<_>>();
+        assert_eq!((1..=25).collect::<Vec<u8>>(), days);
+    }
+
+    #[test]
+    fn test_registry_find() {
+        assert_eq!(Some(2), find(8).map(|s| s.parts()));
+        assert_eq!(Some(1), find(25).map(|s| s.parts()));
+        assert!(find(26).is_none());
+    }
+}
@@ -0,0 +1,399 @@
+//! Interactive session exploring the inputs with the types of the days.
+//!
+//! `parse N` loads and parses the input of a day, which stays loaded for the commands that
+//! follow: `part` solves it, `match` checks messages against the rules of day 19 and `step`
+//! advances the seats of day 11 by generations.
+
+use crate::input::{self, Source};
+use crate::runner::{self, catch_panic, format_duration};
+use aoc_2020::{day11, day18, day19, registry};
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const HELP: &str = "\
+Commands:
+    parse DAY [PATH]   load and parse the input of the day, from PATH if given
+    part [DAY] PART    solve the part of the loaded day, loading DAY first if
+                       it is another one
+    eval EXPRESSION    evaluate the expression of day 18 with the precedence
+                       of both parts
+    match MESSAGE      check the message against the rules of day 19
+    step [N] [PART]    advance the seats of day 11 by N generations [default: 1]
+                       with the rules of the part [default: 1]
+    show               print the loaded input and the state of the seats
+    help               print this help
+    quit               end the session";
+
+/// Input of a day together with its parsed form.
+struct Loaded {
+    day: u8,
+    input: String,
+    parsed: Box<dyn Any>,
+}
+
+/// Seats of day 11 advanced by `step`.
+struct Seats {
+    layout: day11::Layout,
+    generation: usize,
+}
+
+pub struct Repl {
+    input_dir: PathBuf,
+    loaded: Option<Loaded>,
+    seats: Option<Seats>,
+}
+
+fn parse_number<T: std::str::FromStr>(what: &str, s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("invalid {} '{}'", what, s))
+}
+
+impl Repl {
+    pub fn new(input_dir: &Path) -> Repl {
+        Repl {
+            input_dir: input_dir.to_path_buf(),
+            loaded: None,
+            seats: None,
+        }
+    }
+
+    /// Runs the command on the `line` and returns its output.
+    ///
+    /// Returns `None` when the session ends.
+    pub fn execute(&mut self, line: &str) -> Option<Result<String, String>> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("");
+        let args: Vec<&str> = words.collect();
+        let output = match (command, &args[..]) {
+            ("", []) => Ok(String::new()),
+            ("quit", []) | ("exit", []) => return None,
+            ("help", []) => Ok(HELP.to_string()),
+            ("parse", [day]) => self.parse(day, None),
+            ("parse", [day, path]) => self.parse(day, Some(Path::new(path))),
+            ("part", [part]) => self.part(None, part),
+            ("part", [day, part]) => self.part(Some(day), part),
+            ("eval", [_, ..]) => self.eval(line.trim_start()["eval".len()..].trim()),
+            ("match", [message]) => self.match_message(message),
+            ("step", []) => self.step("1", "1"),
+            ("step", [count]) => self.step(count, "1"),
+            ("step", [count, part]) => self.step(count, part),
+            ("show", []) => self.show(),
+            ("parse", _) | ("part", _) | ("eval", _) | ("match", _) | ("step", _) | ("show", _) => {
+                Err(format!("wrong arguments of '{}', see 'help'", command))
+            }
+            _ => Err(format!("unknown command '{}', see 'help'", command)),
+        };
+        Some(output)
+    }
+
+    fn parse(&mut self, day: &str, path: Option<&Path>) -> Result<String, String> {
+        let day = parse_number("day", day)?;
+        let solution = registry::find(day).ok_or_else(|| format!("no solution for day {}", day))?;
+        let input = match path {
+            Some(path) => input::read(&Source::File(path.to_path_buf()))?,
+            None => input::load(day, &self.input_dir)?,
+        };
+        let start = Instant::now();
+        let parsed = catch_panic(|| solution.parse(&input))
+            .map_err(|msg| format!("the parser panicked: {}", msg))?
+            .map_err(|err| format!("day {}: {}", day, err))?;
+        let elapsed = start.elapsed();
+        self.seats = None;
+        let lines = input.lines().count();
+        self.loaded = Some(Loaded { day, input, parsed });
+        Ok(format!(
+            "Day {}: parsed {} lines ({})",
+            day,
+            lines,
+            format_duration(elapsed)
+        ))
+    }
+
+    fn part(&mut self, day: Option<&str>, part: &str) -> Result<String, String> {
+        let mut output = String::new();
+        if let Some(day) = day {
+            if self.loaded.as_ref().map(|l| l.day.to_string()).as_deref() != Some(day) {
+                output = self.parse(day, None)? + "\n";
+            }
+        }
+        let loaded = self.loaded.as_ref().ok_or("no input loaded, see 'parse'")?;
+        let solution = registry::find(loaded.day).expect("loaded day is registered");
+        let part = parse_number("part", part)?;
+        if !(1..=solution.parts()).contains(&part) {
+            return Err(format!("day {} has no part {}", loaded.day, part));
+        }
+        let start = Instant::now();
+        let answer = catch_panic(|| solution.solve(loaded.parsed.as_ref(), part))
+            .map_err(|msg| format!("the solver panicked: {}", msg))?;
+        output += &format!(
+            "{}: {} ({})",
+            runner::label(loaded.day, part, solution.parts() == 1),
+            answer,
+            format_duration(start.elapsed())
+        );
+        Ok(output)
+    }
+
+    fn eval(&self, expression: &str) -> Result<String, String> {
+        let left_to_right = day18::evaluate(expression, 1).map_err(|e| e.to_string())?;
+        let sum_first = day18::evaluate(expression, 2).map_err(|e| e.to_string())?;
+        Ok(format!("part 1: {}, part 2: {}", left_to_right, sum_first))
+    }
+
+    /// The parsed input of the `day`, if it is the loaded one.
+    fn parsed<T: 'static>(&self, day: u8) -> Result<&T, String> {
+        match &self.loaded {
+            Some(loaded) if loaded.day == day => Ok(loaded
+                .parsed
+                .downcast_ref()
+                .expect("parsed type of the day")),
+            _ => Err(format!(
+                "the input of day {0} is not loaded, see 'parse {0}'",
+                day
+            )),
+        }
+    }
+
+    fn match_message(&self, message: &str) -> Result<String, String> {
+        let (rules, _) = self.parsed::<(HashMap<u8, day19::Rule>, Vec<String>)>(19)?;
+        let verdict = |part| {
+            if day19::matches(rules, message, part) {
+                "yes"
+            } else {
+                "no"
+            }
+        };
+        Ok(format!("part 1: {}, part 2: {}", verdict(1), verdict(2)))
+    }
+
+    fn step(&mut self, count: &str, part: &str) -> Result<String, String> {
+        let count: usize = parse_number("number of generations", count)?;
+        let part = match part {
+            "1" => 1,
+            "2" => 2,
+            _ => return Err(format!("unknown part '{}' (expected 1 or 2)", part)),
+        };
+        if self.seats.is_none() {
+            self.seats = Some(Seats {
+                layout: self.parsed::<day11::Layout>(11)?.clone(),
+                generation: 0,
+            });
+        }
+        let seats = self.seats.as_mut().expect("seats are loaded");
+        let mut settled = false;
+        for _ in 0..count {
+            let next = day11::step(&seats.layout, part);
+            if next == seats.layout {
+                settled = true;
+                break;
+            }
+            seats.layout = next;
+            seats.generation += 1;
+        }
+        Ok(format!(
+            "generation {}: {} occupied{}",
+            seats.generation,
+            day11::count_occupied(&seats.layout),
+            if settled { ", settled" } else { "" }
+        ))
+    }
+
+    fn show(&self) -> Result<String, String> {
+        let loaded = self.loaded.as_ref().ok_or("no input loaded, see 'parse'")?;
+        let mut output = format!(
+            "Day {}: {} lines, {} bytes",
+            loaded.day,
+            loaded.input.lines().count(),
+            loaded.input.len()
+        );
+        if loaded.day == 19 {
+            let (rules, messages) = self.parsed::<(HashMap<u8, day19::Rule>, Vec<String>)>(19)?;
+            output += &format!(", {} rules, {} messages", rules.len(), messages.len());
+        }
+        if loaded.day == 11 {
+            let (layout, generation) = match &self.seats {
+                Some(seats) => (&seats.layout, seats.generation),
+                None => (self.parsed::<day11::Layout>(11)?, 0),
+            };
+            output += &format!(
+                "\ngeneration {}: {} occupied\n{}",
+                generation,
+                day11::count_occupied(layout),
+                layout.to_string().trim_end()
+            );
+        }
+        Ok(output)
+    }
+}
+
+/// Runs the commands read from stdin until its end or `quit`.
+pub fn run(input_dir: &Path) -> bool {
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        println!("Type 'help' for the list of commands.");
+    }
+    let mut repl = Repl::new(input_dir);
+    let mut success = true;
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush().expect("cannot write to stdout");
+        }
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(err)) => {
+                eprintln!("error: cannot read a command: {}", err);
+                return false;
+            }
+            None => return success,
+        };
+        match repl.execute(&line) {
+            None => return success,
+            Some(Ok(output)) if output.is_empty() => (),
+            Some(Ok(output)) => println!("{}", output),
+            Some(Err(err)) => {
+                eprintln!("error: {}", err);
+                // commands piped from a script should not fail silently
+                if !interactive {
+                    success = false;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SEATS: &str = "\
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    static RULES: &str = "\
+0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: \"a\"
+5: \"b\"
+
+ababbb
+abbbab
+";
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("aoc_2020_repl_{}_{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn execute(repl: &mut Repl, line: &str) -> Result<String, String> {
+        repl.execute(line).expect("session continues")
+    }
+
+    #[test]
+    fn test_repl_parse_and_part() {
+        let mut repl = Repl::new(Path::new("no-such-directory"));
+        assert!(execute(&mut repl, "part 1").is_err());
+        let path = temp_file("day1.txt", "1721\n979\n366\n299\n675\n1456\n");
+        let output = execute(&mut repl, &format!("parse 1 {}", path.display()));
+        std::fs::remove_file(&path).unwrap();
+        assert!(output.unwrap().starts_with("Day 1: parsed 6 lines ("));
+        assert!(execute(&mut repl, "part 2")
+            .unwrap()
+            .starts_with("Day 1/2: 241861950 ("));
+        assert!(execute(&mut repl, "part 3").is_err());
+        // another day is loaded from the embedded input
+        let output = execute(&mut repl, "part 25 1").unwrap();
+        assert!(output.starts_with("Day 25: parsed 2 lines ("));
+        assert!(output.contains("\nDay 25: "));
+        assert!(execute(&mut repl, "show")
+            .unwrap()
+            .starts_with("Day 25: 2 lines"));
+        assert!(execute(&mut repl, "parse 26").is_err());
+    }
+
+    #[test]
+    fn test_repl_eval() {
+        let mut repl = Repl::new(Path::new("no-such-directory"));
+        assert_eq!(
+            Ok("part 1: 71, part 2: 231".to_string()),
+            execute(&mut repl, "eval 1 + 2 * 3 + 4 * 5 + 6")
+        );
+        assert_eq!(
+            Err("line 1, column 3: unexpected character in '?'".to_string()),
+            execute(&mut repl, "eval 1 ? 2")
+        );
+    }
+
+    #[test]
+    fn test_repl_match() {
+        let mut repl = Repl::new(Path::new("no-such-directory"));
+        assert_eq!(
+            Err("the input of day 19 is not loaded, see 'parse 19'".to_string()),
+            execute(&mut repl, "match ababbb")
+        );
+        let path = temp_file("day19.txt", RULES);
+        execute(&mut repl, &format!("parse 19 {}", path.display())).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            Ok("part 1: yes, part 2: yes".to_string()),
+            execute(&mut repl, "match ababbb")
+        );
+        assert_eq!(
+            Ok("part 1: no, part 2: no".to_string()),
+            execute(&mut repl, "match aaaabbb")
+        );
+        assert_eq!(
+            Ok("Day 19: 9 lines, 77 bytes, 6 rules, 2 messages".to_string()),
+            execute(&mut repl, "show")
+        );
+    }
+
+    #[test]
+    fn test_repl_step() {
+        let mut repl = Repl::new(Path::new("no-such-directory"));
+        let path = temp_file("day11.txt", SEATS);
+        execute(&mut repl, &format!("parse 11 {}", path.display())).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            Ok("generation 1: 71 occupied".to_string()),
+            execute(&mut repl, "step")
+        );
+        assert_eq!(
+            Ok("generation 5: 37 occupied, settled".to_string()),
+            execute(&mut repl, "step 10")
+        );
+        let shown = execute(&mut repl, "show").unwrap();
+        assert!(shown
+            .starts_with("Day 11: 10 lines, 110 bytes\ngeneration 5: 37 occupied\n#.#L.L#.##\n"));
+        assert!(execute(&mut repl, "step 1 3").is_err());
+        assert!(execute(&mut repl, "step x").is_err());
+    }
+
+    #[test]
+    fn test_repl_commands() {
+        let mut repl = Repl::new(Path::new("no-such-directory"));
+        assert_eq!(Some(Ok(String::new())), repl.execute("  "));
+        assert!(repl.execute("help").unwrap().unwrap().contains("parse DAY"));
+        assert!(repl.execute("show").unwrap().is_err());
+        assert!(repl.execute("jump 3").unwrap().is_err());
+        assert!(repl.execute("match").unwrap().is_err());
+        assert_eq!(None, repl.execute("quit"));
+    }
+}
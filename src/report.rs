@@ -0,0 +1,382 @@
+//! Markdown and HTML reports of the answers with their durations, inputs and relative costs.
+
+use crate::runner::{format_duration, DayResult};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// The journal, its days are linked from the report.
+const NOTES: &str = include_str!("../NOTES.md");
+
+/// Number of characters of the longest cost bar.
+const BAR_WIDTH: usize = 20;
+
+const TITLE: &str = "Advent of Code 2020";
+
+/// Solved day with the size of its input.
+pub struct Entry {
+    pub result: DayResult,
+    pub input_bytes: usize,
+    pub input_lines: usize,
+}
+
+impl Entry {
+    fn elapsed(&self) -> Duration {
+        let parts: Duration = self.result.parts.iter().map(|p| p.elapsed).sum();
+        self.result.parse_elapsed + parts
+    }
+}
+
+/// Anchor of a heading as GitHub renders it: lowercase, without punctuation and with dashes
+/// in place of spaces.
+fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+/// The `path` joined to the current directory, without `.` and `..` components.
+fn absolute(path: &Path) -> PathBuf {
+    let path = env::current_dir().unwrap_or_default().join(path);
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Link to the absolute `target` from the absolute directory `from`.
+fn relative_link(from: &Path, target: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = from.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    let up = from[common..].iter().map(|_| "..".to_string());
+    let down = target[common..]
+        .iter()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned());
+    up.chain(down).collect::<Vec<_>>().join("/")
+}
+
+/// The journal at the root of the repository, wherever the program runs from.
+fn notes_file() -> PathBuf {
+    absolute(&Path::new(env!("CARGO_MANIFEST_DIR")).join("NOTES.md"))
+}
+
+/// Link to the notes from the report written to the `output`.
+pub fn notes_path(output: &Path) -> String {
+    let output = absolute(output);
+    let dir = output.parent().unwrap_or(&output);
+    relative_link(dir, &notes_file())
+}
+
+/// Links to the sections of the days in the notes at the `path`, which have headings like
+/// `## Day 7 - ...`.
+fn notes_links(notes: &str, path: &str) -> BTreeMap<u8, String> {
+    notes
+        .lines()
+        .filter_map(|line| {
+            let heading = line.strip_prefix("## ")?;
+            let rest = heading.strip_prefix("Day ")?;
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let day = rest[..digits].parse().ok()?;
+            Some((day, format!("{}#{}", path, anchor(heading))))
+        })
+        .collect()
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
+
+/// Cells of a row of the report, shared by both formats.
+struct Row {
+    label: String,
+    link: Option<String>,
+    answers: [String; 2],
+    time: String,
+    input: String,
+    /// Fraction of the slowest day, the length of the bar.
+    bar: f64,
+    /// Fraction of all the days.
+    share: f64,
+}
+
+fn rows(entries: &[Entry], links: &BTreeMap<u8, String>) -> Vec<Row> {
+    let total: Duration = entries.iter().map(Entry::elapsed).sum();
+    let slowest = entries.iter().map(Entry::elapsed).max().unwrap_or_default();
+    let fraction = |elapsed: Duration, of: Duration| {
+        if of.is_zero() {
+            0.0
+        } else {
+            elapsed.as_secs_f64() / of.as_secs_f64()
+        }
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            let result = &entry.result;
+            let mut answers = [String::new(), String::new()];
+            for part in &result.parts {
+                answers[part.part as usize - 1] = match &part.answer {
+                    Ok(answer) => answer.to_string(),
+                    Err(_) => "timed out".to_string(),
+                };
+            }
+            let lines = match entry.input_lines {
+                1 => "1 line".to_string(),
+                n => format!("{} lines", n),
+            };
+            Row {
+                label: format!("Day {}", result.day),
+                link: links.get(&result.day).cloned(),
+                answers,
+                time: format_duration(entry.elapsed()),
+                input: format!("{}, {}", format_size(entry.input_bytes), lines),
+                bar: fraction(entry.elapsed(), slowest),
+                share: fraction(entry.elapsed(), total),
+            }
+        })
+        .collect()
+}
+
+fn summary(entries: &[Entry]) -> String {
+    let total: Duration = entries.iter().map(Entry::elapsed).sum();
+    let days = match entries.len() {
+        1 => "1 day".to_string(),
+        n => format!("{} days", n),
+    };
+    format!("Solved {} in {}.", days, format_duration(total))
+}
+
+/// Bar of a cost with the `fraction` of the longest one, at least one block unless it is zero.
+fn bar(fraction: f64) -> String {
+    let blocks = (fraction * BAR_WIDTH as f64).round() as usize;
+    let blocks = if fraction > 0.0 { blocks.max(1) } else { 0 };
+    "█".repeat(blocks)
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Markdown report linking to the notes at the `notes_path`.
+pub fn markdown(entries: &[Entry], notes_path: &str) -> String {
+    markdown_with_links(entries, &notes_links(NOTES, notes_path))
+}
+
+fn markdown_with_links(entries: &[Entry], links: &BTreeMap<u8, String>) -> String {
+    let mut out = String::new();
+    writeln!(out, "# {}", TITLE).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "{}", summary(entries)).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "| Day | Part 1 | Part 2 | Time | Input | Cost |").unwrap();
+    writeln!(out, "|-----|--------|--------|-----:|------:|------|").unwrap();
+    for row in rows(entries, links) {
+        let day = match &row.link {
+            Some(link) => format!("[{}]({})", row.label, link),
+            None => row.label,
+        };
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} {:.1}% |",
+            day,
+            markdown_cell(&row.answers[0]),
+            markdown_cell(&row.answers[1]),
+            row.time,
+            row.input,
+            bar(row.bar),
+            row.share * 100.0
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; text-align: left; }
+td.number { text-align: right; white-space: nowrap; }
+.bar { display: inline-block; height: 0.8em; margin-right: 0.5em; background: #4a7; }
+";
+
+/// Standalone HTML page of the report, linking to the notes at the `notes_path`.
+pub fn html(entries: &[Entry], notes_path: &str) -> String {
+    html_with_links(entries, &notes_links(NOTES, notes_path))
+}
+
+fn html_with_links(entries: &[Entry], links: &BTreeMap<u8, String>) -> String {
+    let mut out = String::new();
+    writeln!(out, "<!DOCTYPE html>").unwrap();
+    writeln!(out, "<html lang=\"en\">").unwrap();
+    writeln!(out, "<head>").unwrap();
+    writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(out, "<title>{}</title>", TITLE).unwrap();
+    write!(out, "<style>\n{}</style>\n", STYLE).unwrap();
+    writeln!(out, "</head>").unwrap();
+    writeln!(out, "<body>").unwrap();
+    writeln!(out, "<h1>{}</h1>", TITLE).unwrap();
+    writeln!(out, "<p>{}</p>", escape_html(&summary(entries))).unwrap();
+    writeln!(out, "<table>").unwrap();
+    writeln!(
+        out,
+        "<tr><th>Day</th><th>Part 1</th><th>Part 2</th><th>Time</th><th>Input</th><th>Cost</th></tr>"
+    )
+    .unwrap();
+    for row in rows(entries, links) {
+        let day = match &row.link {
+            Some(link) => format!("<a href=\"{}\">{}</a>", escape_html(link), row.label),
+            None => row.label,
+        };
+        writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"number\">{}</td>\
+             <td class=\"number\">{}</td><td><span class=\"bar\" style=\"width: {:.1}em\">\
+             </span>{:.1}%</td></tr>",
+            day,
+            escape_html(&row.answers[0]),
+            escape_html(&row.answers[1]),
+            escape_html(&row.time),
+            escape_html(&row.input),
+            row.bar * 10.0,
+            row.share * 100.0
+        )
+        .unwrap();
+    }
+    writeln!(out, "</table>").unwrap();
+    writeln!(out, "</body>").unwrap();
+    writeln!(out, "</html>").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::PartResult;
+    use aoc_2020::progress::Cancelled;
+    use aoc_2020::solution::Answer;
+
+    fn entry(day: u8, answers: Vec<Result<Answer, Cancelled>>, millis: u64) -> Entry {
+        let parts = answers
+            .into_iter()
+            .zip(1..)
+            .map(|(answer, part)| PartResult {
+                part,
+                answer,
+                elapsed: Duration::from_millis(millis),
+                facts: Vec::new(),
+            })
+            .collect();
+        Entry {
+            result: DayResult {
+                day,
+                single_part: day == 25,
+                parse_elapsed: Duration::ZERO,
+                parts,
+            },
+            input_bytes: 2048,
+            input_lines: 1,
+        }
+    }
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            entry(
+                1,
+                vec![Ok(Answer::Int(514579)), Ok(Answer::Int(241861950))],
+                10,
+            ),
+            entry(15, vec![Ok(Answer::UInt(436)), Err(Cancelled)], 40),
+            entry(25, vec![Ok(Answer::Text("a|<b>".to_string()))], 0),
+        ]
+    }
+
+    #[test]
+    fn test_report_notes_links() {
+        let links = notes_links(NOTES, "NOTES.md");
+        assert!((1..=25).all(|day| links.contains_key(&day)));
+        assert_eq!(
+            Some("NOTES.md#day-1---2020-12-01"),
+            links.get(&1).map(|s| s.as_str())
+        );
+        let notes = "# Notes\n## Day (-1) - 2020-11-29\n## Day 7: Bags!\n### Day 8\n";
+        let links = notes_links(notes, "../NOTES.md");
+        assert_eq!(
+            vec![(7, "../NOTES.md#day-7-bags".to_string())],
+            links.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_report_notes_path() {
+        let notes = Path::new("/aoc/NOTES.md");
+        assert_eq!("NOTES.md", relative_link(Path::new("/aoc"), notes));
+        assert_eq!(
+            "../NOTES.md",
+            relative_link(Path::new("/aoc/target"), notes)
+        );
+        assert_eq!(
+            "../../aoc/NOTES.md",
+            relative_link(Path::new("/tmp/reports"), notes)
+        );
+        assert_eq!("NOTES.md", notes_path(Path::new("report.md")));
+        assert_eq!("../NOTES.md", notes_path(Path::new("target/report.md")));
+        assert_eq!("NOTES.md", notes_path(Path::new("target/../report.md")));
+        let elsewhere = env::temp_dir().join("report.md");
+        let link = notes_path(&elsewhere);
+        assert!(absolute(&env::temp_dir().join(link)).is_file());
+    }
+
+    #[test]
+    fn test_report_markdown() {
+        let mut links = BTreeMap::new();
+        links.insert(1, "NOTES.md#day-1".to_string());
+        let expected = "\
+# Advent of Code 2020
+
+Solved 3 days in 100.00ms.
+
+| Day | Part 1 | Part 2 | Time | Input | Cost |
+|-----|--------|--------|-----:|------:|------|
+| [Day 1](NOTES.md#day-1) | 514579 | 241861950 | 20.00ms | 2.0 KiB, 1 line | █████ 20.0% |
+| Day 15 | 436 | timed out | 80.00ms | 2.0 KiB, 1 line | ████████████████████ 80.0% |
+| Day 25 | a\\|<b> |  | 0ns | 2.0 KiB, 1 line |  0.0% |
+";
+        assert_eq!(expected, markdown_with_links(&entries(), &links));
+    }
+
+    #[test]
+    fn test_report_html() {
+        let html = html_with_links(&entries(), &BTreeMap::new());
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<p>Solved 3 days in 100.00ms.</p>"));
+        assert!(html.contains("<td>a|&lt;b&gt;</td>"));
+        assert!(html.contains("style=\"width: 10.0em\"></span>80.0%"));
+        assert!(html.ends_with("</html>\n"));
+    }
+}
@@ -0,0 +1,455 @@
+use crate::cli::Parts;
+use aoc_2020::explain::{self, Fact};
+use aoc_2020::params::Params;
+use aoc_2020::progress::{self, Cancelled};
+use aoc_2020::solution::{Answer, DynSolution};
+use aoc_2020::ParseError;
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct PartResult {
+    pub part: u8,
+    /// The answer, or `Cancelled` when the part timed out.
+    pub answer: Result<Answer, Cancelled>,
+    pub elapsed: Duration,
+    /// Intermediate facts of the solver, collected with [`Supervision::explain`].
+    pub facts: Vec<Fact>,
+}
+
+pub struct DayResult {
+    pub day: u8,
+    pub single_part: bool,
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+pub fn label(day: u8, part: u8, single_part: bool) -> String {
+    if single_part {
+        format!("Day {}", day)
+    } else {
+        format!("Day {}/{}", day, part)
+    }
+}
+
+impl DayResult {
+    pub fn label(&self, part: u8) -> String {
+        label(self.day, part, self.single_part)
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn selected_parts(solution: &dyn DynSolution, parts: Parts) -> impl Iterator<Item = u8> {
+    (1..=solution.parts()).filter(move |&p| parts.contains(p))
+}
+
+/// Time between two reports of the progress of a part.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Limits and feedback of solving the parts.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct Supervision {
+    /// Parts running longer are abandoned.
+    pub timeout: Option<Duration>,
+    /// Whether the progress of slow parts is shown on stderr.
+    pub show_progress: bool,
+    /// Whether the facts the solvers state are collected.
+    pub explain: bool,
+}
+
+impl Supervision {
+    /// Solves the `part` of the `parsed` input within the limits, with the facts stated.
+    pub fn solve(
+        &self,
+        solution: &dyn DynSolution,
+        parsed: &dyn Any,
+        part: u8,
+        params: &Params,
+    ) -> (Result<Answer, Cancelled>, Vec<Fact>) {
+        let mut context = progress::Context {
+            deadline: self.timeout.map(|timeout| Instant::now() + timeout),
+            ..progress::Context::default()
+        };
+        let reported = Rc::new(Cell::new(false));
+        if self.show_progress {
+            let label = label(solution.day(), part, solution.parts() == 1);
+            let reported = reported.clone();
+            context.reporter = Some(Box::new(move |fraction| {
+                eprint!("\r{}: {:.0}%", label, fraction * 100.0);
+                reported.set(true);
+            }));
+            context.interval = PROGRESS_INTERVAL;
+        }
+        let solve = || solution.solve_with(parsed, part, params);
+        let (answer, facts) = progress::with_context(context, || {
+            if self.explain {
+                explain::collect(solve)
+            } else {
+                (solve(), Vec::new())
+            }
+        })
+        .map_or_else(
+            |cancelled| (Err(cancelled), Vec::new()),
+            |(a, f)| (Ok(a), f),
+        );
+        if reported.get() {
+            // erases the progress line
+            eprint!("\r\x1b[K");
+        }
+        (answer, facts)
+    }
+}
+
+pub fn run(
+    solution: &dyn DynSolution,
+    input: &str,
+    parts: Parts,
+    params: &Params,
+    supervision: &Supervision,
+) -> Result<DayResult, ParseError> {
+    let (parsed, parse_elapsed) = timed(|| solution.parse(input));
+    let parsed = parsed?;
+    let parts = selected_parts(solution, parts)
+        .map(|part| {
+            let ((answer, facts), elapsed) =
+                timed(|| supervision.solve(solution, parsed.as_ref(), part, params));
+            PartResult {
+                part,
+                answer,
+                elapsed,
+                facts,
+            }
+        })
+        .collect();
+    Ok(DayResult {
+        day: solution.day(),
+        single_part: solution.parts() == 1,
+        parse_elapsed,
+        parts,
+    })
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len() % 2 == 1 {
+            samples[mid]
+        } else {
+            (samples[mid - 1] + samples[mid]) / 2
+        };
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub day: u8,
+    pub single_part: bool,
+    pub parse: Stats,
+    pub parts: Vec<(u8, Stats)>,
+}
+
+pub fn bench(
+    solution: &dyn DynSolution,
+    input: &str,
+    parts: Parts,
+    params: &Params,
+    runs: usize,
+) -> Result<BenchResult, ParseError> {
+    assert!(runs > 0, "at least one run is required");
+    let parsed = solution.parse(input)?;
+    let sample = |f: &dyn Fn()| {
+        let samples = (0..runs).map(|_| timed(f).1).collect();
+        Stats::from_samples(samples)
+    };
+    let parse = sample(&|| drop(solution.parse(input)));
+    let parts = selected_parts(solution, parts)
+        .map(|part| {
+            (
+                part,
+                sample(&|| drop(solution.solve_with(parsed.as_ref(), part, params))),
+            )
+        })
+        .collect();
+    Ok(BenchResult {
+        day: solution.day(),
+        single_part: solution.parts() == 1,
+        parse,
+        parts,
+    })
+}
+
+// as much as the main thread gets, some of the solutions recurse deeply
+pub const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Maps the jobs with `f` on up to `threads` worker threads and passes the results to `emit`
+/// in the order of the jobs, each as soon as it and all the results before it are done.
+pub fn map_in_order<T, R, F, E>(jobs: Vec<T>, threads: usize, f: F, mut emit: E)
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+    E: FnMut(R),
+{
+    let threads = threads.min(jobs.len());
+    if threads <= 1 {
+        jobs.into_iter().map(f).for_each(emit);
+        return;
+    }
+    let queue = Mutex::new(jobs.into_iter().enumerate());
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let (queue, f, tx) = (&queue, &f, tx.clone());
+            thread::Builder::new()
+                .stack_size(WORKER_STACK_SIZE)
+                .spawn_scoped(scope, move || loop {
+                    let next = queue.lock().unwrap().next();
+                    match next {
+                        Some((i, job)) => tx.send((i, f(job))).unwrap(),
+                        None => break,
+                    }
+                })
+                .expect("cannot spawn a worker thread");
+        }
+        drop(tx);
+
+        let mut done = BTreeMap::new();
+        let mut next = 0;
+        for (i, result) in rx {
+            done.insert(i, result);
+            while let Some(result) = done.remove(&next) {
+                emit(result);
+                next += 1;
+            }
+        }
+    });
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs `f`, a panic is turned into an error with the panic message.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(panic_message)
+}
+
+/// Formats the duration with a unit matching its magnitude, e.g. `1.50ms`.
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", d.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_2020::registry;
+
+    const DAY1_SAMPLE: &str = "1721\n979\n366\n299\n675\n1456\n";
+
+    #[test]
+    fn test_runner_run_selected_parts() {
+        let solution = registry::find(1).unwrap();
+        let result = run(
+            solution,
+            DAY1_SAMPLE,
+            Parts::Only(2),
+            &Params::default(),
+            &Supervision::default(),
+        )
+        .unwrap();
+        assert_eq!(1, result.day);
+        assert_eq!(1, result.parts.len());
+        assert_eq!(2, result.parts[0].part);
+        assert_eq!(Ok(Answer::Int(241861950)), result.parts[0].answer);
+        assert_eq!("Day 1/2", result.label(2));
+    }
+
+    #[test]
+    fn test_runner_timeout() {
+        let solution = registry::find(15).unwrap();
+        let supervision = Supervision {
+            timeout: Some(Duration::from_millis(1)),
+            ..Supervision::default()
+        };
+        let result = run(
+            solution,
+            "0,3,6\n",
+            Parts::Both,
+            &Params::default(),
+            &supervision,
+        )
+        .unwrap();
+        assert_eq!(Ok(Answer::UInt(436)), result.parts[0].answer);
+        assert_eq!(Err(Cancelled), result.parts[1].answer);
+        assert!(result.parts[1].elapsed < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_runner_explain() {
+        let solution = registry::find(1).unwrap();
+        let supervision = Supervision {
+            explain: true,
+            ..Supervision::default()
+        };
+        let result = run(
+            solution,
+            DAY1_SAMPLE,
+            Parts::Both,
+            &Params::default(),
+            &supervision,
+        )
+        .unwrap();
+        let facts: Vec<Vec<String>> = result
+            .parts
+            .iter()
+            .map(|part| part.facts.iter().map(Fact::to_string).collect())
+            .collect();
+        assert_eq!(
+            vec![
+                vec!["pair: 1721 + 299 = 2020"],
+                vec!["triple: 979 + 366 + 675 = 2020"]
+            ],
+            facts
+        );
+        let result = run(
+            solution,
+            DAY1_SAMPLE,
+            Parts::Both,
+            &Params::default(),
+            &Supervision::default(),
+        )
+        .unwrap();
+        assert!(result.parts.iter().all(|part| part.facts.is_empty()));
+    }
+
+    #[test]
+    fn test_runner_bench() {
+        let solution = registry::find(1).unwrap();
+        let result = bench(solution, DAY1_SAMPLE, Parts::Both, &Params::default(), 3).unwrap();
+        assert_eq!(1, result.day);
+        assert_eq!(
+            vec![1, 2],
+            result.parts.iter().map(|p| p.0).collect::<Vec<_>>()
+        );
+        assert!(result.parse.min <= result.parse.median);
+        assert!(result.parse.median <= result.parse.max);
+    }
+
+    #[test]
+    fn test_runner_parse_error() {
+        let solution = registry::find(1).unwrap();
+        let err = run(
+            solution,
+            "1721\n97x9\n",
+            Parts::Both,
+            &Params::default(),
+            &Supervision::default(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((2, 1, "97x9"), (err.line, err.column, err.text.as_str()));
+        assert!(bench(solution, "x", Parts::Both, &Params::default(), 1).is_err());
+    }
+
+    #[test]
+    fn test_runner_catch_panic() {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let caught = catch_panic(|| -> u8 { panic!("bad input {}", 5) });
+        let message = catch_panic(|| -> u8 { panic!("bad input") });
+        panic::set_hook(hook);
+        assert_eq!(Err("bad input 5".to_string()), caught);
+        assert_eq!(Err("bad input".to_string()), message);
+        assert_eq!(Ok(5), catch_panic(|| 5));
+    }
+
+    #[test]
+    fn test_runner_stats_from_samples() {
+        let ms = Duration::from_millis;
+        assert_eq!(
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                max: ms(9)
+            },
+            Stats::from_samples(vec![ms(9), ms(1), ms(3)])
+        );
+        assert_eq!(
+            Stats {
+                min: ms(1),
+                median: ms(4),
+                max: ms(9)
+            },
+            Stats::from_samples(vec![ms(9), ms(1), ms(5), ms(3)])
+        );
+    }
+
+    #[test]
+    fn test_runner_map_in_order() {
+        for threads in [1, 3, 20] {
+            let mut results = Vec::new();
+            let jobs = (0..10).collect::<Vec<u64>>();
+            map_in_order(
+                jobs,
+                threads,
+                |n| {
+                    // later jobs finish first
+                    thread::sleep(Duration::from_millis(10 - n));
+                    n * n
+                },
+                |r| results.push(r),
+            );
+            assert_eq!(
+                (0..10).map(|n| n * n).collect::<Vec<_>>(),
+                results,
+                "with {} threads",
+                threads
+            );
+        }
+    }
+
+    #[test]
+    fn test_runner_format_duration() {
+        assert_eq!("999ns", format_duration(Duration::from_nanos(999)));
+        assert_eq!("1.50µs", format_duration(Duration::from_nanos(1_500)));
+        assert_eq!("12.35ms", format_duration(Duration::from_micros(12_346)));
+        assert_eq!("2.00s", format_duration(Duration::from_secs(2)));
+    }
+}
@@ -0,0 +1,164 @@
+//! Sample inputs from the puzzle descriptions together with their expected answers.
+//!
+//! The inputs are stored in `src/samples`. The [`SAMPLES`] table is the single source of the
+//! sample tests: every entry is also compiled into a unit test of this module.
+
+use crate::params::Params;
+use crate::solution::Answer;
+use crate::{registry, ParseError};
+
+/// Sample input of a part and the answer the puzzle description gives for it.
+#[derive(Debug)]
+pub struct Sample {
+    pub day: u8,
+    pub part: u8,
+    /// Name of the input file in `src/samples`.
+    pub name: &'static str,
+    pub input: &'static str,
+    pub expected: &'static str,
+    /// Parameters of the puzzle which the sample sets differently, like the preamble length
+    /// of day 9.
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+impl Sample {
+    /// Solves the part on the sample input with the parameters of the sample.
+    ///
+    /// # Panics
+    ///
+    /// Panics on parameters the day does not know.
+    pub fn solve(&self) -> Result<Answer, ParseError> {
+        crate::solve_with(self.day, self.part, self.input, &self.params())
+    }
+
+    fn params(&self) -> Params {
+        let declared = registry::find(self.day).map_or(&[][..], |s| s.params());
+        let mut params = Params::default();
+        for (name, value) in self.params {
+            if let Err(err) = params.set(declared, name, value) {
+                panic!("sample {} of day {}: {}", self.name, self.day, err);
+            }
+        }
+        params
+    }
+
+    /// Solves the sample and compares the answer with the expected one.
+    pub fn check(&self) -> Result<Answer, String> {
+        let answer = self.solve().map_err(|err| err.to_string())?;
+        if answer.to_string() == self.expected {
+            Ok(answer)
+        } else {
+            Err(format!("expected {}, got {}", self.expected, answer))
+        }
+    }
+}
+
+macro_rules! samples {
+    ($($test:ident: $day:literal / $part:literal $name:literal => $expected:literal
+        $(, $param:literal = $value:literal)*;)*) => {
+        /// All the samples, ordered by day and part.
+        pub static SAMPLES: &[Sample] = &[$(samples!(@sample $day, $part, $name, $expected,
+            [$(($param, stringify!($value))),*])),*];
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            $(
+                #[test]
+                fn $test() {
+                    let sample = samples!(@sample $day, $part, $name, $expected,
+                        [$(($param, stringify!($value))),*]);
+                    if let Err(err) = sample.check() {
+                        panic!("{} of day {} part {}: {}", $name, $day, $part, err);
+                    }
+                }
+            )*
+        }
+    };
+    (@sample $day:literal, $part:literal, $name:literal, $expected:literal, $params:tt) => {
+        Sample {
+            day: $day,
+            part: $part,
+            name: $name,
+            input: include_str!(concat!("samples/", $name)),
+            expected: $expected,
+            params: &$params,
+        }
+    };
+}
+
+samples! {
+    test_samples_day1_part1: 1 / 1 "day1.txt" => "514579";
+    test_samples_day1_part2: 1 / 2 "day1.txt" => "241861950";
+    test_samples_day2_part1: 2 / 1 "day2.txt" => "2";
+    test_samples_day2_part2: 2 / 2 "day2.txt" => "1";
+    test_samples_day3_part1: 3 / 1 "day3.txt" => "7";
+    test_samples_day3_part2: 3 / 2 "day3.txt" => "336";
+    test_samples_day4_part1: 4 / 1 "day4.txt" => "2";
+    test_samples_day4_part2: 4 / 2 "day4-2.txt" => "4";
+    test_samples_day5_part1: 5 / 1 "day5.txt" => "820";
+    test_samples_day6_part1: 6 / 1 "day6.txt" => "11";
+    test_samples_day6_part2: 6 / 2 "day6.txt" => "6";
+    test_samples_day7_part1: 7 / 1 "day7.txt" => "4";
+    test_samples_day7_part2: 7 / 2 "day7.txt" => "32";
+    test_samples_day7_part2_sample2: 7 / 2 "day7-2.txt" => "126";
+    test_samples_day8_part1: 8 / 1 "day8.txt" => "5";
+    test_samples_day8_part2: 8 / 2 "day8.txt" => "8";
+    test_samples_day9_part1: 9 / 1 "day9.txt" => "127", "preamble" = 5;
+    test_samples_day9_part2: 9 / 2 "day9.txt" => "62", "preamble" = 5;
+    test_samples_day10_part1: 10 / 1 "day10.txt" => "35";
+    test_samples_day10_part1_sample2: 10 / 1 "day10-2.txt" => "220";
+    test_samples_day10_part2: 10 / 2 "day10.txt" => "8";
+    test_samples_day10_part2_sample2: 10 / 2 "day10-2.txt" => "19208";
+    test_samples_day11_part1: 11 / 1 "day11.txt" => "37";
+    test_samples_day11_part2: 11 / 2 "day11.txt" => "26";
+    test_samples_day12_part1: 12 / 1 "day12.txt" => "25";
+    test_samples_day12_part2: 12 / 2 "day12.txt" => "286";
+    test_samples_day13_part1: 13 / 1 "day13.txt" => "295";
+    test_samples_day13_part2: 13 / 2 "day13.txt" => "1068781";
+    test_samples_day13_part2_sample2: 13 / 2 "day13-2.txt" => "3417";
+    test_samples_day13_part2_sample3: 13 / 2 "day13-3.txt" => "754018";
+    test_samples_day13_part2_sample4: 13 / 2 "day13-4.txt" => "779210";
+    test_samples_day13_part2_sample5: 13 / 2 "day13-5.txt" => "1261476";
+    test_samples_day13_part2_sample6: 13 / 2 "day13-6.txt" => "1202161486";
+    test_samples_day14_part1: 14 / 1 "day14.txt" => "165";
+    test_samples_day14_part2: 14 / 2 "day14-2.txt" => "208";
+    test_samples_day15_part1: 15 / 1 "day15.txt" => "436";
+    test_samples_day15_part1_sample2: 15 / 1 "day15-2.txt" => "1";
+    test_samples_day15_part1_sample3: 15 / 1 "day15-3.txt" => "10";
+    test_samples_day15_part1_sample4: 15 / 1 "day15-4.txt" => "27";
+    test_samples_day15_part1_sample5: 15 / 1 "day15-5.txt" => "78";
+    test_samples_day15_part1_sample6: 15 / 1 "day15-6.txt" => "438";
+    test_samples_day15_part1_sample7: 15 / 1 "day15-7.txt" => "1836";
+    test_samples_day15_part2: 15 / 2 "day15.txt" => "175594";
+    test_samples_day15_part2_sample2: 15 / 2 "day15-2.txt" => "2578";
+    test_samples_day15_part2_sample3: 15 / 2 "day15-3.txt" => "3544142";
+    test_samples_day15_part2_sample4: 15 / 2 "day15-4.txt" => "261214";
+    test_samples_day15_part2_sample5: 15 / 2 "day15-5.txt" => "6895259";
+    test_samples_day15_part2_sample6: 15 / 2 "day15-6.txt" => "18";
+    test_samples_day15_part2_sample7: 15 / 2 "day15-7.txt" => "362";
+    test_samples_day15_part2_turns10: 15 / 2 "day15.txt" => "0", "turns" = 10;
+    test_samples_day16_part1: 16 / 1 "day16.txt" => "71";
+    test_samples_day17_part1: 17 / 1 "day17.txt" => "112";
+    test_samples_day17_part2: 17 / 2 "day17.txt" => "848";
+    test_samples_day18_part1: 18 / 1 "day18.txt" => "26335";
+    test_samples_day18_part2: 18 / 2 "day18-2.txt" => "693942";
+    test_samples_day19_part1: 19 / 1 "day19.txt" => "2";
+    test_samples_day19_part1_sample2: 19 / 1 "day19-2.txt" => "3";
+    test_samples_day19_part2: 19 / 2 "day19-2.txt" => "12";
+    test_samples_day20_part1: 20 / 1 "day20.txt" => "20899048083289";
+    test_samples_day20_part2: 20 / 2 "day20.txt" => "273";
+    test_samples_day21_part1: 21 / 1 "day21.txt" => "5";
+    test_samples_day21_part2: 21 / 2 "day21.txt" => "mxmxvkd,sqjhc,fvjkl";
+    test_samples_day22_part1: 22 / 1 "day22.txt" => "306";
+    test_samples_day22_part2: 22 / 2 "day22.txt" => "291";
+    test_samples_day22_part2_sample2: 22 / 2 "day22-2.txt" => "105";
+    test_samples_day23_part1: 23 / 1 "day23.txt" => "67384529";
+    test_samples_day23_part1_moves10: 23 / 1 "day23.txt" => "92658374", "moves" = 10;
+    test_samples_day23_part2: 23 / 2 "day23.txt" => "149245887792";
+    test_samples_day24_part1: 24 / 1 "day24.txt" => "10";
+    test_samples_day24_part2: 24 / 2 "day24.txt" => "2208";
+    test_samples_day24_part2_days10: 24 / 2 "day24.txt" => "37", "days" = 10;
+    test_samples_day25_part1: 25 / 1 "day25.txt" => "14897079";
+}
@@ -0,0 +1,288 @@
+//! Scaffolding of the days of later years, each year a module `yYYYY` of the library.
+//!
+//! A new day gets a module `src/yYYYY/dayN.rs` with stubs of its solvers and a sample test,
+//! and an empty input in `src/yYYYY/input/dayN.txt`. The module of the year lists its days in
+//! `find` and their inputs in `input`, it is generated again from the day modules present.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The year of the days at the root of the library.
+pub const BASE_YEAR: u16 = 2020;
+/// The first year of Advent of Code.
+pub const FIRST_YEAR: u16 = 2015;
+
+const DAY_TEMPLATE: &str = "\
+use crate::ParseError;
+
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    Ok(crate::to_lines(input))
+}
+
+pub fn part1(_lines: &[String]) -> usize {
+    0
+}
+
+pub fn part2(_lines: &[String]) -> usize {
+    0
+}
+
+use crate::solution::{Answer, DynSolution, Solution};
+
+struct Day{day};
+
+pub const SOLUTION: &dyn DynSolution = &Day{day};
+
+impl Solution for Day{day} {
+    const DAY: u8 = {day};
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        part1(parsed).into()
+    }
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        part2(parsed).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the sample input of the puzzle description
+    const SAMPLE: &str = \"\\
+\";
+
+    #[test]
+    fn test_day{day}_sample() {
+        let lines = parse(SAMPLE).unwrap();
+        assert_eq!(0, part1(&lines));
+        assert_eq!(0, part2(&lines));
+    }
+}
+";
+
+/// Name of the module of the `year`.
+fn year_module(year: u16) -> String {
+    format!("y{}", year)
+}
+
+/// Source of the module of a new day.
+fn day_source(day: u8) -> String {
+    DAY_TEMPLATE.replace("{day}", &day.to_string())
+}
+
+/// Source of the module of the `year` with the `days`, in the order rustfmt keeps them.
+fn year_source(year: u16, days: &[u8]) -> String {
+    let mut names: Vec<String> = days.iter().map(|day| format!("day{}", day)).collect();
+    names.sort();
+    let mut source = format!(
+        "//! Solutions of the Advent of Code {} puzzles.\n\
+         //!\n\
+         //! Generated by `aoc_2020 new`, which adds the days to it.\n\
+         \n\
+         use crate::solution::DynSolution;\n\
+         \n",
+        year
+    );
+    for name in names {
+        source += &format!("pub mod {};\n", name);
+    }
+    source += "\npub fn all() -> Vec<&'static dyn DynSolution> {\n    (1..=25).filter_map(find).collect()\n}\n";
+    source += "\npub fn find(day: u8) -> Option<&'static dyn DynSolution> {\n    match day {\n";
+    for day in days {
+        source += &format!("        {} => Some(day{}::SOLUTION),\n", day, day);
+    }
+    source += "        _ => None,\n    }\n}\n";
+    source += "\n/// The input of the `day` in `input/dayN.txt`.\n";
+    source += "pub fn input(day: u8) -> Option<&'static str> {\n    match day {\n";
+    for day in days {
+        source += &format!(
+            "        {} => Some(include_str!(\"input/day{}.txt\")),\n",
+            day, day
+        );
+    }
+    source += "        _ => None,\n    }\n}\n";
+    source
+}
+
+/// Name of the module declared on the `line` of the source of the library.
+fn module_name(line: &str) -> Option<&str> {
+    let line = line.strip_prefix("pub ").unwrap_or(line);
+    line.strip_prefix("mod ")?.strip_suffix(';')
+}
+
+/// Adds the declaration of the module `name` to the source of the library, between the other
+/// modules in alphabetical order. Returns `None` when it is already declared.
+fn declare_module(lib: &str, name: &str) -> Option<String> {
+    let declaration = format!("pub mod {};", name);
+    let lines: Vec<&str> = lib.lines().collect();
+    if lines.contains(&declaration.as_str()) {
+        return None;
+    }
+    let modules: Vec<usize> = (0..lines.len())
+        .filter(|&i| module_name(lines[i]).is_some())
+        .collect();
+    let at = match modules
+        .iter()
+        .find(|&&i| module_name(lines[i]) > Some(name))
+    {
+        // before the attributes of the following module
+        Some(&i) => (0..i)
+            .rev()
+            .take_while(|&j| lines[j].starts_with("#["))
+            .last()
+            .unwrap_or(i),
+        None => modules.last().map_or(0, |&i| i + 1),
+    };
+    let mut lines = lines;
+    lines.insert(at, &declaration);
+    Some(lines.join("\n") + "\n")
+}
+
+/// The days of the year in the directory of its module.
+fn days_in(dir: &Path) -> Result<Vec<u8>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|err| format!("cannot read directory '{}': {}", dir.display(), err))?;
+    let mut days: Vec<u8> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_prefix("day")?.strip_suffix(".rs")?.parse().ok()
+        })
+        .collect();
+    days.sort_unstable();
+    Ok(days)
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("cannot write '{}': {}", path.display(), err))
+}
+
+/// Creates the module and the input of the `day` of the `year` in the crate at `root` and
+/// registers the day in the module of the year, which is added to the library when it is the
+/// first day of it. Returns the created and changed files.
+pub fn create(root: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>, String> {
+    let src = root.join("src");
+    // nothing is written outside of a crate
+    let lib_path = src.join("lib.rs");
+    let lib = fs::read_to_string(&lib_path)
+        .map_err(|err| format!("cannot read '{}': {}", lib_path.display(), err))?;
+    let module = year_module(year);
+    let dir = src.join(&module);
+    let day_path = dir.join(format!("day{}.rs", day));
+    if day_path.exists() {
+        return Err(format!(
+            "day {} of {} exists already in '{}'",
+            day,
+            year,
+            day_path.display()
+        ));
+    }
+    let input_dir = dir.join("input");
+    fs::create_dir_all(&input_dir)
+        .map_err(|err| format!("cannot create '{}': {}", input_dir.display(), err))?;
+
+    let mut changed = Vec::new();
+    write(&day_path, &day_source(day))?;
+    changed.push(day_path);
+    // an input pasted before is kept
+    let input_path = input_dir.join(format!("day{}.txt", day));
+    if !input_path.exists() {
+        write(&input_path, "")?;
+        changed.push(input_path);
+    }
+    let year_path = dir.join("mod.rs");
+    write(&year_path, &year_source(year, &days_in(&dir)?))?;
+    changed.push(year_path);
+
+    if let Some(lib) = declare_module(&lib, &module) {
+        write(&lib_path, &lib)?;
+        changed.push(lib_path);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaffold_day_source() {
+        let source = day_source(7);
+        assert!(source.contains("\nstruct Day7;\n"));
+        assert!(source.contains("    const DAY: u8 = 7;\n"));
+        assert!(source.contains("    fn test_day7_sample() {\n"));
+        assert!(source.contains("    const SAMPLE: &str = \"\\\n\";\n"));
+        assert!(!source.contains("{day}"));
+    }
+
+    #[test]
+    fn test_scaffold_year_source() {
+        let source = year_source(2021, &[2, 10]);
+        assert!(source.starts_with("//! Solutions of the Advent of Code 2021 puzzles.\n"));
+        assert!(source.contains("\npub mod day10;\npub mod day2;\n"));
+        assert!(source.contains(
+            "        2 => Some(day2::SOLUTION),\n        10 => Some(day10::SOLUTION),\n"
+        ));
+        assert!(source.contains("        10 => Some(include_str!(\"input/day10.txt\")),\n"));
+    }
+
+    #[test]
+    fn test_scaffold_declare_module() {
+        let lib = "//! Docs.\n\npub mod day1;\n#[cfg(test)]\nmod differential;\npub mod y2022;\n\nuse std::fmt;\n";
+        assert_eq!(
+            Some("//! Docs.\n\npub mod day1;\n#[cfg(test)]\nmod differential;\npub mod y2021;\npub mod y2022;\n\nuse std::fmt;\n".to_string()),
+            declare_module(lib, "y2021")
+        );
+        assert_eq!(
+            Some("//! Docs.\n\npub mod day1;\npub mod deque;\n#[cfg(test)]\nmod differential;\npub mod y2022;\n\nuse std::fmt;\n".to_string()),
+            declare_module(lib, "deque")
+        );
+        assert!(declare_module(lib, "y2022").is_none());
+    }
+
+    #[test]
+    fn test_scaffold_create() {
+        let root = std::env::temp_dir().join(format!("aoc_2020_scaffold_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod day1;\n").unwrap();
+        let first = create(&root, 2021, 3);
+        fs::create_dir_all(root.join("src/y2021/input")).unwrap();
+        fs::write(root.join("src/y2021/input/day1.txt"), "pasted\n").unwrap();
+        let second = create(&root, 2021, 1);
+        let again = create(&root, 2021, 3);
+        let outside = create(&root.join("src"), 2021, 4);
+        let written_outside = root.join("src/src").exists();
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        let year = fs::read_to_string(root.join("src/y2021/mod.rs")).unwrap();
+        let input = fs::read_to_string(root.join("src/y2021/input/day1.txt")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let relative = |paths: Vec<PathBuf>| -> Vec<String> {
+            let paths = paths.iter().map(|p| p.strip_prefix(&root).unwrap());
+            paths.map(|p| p.display().to_string()).collect()
+        };
+        assert_eq!(
+            vec![
+                "src/y2021/day3.rs",
+                "src/y2021/input/day3.txt",
+                "src/y2021/mod.rs",
+                "src/lib.rs"
+            ],
+            relative(first.unwrap())
+        );
+        assert_eq!(
+            vec!["src/y2021/day1.rs", "src/y2021/mod.rs"],
+            relative(second.unwrap())
+        );
+        assert!(again.unwrap_err().contains("exists already"));
+        assert!(outside.unwrap_err().contains("cannot read"));
+        assert!(!written_outside);
+        assert_eq!("pub mod day1;\npub mod y2021;\n", lib);
+        assert_eq!(year_source(2021, &[1, 3]), year);
+        assert_eq!("pasted\n", input);
+    }
+}
@@ -0,0 +1,473 @@
+//! Minimal HTTP/1.1 service solving the puzzles, for tools which cannot link the library.
+//!
+//! `GET /days` lists the solutions and `POST /day/N/part/P` solves the part on the request
+//! body. Every response is JSON and closes the connection.
+//!
+//! Each connection is handled by a worker thread, up to [`Limits::workers`] of them; the
+//! connections beyond are refused with `503 Service Unavailable`. Solvers running past
+//! [`Limits::timeout`] are abandoned like with `--timeout` and answered with
+//! `504 Gateway Timeout`.
+
+use crate::input;
+use crate::output::{json_string, Record};
+use crate::runner::{self, catch_panic, format_duration, Supervision};
+use aoc_2020::params::Params;
+use aoc_2020::{registry, ParseError};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_PORT: u16 = 2020;
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+pub const DEFAULT_WORKERS: usize = 4;
+
+/// Larger request bodies are refused, the real puzzle inputs have at most a few dozens of KiB.
+const MAX_BODY_LEN: usize = 1024 * 1024;
+/// Time to wait for a slow client.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time to wait for the request of a refused connection, which is read before answering it so
+/// that closing the connection does not reset it.
+const REFUSED_READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Limits of the service.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Limits {
+    /// Solvers running longer are abandoned.
+    pub timeout: Duration,
+    /// Maximum number of connections handled at once.
+    pub workers: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            timeout: DEFAULT_TIMEOUT,
+            workers: DEFAULT_WORKERS,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+#[derive(PartialEq, Debug)]
+struct Response {
+    status: u16,
+    /// Methods allowed for the path, for `405 Method Not Allowed`.
+    allow: Option<&'static str>,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Response {
+        Response {
+            status: 200,
+            allow: None,
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response {
+            status,
+            allow: None,
+            body: format!("{{\"error\": {}}}", json_string(message)),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "HTTP/1.1 {} {}\r\n", self.status, self.reason())?;
+        write!(out, "Content-Type: application/json\r\n")?;
+        write!(out, "Content-Length: {}\r\n", self.body.len() + 1)?;
+        if let Some(allow) = self.allow {
+            write!(out, "Allow: {}\r\n", allow)?;
+        }
+        write!(out, "Connection: close\r\n\r\n{}\n", self.body)?;
+        out.flush()
+    }
+}
+
+/// Reads the request line, the headers and the body given by `Content-Length`.
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let bad_request = |message: &str| Response::error(400, message);
+    let mut read_line = || {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => Err(bad_request("incomplete request")),
+            Ok(_) => Ok(line.trim_end_matches(['\r', '\n']).to_string()),
+        }
+    };
+    let request_line = read_line()?;
+    let mut parts = request_line.split(' ');
+    let (method, path) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(path), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), path.to_string())
+        }
+        _ => return Err(bad_request("malformed request line")),
+    };
+    let mut content_length = 0;
+    loop {
+        let header = read_line()?;
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| bad_request("malformed header"))?;
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .trim()
+                .parse()
+                .map_err(|_| bad_request("invalid Content-Length"))?;
+        }
+    }
+    if content_length > MAX_BODY_LEN {
+        return Err(Response::error(413, "request body is too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad_request("incomplete request body"))?;
+    Ok(Request { method, path, body })
+}
+
+fn list_days() -> Response {
+    let days: Vec<String> = registry::all()
+        .iter()
+        .map(|s| format!("{{\"day\": {}, \"parts\": {}}}", s.day(), s.parts()))
+        .collect();
+    Response::ok(format!("{{\"days\": [{}]}}", days.join(", ")))
+}
+
+fn solve(day: &str, part: &str, body: &[u8], timeout: Duration) -> Response {
+    let solution = match day.parse().ok().and_then(registry::find) {
+        Some(solution) => solution,
+        None => return Response::error(404, &format!("no solution for day '{}'", day)),
+    };
+    let part = match part.parse::<u8>() {
+        Ok(part) if (1..=solution.parts()).contains(&part) => part,
+        _ => {
+            let message = format!("day {} has no part '{}'", solution.day(), part);
+            return Response::error(404, &message);
+        }
+    };
+    let input = match std::str::from_utf8(body) {
+        Ok(input) => input::normalize(input.to_string()),
+        Err(_) => return Response::error(400, "the input is not valid UTF-8"),
+    };
+    let supervision = Supervision {
+        timeout: Some(timeout),
+        ..Supervision::default()
+    };
+    let start = Instant::now();
+    let solved = catch_panic(|| -> Result<_, ParseError> {
+        let parsed = solution.parse(&input)?;
+        let (answer, _) = supervision.solve(solution, parsed.as_ref(), part, &Params::default());
+        Ok(answer)
+    });
+    match solved {
+        Ok(Ok(Ok(answer))) => {
+            let record = Record {
+                day: solution.day(),
+                part,
+                answer: &answer,
+                elapsed: start.elapsed(),
+                verdict: None,
+                facts: None,
+            };
+            Response::ok(record.to_json())
+        }
+        Ok(Ok(Err(_))) => {
+            let message = format!("the solver ran longer than {}", format_duration(timeout));
+            Response::error(504, &message)
+        }
+        Ok(Err(err)) => Response {
+            status: 422,
+            allow: None,
+            body: format!(
+                "{{\"error\": {}, \"line\": {}, \"column\": {}}}",
+                json_string(&err.to_string()),
+                err.line,
+                err.column
+            ),
+        },
+        Err(message) => Response::error(500, &format!("the solver panicked: {}", message)),
+    }
+}
+
+fn handle(request: &Request, limits: &Limits) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let (allow, response) = match segments[..] {
+        ["days"] => ("GET", (request.method == "GET").then(list_days)),
+        ["day", day, "part", part] => (
+            "POST",
+            (request.method == "POST").then(|| solve(day, part, &request.body, limits.timeout)),
+        ),
+        _ => return Response::error(404, &format!("no such resource '{}'", request.path)),
+    };
+    response.unwrap_or_else(|| Response {
+        allow: Some(allow),
+        ..Response::error(405, &format!("use {} for '{}'", allow, request.path))
+    })
+}
+
+/// A worker counted in the busy ones until it is dropped.
+struct Worker(Arc<AtomicUsize>);
+
+impl Worker {
+    /// Counts a new worker, unless `max` of them are busy already.
+    fn hire(busy: &Arc<AtomicUsize>, max: usize) -> Option<Worker> {
+        busy.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+            (n < max).then_some(n + 1)
+        })
+        .ok()
+        .map(|_| Worker(busy.clone()))
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Answers the request on the `stream`, with `refused` when there is no worker for it.
+fn handle_connection(stream: TcpStream, limits: &Limits, refused: bool) -> io::Result<()> {
+    let read_timeout = if refused {
+        REFUSED_READ_TIMEOUT
+    } else {
+        READ_TIMEOUT
+    };
+    stream.set_read_timeout(Some(read_timeout))?;
+    let mut reader = BufReader::new(&stream);
+    let start = Instant::now();
+    let (line, response) = match read_request(&mut reader) {
+        Ok(request) => (
+            format!("{} {}", request.method, request.path),
+            if refused {
+                Response::error(503, "all the workers are busy, retry later")
+            } else {
+                handle(&request, limits)
+            },
+        ),
+        Err(response) => ("-".to_string(), response),
+    };
+    println!(
+        "{} {} ({})",
+        line,
+        response.status,
+        format_duration(start.elapsed())
+    );
+    response.write_to(&mut &stream)
+}
+
+/// Serves the requests on the localhost `port` within the `limits` until the process is
+/// stopped.
+pub fn serve(port: u16, limits: Limits) -> Result<(), String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| format!("cannot listen on port {}: {}", port, e))?;
+    println!("Listening on http://{}", listener.local_addr().unwrap());
+    let busy = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("error: cannot accept a connection: {}", err);
+                continue;
+            }
+        };
+        let worker = match Worker::hire(&busy, limits.workers) {
+            Some(worker) => worker,
+            None => {
+                // refused on the listening thread, which reads the request only briefly
+                if let Err(err) = handle_connection(stream, &limits, true) {
+                    eprintln!("error: cannot respond: {}", err);
+                }
+                continue;
+            }
+        };
+        // a slow day must not block the others
+        thread::Builder::new()
+            .stack_size(runner::WORKER_STACK_SIZE)
+            .spawn(move || {
+                if let Err(err) = handle_connection(stream, &limits, false) {
+                    eprintln!("error: cannot respond: {}", err);
+                }
+                drop(worker);
+            })
+            .map_err(|e| format!("cannot spawn a worker thread: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn handle_default(request: &Request) -> Response {
+        handle(request, &Limits::default())
+    }
+
+    /// Sends the `input` to day 1 part 1 over a connection handled with `refused`, returns the
+    /// raw response.
+    fn exchange(refused: bool, input: &str) -> String {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_connection(stream, &Limits::default(), refused).unwrap();
+        });
+        let mut client = TcpStream::connect(addr).unwrap();
+        write!(
+            client,
+            "POST /day/1/part/1 HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            input.len(),
+            input
+        )
+        .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        server.join().unwrap();
+        response
+    }
+
+    #[test]
+    fn test_server_read_request() {
+        let raw = "POST /day/1/part/2 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 9\r\n\r\n1721\n979\nrest";
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+        assert_eq!(
+            self::request("POST", "/day/1/part/2", "1721\n979\n"),
+            request
+        );
+
+        let raw = "GET /days HTTP/1.0\n\n";
+        assert_eq!(
+            Ok(self::request("GET", "/days", "")),
+            read_request(&mut raw.as_bytes())
+        );
+
+        let status = |raw: &str| read_request(&mut raw.as_bytes()).unwrap_err().status;
+        assert_eq!(400, status("GET /days\r\n\r\n"));
+        assert_eq!(400, status("GET /days HTTP/1.1\r\nHost localhost\r\n\r\n"));
+        assert_eq!(
+            400,
+            status("POST /day/1/part/1 HTTP/1.1\r\nContent-Length: 5\r\n\r\n12")
+        );
+        assert_eq!(400, status("GET /days HTTP/1.1\r\n"));
+        assert_eq!(
+            413,
+            status("POST / HTTP/1.1\r\nContent-Length: 9999999\r\n\r\n")
+        );
+    }
+
+    #[test]
+    fn test_server_solve() {
+        let response = handle_default(&request(
+            "POST",
+            "/day/8/part/1",
+            "nop +0\r\nacc +1\r\njmp -2\r\n",
+        ));
+        assert_eq!(200, response.status);
+        assert!(response
+            .body
+            .starts_with(r#"{"day": 8, "part": 1, "answer": "1", "type": "int", "elapsed_ns": "#));
+
+        let response = handle_default(&request("POST", "/day/8/part/1", "nop +0\nadd +1\n"));
+        assert_eq!(422, response.status);
+        assert_eq!(
+            r#"{"error": "line 2, column 1: unknown operation in 'add'", "line": 2, "column": 1}"#,
+            response.body
+        );
+        let response = handle_default(&request("POST", "/day/9/part/2", ""));
+        assert_eq!(422, response.status);
+        assert_eq!(
+            r#"{"error": "line 1, column 1: fewer than two numbers", "line": 1, "column": 1}"#,
+            response.body
+        );
+    }
+
+    #[test]
+    fn test_server_timeout() {
+        let limits = Limits {
+            timeout: Duration::from_millis(1),
+            ..Limits::default()
+        };
+        let response = handle(&request("POST", "/day/15/part/2", "0,3,6\n"), &limits);
+        assert_eq!(504, response.status);
+        assert!(response.body.contains("the solver ran longer than"));
+    }
+
+    #[test]
+    fn test_server_workers() {
+        let busy = Arc::new(AtomicUsize::new(0));
+        let first = Worker::hire(&busy, 2);
+        let second = Worker::hire(&busy, 2);
+        assert!(first.is_some() && second.is_some());
+        assert!(Worker::hire(&busy, 2).is_none());
+        drop(first);
+        assert_eq!(1, busy.load(Ordering::SeqCst));
+        assert!(Worker::hire(&busy, 2).is_some());
+        assert_eq!(1, busy.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_server_routes() {
+        let response = handle_default(&request("GET", "/days", ""));
+        assert_eq!(200, response.status);
+        assert!(response
+            .body
+            .starts_with(r#"{"days": [{"day": 1, "parts": 2}, "#));
+        assert!(response.body.ends_with(r#"{"day": 25, "parts": 1}]}"#));
+
+        let status = |method, path| handle_default(&request(method, path, "1\n")).status;
+        assert_eq!(404, status("POST", "/day/26/part/1"));
+        assert_eq!(404, status("POST", "/day/25/part/2"));
+        assert_eq!(404, status("POST", "/day/x/part/1"));
+        assert_eq!(404, status("GET", "/"));
+        let response = handle_default(&request("GET", "/day/1/part/1", ""));
+        assert_eq!((405, Some("POST")), (response.status, response.allow));
+        assert_eq!(405, status("POST", "/days"));
+    }
+
+    #[test]
+    fn test_server_connection() {
+        let response = exchange(false, "1721\n979\n366\n299\n675\n1456\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\r\nConnection: close\r\n"));
+        assert!(response.contains(r#""answer": "514579""#));
+
+        let response = exchange(true, "1721\n979\n366\n299\n675\n1456\n");
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.contains("all the workers are busy"));
+    }
+}
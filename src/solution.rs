@@ -0,0 +1,221 @@
+use crate::params::{Param, Params};
+use crate::ParseError;
+use std::any::Any;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+    /// The input has no solution for the part, like with parameters the puzzle does not use.
+    None,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::UInt(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::None => write!(f, "no solution"),
+        }
+    }
+}
+
+impl Answer {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Answer::Int(_) => "int",
+            Answer::UInt(_) => "uint",
+            Answer::Text(_) => "text",
+            Answer::None => "none",
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Int(n.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Int(n)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::UInt(n.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::UInt(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::UInt(n as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::None, Into::into)
+    }
+}
+
+/// Solution of a single day's puzzle.
+///
+/// Input is parsed once and the parsed data is shared by both parts.
+pub trait Solution {
+    const DAY: u8;
+    /// Number of parts of the puzzle, the last day has only one.
+    const PARTS: u8 = 2;
+
+    type Parsed: 'static;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError>;
+    fn part1(parsed: &Self::Parsed) -> Answer;
+    fn part2(_parsed: &Self::Parsed) -> Answer {
+        unreachable!("day {} has only one part", Self::DAY)
+    }
+    /// Parameters of the puzzle which [`part1_with`](Solution::part1_with) and
+    /// [`part2_with`](Solution::part2_with) accept.
+    const PARAMS: &'static [Param] = &[];
+    /// Solves the first part with the `params` set to other values than the puzzle's.
+    fn part1_with(parsed: &Self::Parsed, _params: &Params) -> Answer {
+        Self::part1(parsed)
+    }
+    fn part2_with(parsed: &Self::Parsed, _params: &Params) -> Answer {
+        Self::part2(parsed)
+    }
+    /// Answer the part gives up with when the input has no solution, if it has such.
+    fn sentinel(_part: u8) -> Option<Answer> {
+        None
+    }
+}
+
+/// Object safe counterpart of the `Solution` so that all days can be kept together.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+    fn parts(&self) -> u8;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+    fn solve(&self, parsed: &dyn Any, part: u8) -> Answer;
+    fn params(&self) -> &'static [Param];
+    /// Solves the part with the parameters, which must be the declared ones.
+    fn solve_with(&self, parsed: &dyn Any, part: u8, params: &Params) -> Answer;
+    fn sentinel(&self, part: u8) -> Option<Answer>;
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parts(&self) -> u8 {
+        S::PARTS
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        let parsed = S::parse(input)?;
+        Ok(Box::new(parsed))
+    }
+
+    fn solve(&self, parsed: &dyn Any, part: u8) -> Answer {
+        self.solve_with(parsed, part, &Params::default())
+    }
+
+    fn params(&self) -> &'static [Param] {
+        S::PARAMS
+    }
+
+    fn solve_with(&self, parsed: &dyn Any, part: u8, params: &Params) -> Answer {
+        let parsed = parsed
+            .downcast_ref::<S::Parsed>()
+            .unwrap_or_else(|| panic!("data parsed by other day than {}", S::DAY));
+        match part {
+            1 => S::part1_with(parsed, params),
+            2 if S::PARTS >= 2 => S::part2_with(parsed, params),
+            _ => panic!("day {} has no part {}", S::DAY, part),
+        }
+    }
+
+    fn sentinel(&self, part: u8) -> Option<Answer> {
+        S::sentinel(part)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 100;
+        const PARTS: u8 = 1;
+        type Parsed = Vec<i32>;
+
+        fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+            let mut cursor = crate::parser::Cursor::new(input);
+            let nums = cursor.separated(",", |c| c.take_number())?;
+            cursor.expect_end()?;
+            Ok(nums)
+        }
+        fn part1(parsed: &Self::Parsed) -> Answer {
+            parsed.iter().sum::<i32>().into()
+        }
+        const PARAMS: &'static [Param] = &[Param::number("offset", 0, 100, "added to the sum")];
+        fn part1_with(parsed: &Self::Parsed, params: &Params) -> Answer {
+            (parsed.iter().sum::<i32>() + params.number("offset", 0)).into()
+        }
+    }
+
+    #[test]
+    fn test_solution_dyn_solve() {
+        let solution: &dyn DynSolution = &Sum;
+        let parsed = solution.parse("1,2,-5").unwrap();
+        assert_eq!(100, solution.day());
+        assert_eq!(Answer::Int(-2), solution.solve(parsed.as_ref(), 1));
+        let mut params = Params::default();
+        params.set(solution.params(), "offset", "10").unwrap();
+        assert_eq!(
+            Answer::Int(8),
+            solution.solve_with(parsed.as_ref(), 1, &params)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_solution_dyn_solve_missing_part() {
+        let solution: &dyn DynSolution = &Sum;
+        let parsed = solution.parse("1").unwrap();
+        solution.solve(parsed.as_ref(), 2);
+    }
+
+    #[test]
+    fn test_solution_dyn_parse_error() {
+        let solution: &dyn DynSolution = &Sum;
+        let err = solution.parse("1,x").err().unwrap();
+        assert_eq!((1, 3), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_solution_answer_display() {
+        assert_eq!("-7", Answer::from(-7).to_string());
+        assert_eq!("18446744073709551615", Answer::from(u64::MAX).to_string());
+        assert_eq!("a,b", Answer::from("a,b".to_string()).to_string());
+    }
+}
@@ -0,0 +1,234 @@
+//! Solves a day again whenever its input file changes, found by polling its modification time.
+
+use crate::cli::{Args, Selection};
+use crate::input::{self, Source};
+use crate::runner::{self, catch_panic, format_duration, DayResult, Supervision};
+use aoc_2020::params::Params;
+use aoc_2020::registry;
+use aoc_2020::solution::Answer;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification time and length of the file, an edit changes at least one of them even on
+/// file systems with coarse timestamps.
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(Stamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+/// The file to watch: the `--input` file, or the one in the input directory, or the source of
+/// the embedded input when the program runs from its repository.
+fn watched_path(args: &Args, day: u8) -> Result<PathBuf, String> {
+    match &args.input {
+        Some(Source::File(path)) => return Ok(path.clone()),
+        Some(Source::Stdin) => return Err("stdin cannot be watched".to_string()),
+        None => (),
+    }
+    let path = input::path_in_dir(&args.input_dir, day);
+    if path.is_file() {
+        return Ok(path);
+    }
+    let embedded = input::embedded_path(day);
+    if embedded.is_file() {
+        Ok(embedded)
+    } else {
+        Err(format!(
+            "no input file of day {} to watch, expected '{}'",
+            day,
+            path.display()
+        ))
+    }
+}
+
+/// Answers of the parts, or the error which prevented them.
+type Answers = Result<Vec<(String, Answer)>, String>;
+
+fn solve(
+    selection: &Selection,
+    path: &Path,
+    params: &Params,
+    supervision: &Supervision,
+) -> (Answers, Option<DayResult>) {
+    let solution = registry::find(selection.day).expect("selected day is registered");
+    let input = match input::read(&Source::File(path.to_path_buf())) {
+        Ok(input) => input,
+        Err(err) => return (Err(err), None),
+    };
+    // panics of the solvers are reported as errors of the run
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let caught =
+        catch_panic(|| runner::run(solution, &input, selection.parts, params, supervision));
+    panic::set_hook(hook);
+    match caught {
+        Ok(Ok(result)) => {
+            let answers = result
+                .parts
+                .iter()
+                .map(|part| match &part.answer {
+                    Ok(answer) => Ok((result.label(part.part), answer.clone())),
+                    Err(_) => Err(format!(
+                        "{} timed out after {}",
+                        result.label(part.part),
+                        format_duration(part.elapsed)
+                    )),
+                })
+                .collect();
+            (answers, Some(result))
+        }
+        Ok(Err(err)) => (Err(format!("day {}: {}", selection.day, err)), None),
+        Err(msg) => (
+            Err(format!("day {} panicked: {}", selection.day, msg)),
+            None,
+        ),
+    }
+}
+
+/// Lines comparing the `current` answers with the `previous` ones.
+fn diff(previous: Option<&Answers>, current: &Answers) -> Vec<String> {
+    let current = match current {
+        Ok(current) => current,
+        Err(err) => return vec![format!("error: {}", err)],
+    };
+    current
+        .iter()
+        .map(|(label, answer)| {
+            let before = match previous {
+                Some(Ok(previous)) => previous.iter().find(|(l, _)| l == label),
+                _ => None,
+            };
+            match before {
+                None => format!("{}: {}", label, answer),
+                Some((_, before)) if before == answer => {
+                    format!("{}: {} (unchanged)", label, answer)
+                }
+                Some((_, before)) => format!("{}: {} -> {}", label, before, answer),
+            }
+        })
+        .collect()
+}
+
+/// Solves the single selected day on every change of its input, until the process is stopped.
+pub fn run(args: &Args) -> Result<(), String> {
+    let selection = &args.selections[0];
+    let path = watched_path(args, selection.day)?;
+    println!("Watching {}", path.display());
+    let supervision = Supervision {
+        timeout: args.timeout,
+        show_progress: io::stderr().is_terminal(),
+        explain: false,
+    };
+    let params = args.params.for_day(selection.day);
+    let mut last_stamp = None;
+    let mut previous = None;
+    loop {
+        let current_stamp = stamp(&path);
+        if current_stamp.is_some() && current_stamp != last_stamp {
+            if last_stamp.is_some() {
+                println!();
+                println!("Input changed, solving again");
+            }
+            last_stamp = current_stamp;
+            let (answers, result) = solve(selection, &path, params, &supervision);
+            for line in diff(previous.as_ref(), &answers) {
+                println!("{}", line);
+            }
+            if let (true, Some(result)) = (args.time, result) {
+                let total = result.parse_elapsed + result.parts.iter().map(|p| p.elapsed).sum();
+                println!("Solved in {}", format_duration(total));
+            }
+            previous = Some(answers);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Parts;
+
+    fn answers(values: &[(&str, i64)]) -> Answers {
+        Ok(values
+            .iter()
+            .map(|&(label, n)| (label.to_string(), Answer::Int(n)))
+            .collect())
+    }
+
+    #[test]
+    fn test_watch_diff() {
+        let first = answers(&[("Day 8/1", 5), ("Day 8/2", 8)]);
+        assert_eq!(vec!["Day 8/1: 5", "Day 8/2: 8"], diff(None, &first));
+        let second = answers(&[("Day 8/1", 5), ("Day 8/2", 9)]);
+        assert_eq!(
+            vec!["Day 8/1: 5 (unchanged)", "Day 8/2: 8 -> 9"],
+            diff(Some(&first), &second)
+        );
+        let failed = Err("day 8: line 1, column 1: unknown operation in 'add'".to_string());
+        assert_eq!(
+            vec!["error: day 8: line 1, column 1: unknown operation in 'add'"],
+            diff(Some(&second), &failed)
+        );
+        assert_eq!(
+            vec!["Day 8/1: 5", "Day 8/2: 8"],
+            diff(Some(&failed), &first)
+        );
+    }
+
+    #[test]
+    fn test_watch_solves_changed_file() {
+        let path = std::env::temp_dir().join(format!("aoc_2020_watch_{}.txt", std::process::id()));
+        let selection = Selection {
+            day: 8,
+            parts: Parts::Both,
+        };
+        fs::write(&path, "nop +0\nacc +1\njmp -2\n").unwrap();
+        let before = stamp(&path);
+        let (first, _) = solve(
+            &selection,
+            &path,
+            &Params::default(),
+            &Supervision::default(),
+        );
+        fs::write(&path, "nop +0\nacc +2\njmp -2\nacc +4\n").unwrap();
+        let after = stamp(&path);
+        let (second, _) = solve(
+            &selection,
+            &path,
+            &Params::default(),
+            &Supervision::default(),
+        );
+        fs::write(&path, "nop +0\nadd +1\n").unwrap();
+        let (third, _) = solve(
+            &selection,
+            &path,
+            &Params::default(),
+            &Supervision::default(),
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert!(before.is_some());
+        assert_ne!(before, after);
+        assert_eq!(None, stamp(&path));
+        assert_eq!(
+            vec!["Day 8/1: 1 -> 2", "Day 8/2: 1 -> 6"],
+            diff(Some(&first), &second)
+        );
+        assert!(third.unwrap_err().starts_with("day 8: line 2, column 1: "));
+    }
+}
@@ -0,0 +1,44 @@
+//! Runs of the binary with puzzle parameters the embedded inputs have no solution for.
+
+use std::process::Command;
+
+/// Runs the binary with the `args`, returns its exit code and stdout.
+fn run(args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc_2020"))
+        .args(args)
+        .output()
+        .expect("cannot run the binary");
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn test_cli_sum_without_entries() {
+    let (code, stdout) = run(&["--param", "day1.sum=5", "1"]);
+    assert_eq!(Some(0), code);
+    assert!(
+        stdout.contains("Day 1/1: no solution\nDay 1/2: no solution\n"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_cli_bag_without_rule() {
+    let (code, stdout) = run(&["--param", "day7.bag=foo", "7"]);
+    assert_eq!(Some(0), code);
+    assert!(
+        stdout.contains("Day 7/1: no solution\nDay 7/2: no solution\n"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_cli_subject_without_loop_size() {
+    let (code, stdout) = run(&["--param", "day25.modulus=5", "25"]);
+    assert_eq!(Some(0), code);
+    assert!(stdout.contains("Day 25: no solution\n"), "{}", stdout);
+}
@@ -0,0 +1,108 @@
+use aoc_2020::*;
+
+#[test]
+fn test_days_report_repair() {
+    let nums = day1::parse("1721\n979\n366\n299\n675\n1456\n").unwrap();
+    assert_eq!(514579, day1::part1(&nums));
+    assert_eq!(241861950, day1::part2(&nums));
+}
+
+#[test]
+fn test_days_passports() {
+    let passports: Vec<day4::Passport> = day4::parse(
+        "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+",
+    )
+    .unwrap();
+    assert_eq!(3, passports.len());
+    assert_eq!(2, day4::part1(&passports));
+    assert_eq!(2, day4::part2(&passports));
+}
+
+#[test]
+fn test_days_boot_code() {
+    let code: day8::BootCode = day8::parse(
+        "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+",
+    )
+    .unwrap();
+    assert_eq!(5, day8::part1(&code));
+    assert_eq!(8, day8::part2(&code));
+}
+
+#[test]
+fn test_days_shuttle_notes() {
+    let notes: day13::Notes = day13::parse("939\n7,13,x,x,59,x,31,19\n").unwrap();
+    assert_eq!(295, day13::part1(&notes));
+    assert_eq!(1068781, day13::part2(&notes));
+}
+
+#[test]
+fn test_days_ticket_notes() {
+    let notes: day16::Notes = day16::parse(
+        "\
+class: 1-3 or 5-7
+row: 6-11 or 33-44
+seat: 13-40 or 45-50
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4,50
+55,2,20
+38,6,12
+",
+    )
+    .unwrap();
+    assert_eq!(71, day16::part1(&notes));
+}
+
+#[test]
+fn test_days_tiles() {
+    let tiles: Vec<day20::Tile> = day20::parse("Tile 1:\n#.\n.#\n\nTile 2:\n..\n##\n").unwrap();
+    assert_eq!(2, tiles.len());
+}
+
+#[test]
+fn test_days_crab_combat() {
+    let decks = day22::parse("Player 1:\n9\n2\n6\n3\n1\n\nPlayer 2:\n5\n8\n4\n7\n10\n").unwrap();
+    assert_eq!(Some(306), day22::part1(&decks));
+    assert_eq!(291, day22::part2(&decks));
+}
+
+#[test]
+fn test_days_combo_breaker() {
+    let keys = day25::parse("5764801\n17807724\n").unwrap();
+    assert_eq!(14897079, day25::part1(&keys));
+}
+
+#[test]
+fn test_days_parse_errors() {
+    let err = day8::parse("nop +0\nadd +1\n").err().unwrap();
+    assert_eq!((2, 1), (err.line, err.column));
+    assert_eq!(
+        "line 2, column 1: unknown operation in 'add'",
+        err.to_string()
+    );
+}
@@ -0,0 +1,44 @@
+//! The seed corpus of the fuzz targets in `fuzz/corpus` covers every day and its inputs go
+//! through the parsers and solvers of the fuzz targets without panicking.
+
+#[path = "../fuzz/src/lib.rs"]
+mod fuzz;
+
+use aoc_2020::params::Kind;
+use aoc_2020::registry;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_fuzz_seeds_solve() {
+    let seeds = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus");
+    for solution in registry::all() {
+        let dir = seeds.join(format!("day{}", solution.day()));
+        let entries = fs::read_dir(&dir).unwrap_or_else(|e| panic!("{}: {}", dir.display(), e));
+        let mut parsed = 0;
+        for entry in entries {
+            let input = fs::read_to_string(entry.unwrap().path()).unwrap();
+            parsed += solution.parse(&input).is_ok() as usize;
+            fuzz::parse_and_solve(solution.day(), input.as_bytes());
+        }
+        assert!(parsed > 0, "no valid seed input for day {}", solution.day());
+    }
+}
+
+#[test]
+fn test_fuzz_params_capped() {
+    for solution in registry::all() {
+        for param in solution.params() {
+            if let Kind::Number { min, max } = param.kind {
+                let (_, _, value) = fuzz::CAPPED_PARAMS
+                    .iter()
+                    .find(|capped| (capped.0, capped.1) == (solution.day(), param.name))
+                    .unwrap_or_else(|| {
+                        panic!("day {} '{}' not capped", solution.day(), param.name)
+                    });
+                let value: u64 = value.parse().unwrap();
+                assert!((min..=max).contains(&value));
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use aoc_2020::{registry, solve, Answer};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_solve_over_str_input() {
+    let input = "1721\n979\n366\n299\n675\n1456\n";
+    assert_eq!(Ok(Answer::Int(514579)), solve(1, 1, input));
+    assert_eq!(Ok(Answer::Int(241861950)), solve(1, 2, input));
+    assert!(solve(1, 1, "1721\nx\n").is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_solve_unknown_day() {
+    let _ = solve(26, 1, "");
+}
+
+#[test]
+fn test_solve_registry() {
+    assert_eq!(25, registry::all().len());
+    let solution = registry::find(21).unwrap();
+    let parsed = solution
+        .parse("mxmxvkd kfcds sqjhc nhms (contains dairy, fish)\ntrh fvjkl sbzzf mxmxvkd (contains dairy)\nsqjhc fvjkl (contains soy)\nsqjhc mxmxvkd sbzzf (contains fish)\n")
+        .unwrap();
+    assert_eq!(Answer::UInt(5), solution.solve(parsed.as_ref(), 1));
+    assert_eq!(
+        Answer::Text("mxmxvkd,sqjhc,fvjkl".to_string()),
+        solution.solve(parsed.as_ref(), 2)
+    );
+}
+
+// the second part of the day 19 runs for minutes without optimizations
+const SLOW: [(u8, u8); 1] = [(19, 2)];
+
+#[test]
+fn test_solve_recorded_answers_of_embedded_inputs() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input");
+    let answers = fs::read_to_string(dir.join("answers.txt")).unwrap();
+    for line in answers.lines().filter(|l| !l.starts_with('#')) {
+        let (key, expected) = line.split_once(' ').unwrap();
+        let (day, part) = key.split_once(':').unwrap();
+        let (day, part) = (day.parse().unwrap(), part.parse().unwrap());
+        if SLOW.contains(&(day, part)) {
+            continue;
+        }
+        let input = fs::read_to_string(dir.join(format!("day{}.txt", day))).unwrap();
+        let answer = solve(day, part, &input).unwrap();
+        assert_eq!(expected, answer.to_string(), "day {} part {}", day, part);
+    }
+}